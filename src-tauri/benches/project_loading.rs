@@ -0,0 +1,154 @@
+//! Benchmark de carga de proyectos con un fixture de 1.500 proyectos.
+//!
+//! Compara la carga anterior (`legacy_load`: una consulta de enlaces por
+//! proyecto e imagen completa en cada fila) con `get_all_projects`. La carga
+//! anterior se reproduce aquí con SQL directo sobre el mismo archivo.
+//!
+//! Ejecutar con: `cargo bench --bench project_loading`
+
+use gestor_proyectos_lib::db::Database;
+use gestor_proyectos_lib::models::project::{CreateLinkDTO, CreateProjectDTO};
+use gestor_proyectos_lib::search;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use image::codecs::jpeg::JpegEncoder;
+use image::{Rgb, RgbImage};
+use rusqlite::{params, Connection};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const PROJECT_COUNT: usize = 1_500;
+const LINKS_PER_PROJECT: usize = 3;
+const IMAGE_SIDE: u32 = 160;
+const ITERATIONS: u32 = 10;
+
+fn fixture_path() -> PathBuf {
+    std::env::temp_dir().join(format!("gestor-proyectos-bench-{}.db", std::process::id()))
+}
+
+/// JPEG de ruido (poco comprimible) para que la imagen pese como una captura real
+fn fixture_image() -> String {
+    let mut seed: u32 = 0x2545_f491;
+    let image = RgbImage::from_fn(IMAGE_SIDE, IMAGE_SIDE, |_, _| {
+        seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        let [r, g, b, _] = seed.to_le_bytes();
+        Rgb([r, g, b])
+    });
+
+    let mut jpeg = Vec::new();
+    image
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut jpeg, 90))
+        .expect("Error generando la imagen del fixture");
+    format!("data:image/jpeg;base64,{}", STANDARD.encode(jpeg))
+}
+
+fn seed_fixture(db: &Database, image_data: &str) {
+    for i in 0..PROJECT_COUNT {
+        let project = db
+            .create_project(CreateProjectDTO {
+                name: format!("Proyecto {}", i),
+                description: format!("Descripción del proyecto de prueba {}", i),
+                local_path: format!("/home/user/work/proyecto-{}", i),
+                documentation_url: Some(format!("https://docs.example.com/{}", i)),
+                ai_documentation_url: None,
+                drive_link: None,
+                notes: Some("Notas de prueba".to_string()),
+                image_data: Some(image_data.to_string()),
+            })
+            .expect("Error creando proyecto del fixture");
+
+        for j in 0..LINKS_PER_PROJECT {
+            db.create_link(CreateLinkDTO {
+                project_id: project.id,
+                link_type: "documentation".to_string(),
+                title: format!("Enlace {}", j),
+                url: format!("https://example.com/{}/{}", i, j),
+            })
+            .expect("Error creando enlace del fixture");
+        }
+    }
+}
+
+/// Id, nombre, imagen completa y número de enlaces
+type LegacyProject = (i64, String, Option<String>, usize);
+
+/// Carga anterior: todas las columnas con la imagen completa y, por cada
+/// proyecto, una consulta de sus enlaces (N+1)
+fn legacy_load(conn: &Connection) -> rusqlite::Result<Vec<LegacyProject>> {
+    let mut projects = conn.prepare_cached(
+        "SELECT id, name, description, local_path, documentation_url, notes,
+                (SELECT image_data FROM project_images WHERE project_id = projects.id),
+                created_at, updated_at, status
+         FROM projects
+         ORDER BY is_pinned DESC, pinned_order ASC, updated_at DESC",
+    )?;
+    let mut links = conn.prepare_cached(
+        "SELECT id, link_type, title, url, created_at FROM project_links
+         WHERE project_id = ?1 ORDER BY created_at DESC",
+    )?;
+
+    let rows = projects
+        .query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(6)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    rows.into_iter()
+        .map(|(id, name, image)| {
+            let count = links
+                .query_map(params![id], |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, String>(2)?, row.get::<_, String>(3)?))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?
+                .len();
+            Ok((id, name, image, count))
+        })
+        .collect()
+}
+
+fn measure<F: FnMut()>(name: &str, mut f: F) -> Duration {
+    f(); // Calentamiento
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let avg = start.elapsed() / ITERATIONS;
+    println!("{:<28} {:>10.2} ms", name, avg.as_secs_f64() * 1000.0);
+    avg
+}
+
+fn main() {
+    let path = fixture_path();
+    let _ = std::fs::remove_file(&path);
+
+    let db = Database::new(path.clone()).expect("Error creando base de datos del fixture");
+    let image_data = fixture_image();
+    println!(
+        "Fixture: {} proyectos, {} enlaces por proyecto, imagen de {} KB",
+        PROJECT_COUNT,
+        LINKS_PER_PROJECT,
+        image_data.len() / 1024
+    );
+    seed_fixture(&db, &image_data);
+
+    let conn = Connection::open(&path).expect("Error abriendo el fixture");
+    let legacy = measure("carga anterior (N+1)", || {
+        std::hint::black_box(legacy_load(&conn).unwrap());
+    });
+    let current = measure("get_all_projects", || {
+        std::hint::black_box(db.get_all_projects().unwrap());
+    });
+    let query = search::parse("proyecto").unwrap();
+    measure("search_projects(\"proyecto\")", || {
+        std::hint::black_box(db.search_projects(&query).unwrap());
+    });
+
+    println!(
+        "Mejora: {:.1}x",
+        legacy.as_secs_f64() / current.as_secs_f64().max(f64::EPSILON)
+    );
+
+    drop(conn);
+    drop(db);
+    let _ = std::fs::remove_file(&path);
+}
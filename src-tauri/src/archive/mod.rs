@@ -0,0 +1,80 @@
+//! Política de archivado de proyectos inactivos.
+//!
+//! Un proyecto 'activo' es inactivo si no se ha abierto en `days_not_opened` días
+//! y tampoco tiene commits en `days_without_commits` días. Los proyectos sin
+//! repositorio git (o con la ruta local inexistente) solo se evalúan por apertura.
+//!
+//! En modo `suggest` solo se listan; en modo `auto` el planificador los archiva
+//! una vez al día. Cada cambio queda registrado como `status_changed` con
+//! `reason: "stale"` en los detalles de la actividad.
+
+use serde_json::json;
+
+use crate::config::ArchivePolicyConfig;
+use crate::db::Database;
+use crate::git;
+use crate::models::project::StaleProject;
+
+/// Proyectos que cumplen la política (independientemente de su modo)
+pub fn find_stale_projects(
+    db: &Database,
+    policy: &ArchivePolicyConfig,
+) -> Result<Vec<StaleProject>, String> {
+    let candidates = db
+        .get_stale_candidates(policy.days_not_opened)
+        .map_err(|e| format!("Error getting stale candidates: {}", e))?;
+
+    Ok(candidates
+        .into_iter()
+        .map(|mut project| {
+            project.days_since_commit = days_since_last_commit(&project.local_path);
+            project
+        })
+        .filter(|project| {
+            project
+                .days_since_commit
+                .is_none_or(|days| days >= i64::from(policy.days_without_commits))
+        })
+        .collect())
+}
+
+/// Archiva los proyectos inactivos (solo los de `only` si se indica) y devuelve los archivados.
+/// Se vuelven a evaluar para no archivar un proyecto que dejó de cumplir la política.
+pub fn archive_stale_projects(
+    db: &Database,
+    policy: &ArchivePolicyConfig,
+    only: Option<&[i64]>,
+) -> Result<Vec<StaleProject>, String> {
+    let mut archived = Vec::new();
+
+    for project in find_stale_projects(db, policy)? {
+        if only.is_some_and(|ids| !ids.contains(&project.project_id)) {
+            continue;
+        }
+
+        let details = json!({
+            "reason": "stale",
+            "days_since_open": project.days_since_open,
+            "days_since_commit": project.days_since_commit,
+        });
+        let changed = db
+            .archive_stale_project(project.project_id, details)
+            .map_err(|e| format!("Error archiving project: {}", e))?;
+
+        if changed {
+            archived.push(project);
+        }
+    }
+
+    Ok(archived)
+}
+
+/// Días desde el último commit de HEAD (`None` si no es un repositorio o no hay commits)
+fn days_since_last_commit(path: &str) -> Option<i64> {
+    if path.trim().is_empty() {
+        return None;
+    }
+
+    let timestamp = git::backend().last_commit_time(path).ok()??;
+    Some((chrono::Utc::now().timestamp() - timestamp).max(0) / 86_400)
+}
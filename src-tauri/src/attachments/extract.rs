@@ -0,0 +1,94 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Máximo de caracteres indexados por adjunto
+const MAX_TEXT_CHARS: usize = 1_000_000;
+
+/// Resultado de extraer el texto de un adjunto
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextExtraction {
+    Text(String),
+    /// El formato no tiene texto indexable (imágenes, binarios, PDFs escaneados...)
+    Unsupported(String),
+    Failed(String),
+}
+
+/// Extrae el texto de un adjunto según su tipo MIME
+pub fn extract_text(bytes: &[u8], mime_type: &str) -> TextExtraction {
+    let result = if is_text_mime(mime_type) {
+        TextExtraction::Text(String::from_utf8_lossy(bytes).into_owned())
+    } else if mime_type == "application/pdf" {
+        extract_pdf_text(bytes)
+    } else {
+        TextExtraction::Unsupported(format!("Tipo de archivo sin texto indexable: {}", mime_type))
+    };
+
+    match result {
+        TextExtraction::Text(text) => TextExtraction::Text(truncate_chars(text.trim(), MAX_TEXT_CHARS)),
+        other => other,
+    }
+}
+
+fn is_text_mime(mime_type: &str) -> bool {
+    mime_type.starts_with("text/")
+        || matches!(
+            mime_type,
+            "application/json" | "application/xml" | "application/yaml" | "application/toml"
+        )
+}
+
+/// Capa de texto del PDF con `pdftotext` (poppler-utils). El PDF se pasa por la
+/// entrada estándar: sin archivos temporales que otro proceso pueda suplantar.
+fn extract_pdf_text(bytes: &[u8]) -> TextExtraction {
+    let child = Command::new("pdftotext")
+        .args(["-enc", "UTF-8", "-q", "-", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return TextExtraction::Failed(
+                "pdftotext no está instalado (paquete poppler-utils)".to_string(),
+            )
+        }
+        Err(e) => return TextExtraction::Failed(format!("Error ejecutando pdftotext: {}", e)),
+    };
+
+    // Escribir desde otro hilo: si pdftotext llena la salida antes de leerlo
+    // todo, ambos procesos se quedarían esperando
+    let output = std::thread::scope(|scope| {
+        let mut stdin = child.stdin.take();
+        scope.spawn(move || {
+            if let Some(stdin) = stdin.as_mut() {
+                // Si pdftotext termina antes (PDF inválido), el error sale en su stderr
+                let _ = stdin.write_all(bytes);
+            }
+        });
+        child.wait_with_output()
+    });
+
+    match output {
+        Ok(output) if output.status.success() => {
+            let text = String::from_utf8_lossy(&output.stdout);
+            if text.trim().is_empty() {
+                TextExtraction::Unsupported("El PDF no tiene capa de texto".to_string())
+            } else {
+                TextExtraction::Text(text.into_owned())
+            }
+        }
+        Ok(output) => TextExtraction::Failed(format!(
+            "pdftotext terminó con error: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+        Err(e) => TextExtraction::Failed(format!("Error ejecutando pdftotext: {}", e)),
+    }
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((index, _)) => text[..index].to_string(),
+        None => text.to_string(),
+    }
+}
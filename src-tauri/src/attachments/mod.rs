@@ -0,0 +1,382 @@
+//! Lectura de archivos adjuntos.
+//!
+//! Los adjuntos pueden llegar como base64 desde el frontend (arrastrar y soltar)
+//! o como rutas del sistema de archivos, que el backend lee directamente sin
+//! pasar el contenido por IPC. En ambos casos el tipo MIME se detecta a partir
+//! del contenido (números mágicos) y no del cliente, y se calcula el hash
+//! SHA-256 para no duplicar archivos dentro de un proyecto.
+//!
+//! Las carpetas se recorren recursivamente, omitiendo entradas ocultas y
+//! enlaces simbólicos.
+//!
+//! Un archivo con el mismo nombre que un documento ya adjunto se guarda como
+//! nueva versión de ese documento; cualquier versión se puede restaurar o
+//! exportar a la carpeta local del proyecto.
+//!
+//! El texto de los adjuntos (texto plano, Markdown y la capa de texto de los
+//! PDF) se extrae en segundo plano y se indexa para la búsqueda; cada adjunto
+//! guarda el estado de su extracción.
+
+mod extract;
+
+pub use extract::{extract_text, TextExtraction};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use tauri::{AppHandle, Manager};
+
+use crate::db::Database;
+use crate::models::project::{AttachmentImportResult, ProjectAttachment, SkippedAttachment};
+
+/// Tamaño máximo de un adjunto
+pub const MAX_ATTACHMENT_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Máximo de archivos que se añaden de cada carpeta elegida (con sus subcarpetas)
+const MAX_FOLDER_FILES: usize = 200;
+
+/// Adjunto ya leído y validado, listo para guardarse
+#[derive(Debug, Clone)]
+pub struct FileAttachment {
+    pub filename: String,
+    pub bytes: Vec<u8>,
+    pub mime_type: String,
+    pub content_hash: String,
+}
+
+impl FileAttachment {
+    pub fn from_bytes(filename: &str, bytes: Vec<u8>) -> Result<Self, String> {
+        let filename = filename.trim();
+        if filename.is_empty() {
+            return Err("El nombre del archivo no puede estar vacío".to_string());
+        }
+        if bytes.len() as u64 > MAX_ATTACHMENT_SIZE {
+            return Err(format!(
+                "El archivo supera el límite de {} MB",
+                MAX_ATTACHMENT_SIZE / (1024 * 1024)
+            ));
+        }
+
+        Ok(FileAttachment {
+            filename: filename.to_string(),
+            mime_type: sniff_mime(&bytes, filename),
+            content_hash: format!("{:x}", Sha256::digest(&bytes)),
+            bytes,
+        })
+    }
+
+    /// Adjunto enviado por el frontend en base64 (sin la cabecera `data:`)
+    pub fn from_base64(filename: &str, data: &str) -> Result<Self, String> {
+        let bytes = STANDARD
+            .decode(data.trim())
+            .map_err(|_| "El archivo no está codificado en base64 válido".to_string())?;
+        Self::from_bytes(filename, bytes)
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        let metadata =
+            fs::metadata(path).map_err(|e| format!("No se pudo leer '{}': {}", path.display(), e))?;
+        if !metadata.is_file() {
+            return Err(format!("'{}' no es un archivo", path.display()));
+        }
+        // Comprobar antes de leer para no cargar en memoria archivos enormes
+        if metadata.len() > MAX_ATTACHMENT_SIZE {
+            return Err(format!(
+                "El archivo supera el límite de {} MB",
+                MAX_ATTACHMENT_SIZE / (1024 * 1024)
+            ));
+        }
+
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .ok_or_else(|| format!("Ruta sin nombre de archivo: '{}'", path.display()))?;
+        let bytes =
+            fs::read(path).map_err(|e| format!("No se pudo leer '{}': {}", path.display(), e))?;
+
+        Self::from_bytes(&filename, bytes)
+    }
+
+    pub fn file_size(&self) -> i64 {
+        self.bytes.len() as i64
+    }
+
+    pub fn base64(&self) -> String {
+        STANDARD.encode(&self.bytes)
+    }
+}
+
+/// Adjunta un único archivo. Si el proyecto ya tiene uno con el mismo contenido, devuelve ese.
+pub fn add_from_path(db: &Database, project_id: i64, path: &Path) -> Result<ProjectAttachment, String> {
+    let file = FileAttachment::from_path(path)?;
+    db.add_file_attachment(project_id, &file)
+        .map(|(attachment, _)| attachment)
+        .map_err(|e| format!("Error guardando adjunto: {}", e))
+}
+
+/// Sube un archivo como nueva versión del documento al que pertenece `attachment_id`
+pub fn add_version_from_path(
+    db: &Database,
+    attachment_id: i64,
+    path: &Path,
+) -> Result<ProjectAttachment, String> {
+    let file = FileAttachment::from_path(path)?;
+    db.add_attachment_version(attachment_id, &file)
+        .map(|(attachment, _)| attachment)
+        .map_err(|e| format!("Error guardando versión: {}", e))
+}
+
+/// Ruta de destino al exportar un adjunto a la carpeta del proyecto.
+/// `relative_path` debe quedar dentro de `local_path`: no se admiten rutas
+/// absolutas ni componentes `..`.
+pub fn export_path(local_path: &str, relative_path: &str) -> Result<PathBuf, String> {
+    let base = Path::new(local_path.trim());
+    if local_path.trim().is_empty() || !base.is_dir() {
+        return Err(format!("La carpeta del proyecto no existe: '{}'", local_path));
+    }
+
+    let relative = Path::new(relative_path.trim());
+    let mut target = base.to_path_buf();
+    for component in relative.components() {
+        match component {
+            Component::Normal(part) => target.push(part),
+            Component::CurDir => {}
+            _ => {
+                return Err(format!(
+                    "La ruta '{}' debe ser relativa a la carpeta del proyecto",
+                    relative_path
+                ))
+            }
+        }
+    }
+
+    if target == base {
+        return Err("La ruta de destino no incluye un nombre de archivo".to_string());
+    }
+    Ok(target)
+}
+
+/// Crea la carpeta de `target` (ruta de `export_path`) y comprueba que, con los
+/// enlaces simbólicos resueltos, sigue dentro de `local_path`: una subcarpeta
+/// enlazada a otro sitio pasa la comprobación por componentes de `export_path`.
+/// Tampoco se escribe a través de un enlace simbólico en el propio archivo.
+pub fn prepare_export_dir(local_path: &str, target: &Path) -> Result<(), String> {
+    let base = Path::new(local_path.trim())
+        .canonicalize()
+        .map_err(|e| format!("Error resolviendo la carpeta del proyecto: {}", e))?;
+    let parent = target
+        .parent()
+        .ok_or_else(|| "La ruta de destino no incluye un nombre de archivo".to_string())?;
+
+    std::fs::create_dir_all(parent).map_err(|e| format!("Error creando directorio: {}", e))?;
+    let parent = parent
+        .canonicalize()
+        .map_err(|e| format!("Error resolviendo el directorio de destino: {}", e))?;
+    if !parent.starts_with(&base) {
+        return Err(format!(
+            "La ruta '{}' sale de la carpeta del proyecto",
+            target.display()
+        ));
+    }
+
+    let is_symlink = std::fs::symlink_metadata(target)
+        .map(|metadata| metadata.file_type().is_symlink())
+        .unwrap_or(false);
+    if is_symlink {
+        return Err(format!(
+            "La ruta '{}' es un enlace simbólico: no se sobrescribe",
+            target.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Adjunta varios archivos y/o carpetas completas. Los archivos que no se pueden
+/// leer, superan el límite o ya estaban adjuntos se informan en `skipped`.
+pub fn import_paths(
+    db: &Database,
+    project_id: i64,
+    paths: &[String],
+) -> Result<AttachmentImportResult, String> {
+    let mut result = AttachmentImportResult::default();
+    let mut files = Vec::new();
+
+    for path in paths {
+        let path = PathBuf::from(path.trim());
+        if path.is_dir() {
+            // El límite se cuenta por carpeta elegida, no sobre el total
+            let mut folder_files = Vec::new();
+            collect_folder(&path, &mut folder_files, &mut result.skipped);
+            files.extend(folder_files);
+        } else {
+            files.push(path);
+        }
+    }
+
+    for path in files {
+        let file = match FileAttachment::from_path(&path) {
+            Ok(file) => file,
+            Err(reason) => {
+                result.skipped.push(skipped(&path, reason));
+                continue;
+            }
+        };
+
+        let (attachment, inserted) = db
+            .add_file_attachment(project_id, &file)
+            .map_err(|e| format!("Error guardando adjunto: {}", e))?;
+
+        if inserted {
+            result.added.push(attachment);
+        } else {
+            result.skipped.push(skipped(
+                &path,
+                format!("Ya estaba adjunto como '{}'", attachment.filename),
+            ));
+        }
+    }
+
+    Ok(result)
+}
+
+/// Evita lanzar varios hilos de extracción a la vez
+static EXTRACTION_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Lanza en segundo plano la extracción de texto de los adjuntos pendientes.
+/// Si ya hay una extracción en curso, esta recogerá también los nuevos.
+pub fn start_text_extraction(app: AppHandle) {
+    if EXTRACTION_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let spawned = thread::Builder::new()
+        .name("attachment-text".to_string())
+        .spawn(move || {
+            loop {
+                let processed = extract_pending_texts(&app.state::<Database>());
+                EXTRACTION_RUNNING.store(false, Ordering::SeqCst);
+
+                // Un adjunto añadido justo al terminar no habría lanzado otro hilo
+                if processed == 0 || EXTRACTION_RUNNING.swap(true, Ordering::SeqCst) {
+                    break;
+                }
+            }
+        });
+
+    if let Err(e) = spawned {
+        EXTRACTION_RUNNING.store(false, Ordering::SeqCst);
+        println!("📎 [ATTACHMENT] No se pudo iniciar la extracción de texto: {}", e);
+    }
+}
+
+/// Extrae e indexa el texto de todos los adjuntos pendientes. Devuelve cuántos procesó.
+pub fn extract_pending_texts(db: &Database) -> usize {
+    let mut processed = 0;
+
+    loop {
+        let (id, content) = match db.claim_pending_attachment_text() {
+            Ok(Some(pending)) => pending,
+            Ok(None) => break,
+            Err(e) => {
+                println!("📎 [ATTACHMENT] Error leyendo adjuntos pendientes: {}", e);
+                break;
+            }
+        };
+
+        let extraction = match STANDARD.decode(content.file_data.trim()) {
+            Ok(bytes) => extract_text(&bytes, &content.mime_type),
+            Err(_) => TextExtraction::Failed("El adjunto no está codificado en base64 válido".to_string()),
+        };
+        if let TextExtraction::Failed(reason) = &extraction {
+            println!("📎 [ATTACHMENT] Sin texto para '{}': {}", content.filename, reason);
+        }
+
+        if let Err(e) = db.save_attachment_text(id, &extraction) {
+            println!("📎 [ATTACHMENT] Error guardando texto de '{}': {}", content.filename, e);
+            break;
+        }
+        processed += 1;
+    }
+
+    processed
+}
+
+/// Archivos de una carpeta (recursivo, en orden alfabético); `files` empieza vacío
+/// y no pasa de `MAX_FOLDER_FILES`
+fn collect_folder(dir: &Path, files: &mut Vec<PathBuf>, skipped_files: &mut Vec<SkippedAttachment>) {
+    let mut entries: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect(),
+        Err(e) => {
+            skipped_files.push(skipped(dir, format!("No se pudo leer la carpeta: {}", e)));
+            return;
+        }
+    };
+    entries.sort();
+
+    for path in entries {
+        let hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        let Ok(metadata) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if hidden || metadata.file_type().is_symlink() {
+            continue;
+        }
+
+        if metadata.is_dir() {
+            collect_folder(&path, files, skipped_files);
+        } else if files.len() >= MAX_FOLDER_FILES {
+            skipped_files.push(skipped(
+                &path,
+                format!("Se alcanzó el máximo de {} archivos por carpeta", MAX_FOLDER_FILES),
+            ));
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+fn skipped(path: &Path, reason: String) -> SkippedAttachment {
+    SkippedAttachment {
+        path: path.display().to_string(),
+        reason,
+    }
+}
+
+/// Tipo MIME según el contenido. Los formatos de texto no tienen números mágicos:
+/// si el contenido es UTF-8 se usa la extensión para afinar el tipo.
+fn sniff_mime(bytes: &[u8], filename: &str) -> String {
+    if let Some(kind) = infer::get(bytes) {
+        return kind.mime_type().to_string();
+    }
+
+    let is_text = !bytes.contains(&0) && std::str::from_utf8(bytes).is_ok();
+    if !is_text {
+        return "application/octet-stream".to_string();
+    }
+
+    let extension = Path::new(filename)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "md" | "markdown" => "text/markdown",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "js" | "mjs" => "text/javascript",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "svg" => "image/svg+xml",
+        "yaml" | "yml" => "application/yaml",
+        "toml" => "application/toml",
+        _ => "text/plain",
+    }
+    .to_string()
+}
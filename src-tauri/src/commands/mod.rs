@@ -1,11 +1,44 @@
+use crate::archive;
+use crate::attachments;
 use crate::config::{AppConfig, ConfigManager, DetectedPrograms};
 use crate::db::Database;
-use crate::models::project::{CreateProjectDTO, CreateLinkDTO, Project, ProjectLink, UpdateProjectDTO, UpdateLinkDTO};
+use crate::git::{
+    self, BranchSwitch, DiscardPreview, FileDiff, GitBranch, GitCommit, GitFileCount, GitStash,
+    GitStatus, HunkSelection,
+};
+use crate::health::{self, GitHealth};
+use crate::links;
+use crate::models::project::{CreateProjectDTO, CreateLinkDTO, Project, ProjectLink, UpdateProjectDTO, UpdateLinkDTO, SmartView, SmartViewFilter, SmartViewSortField, SortDirection, CreateSmartViewDTO, UpdateSmartViewDTO, CreateRelationDTO, ProjectRelation, RelatedProject, ProjectGraph, ProjectMilestone, CreateMilestoneDTO, UpdateMilestoneDTO, UpcomingMilestone, Reminder, CreateReminderDTO, BoardColumn, CreateBoardColumnDTO, UpdateBoardColumnDTO, ProjectBoard, ProjectTodo, ActivityType, ActivityFilter, ProjectActivity, ActivityPruneResult, DailyActivitySummary, ProjectHealth, StaleProject, BrokenLink, LinkCheckSummary, BookmarkFolder, BookmarkMapping, BookmarkImportSummary};
 use crate::platform::{get_platform, ProgramDetector};
+use crate::search::{self, SearchError, SearchQuery};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::io::Write;
 use std::process::Command;
 use std::path::PathBuf;
-use tauri::State;
+use tauri::{Manager, State};
 use chrono::Local;
+use serde_json::json;
+
+/// Registra actividad de operaciones externas (git, rsync) en el proyecto que contiene `path`.
+/// Un fallo al registrar no debe hacer fallar la operación.
+fn log_path_activity(
+    db: &Database,
+    path: &str,
+    activity_type: ActivityType,
+    description: &str,
+    details: serde_json::Value,
+) {
+    match db.find_project_id_by_path(path) {
+        Ok(Some(project_id)) => {
+            if let Err(e) = db.log_activity(project_id, activity_type, description, details) {
+                println!("⚠️ [ACTIVITY] Error registrando actividad: {}", e);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => println!("⚠️ [ACTIVITY] Error buscando proyecto para {}: {}", path, e),
+    }
+}
 
 #[tauri::command]
 pub async fn create_project(
@@ -56,15 +89,43 @@ pub async fn update_project(
 }
 
 #[tauri::command]
-pub async fn delete_project(db: State<'_, Database>, id: i64) -> Result<(), String> {
+pub async fn delete_project(db: State<'_, Database>, id: i64, force: bool) -> Result<(), String> {
+    // Sin `force` no se elimina un proyecto del que dependen otros
+    let dependents = db
+        .get_project_dependents(id)
+        .map_err(|e| format!("Error getting dependents: {}", e))?;
+    if !dependents.is_empty() {
+        let names: Vec<&str> = dependents.iter().map(|d| d.project_name.as_str()).collect();
+        if !force {
+            return Err(format!(
+                "Estos proyectos dependen de él: {}. Confirma para eliminarlo igualmente",
+                names.join(", ")
+            ));
+        }
+        println!("⚠️ [RELATIONS] Eliminando proyecto {} del que dependen: {}", id, names.join(", "));
+    }
     db.delete_project(id)
         .map_err(|e| format!("Error deleting project: {}", e))
 }
 
+/// Buscar proyectos con texto libre o con el lenguaje de consulta (ver `crate::search`)
 #[tauri::command]
-pub async fn search_projects(db: State<'_, Database>, query: String) -> Result<Vec<Project>, String> {
-    db.search_projects(&query)
-        .map_err(|e| format!("Error searching projects: {}", e))
+pub async fn search_projects(db: State<'_, Database>, query: String) -> Result<Vec<Project>, SearchError> {
+    let parsed = search::parse(&query)?;
+    db.search_projects(&parsed)
+        .map_err(|e| SearchError::from(format!("Error searching projects: {}", e)))
+}
+
+/// Validar una consulta sin ejecutarla (para resaltar errores mientras se escribe)
+#[tauri::command]
+pub async fn parse_search_query(query: String) -> Result<SearchQuery, SearchError> {
+    Ok(search::parse(&query)?)
+}
+
+#[tauri::command]
+pub async fn get_project_image(db: State<'_, Database>, id: i64) -> Result<Option<String>, String> {
+    db.get_project_image(id)
+        .map_err(|e| format!("Error getting project image: {}", e))
 }
 
 #[tauri::command]
@@ -190,6 +251,15 @@ pub async fn create_project_backup(
     println!("📊 [BACKUP] Tamaño del contenido: {} bytes", markdown_content.len());
     println!("✅ [BACKUP] Datos de backup generados exitosamente");
 
+    if let Err(e) = db.log_activity(
+        project_id,
+        ActivityType::BackupCreated,
+        "Backup generado",
+        json!({ "filename": filename, "path": result_path }),
+    ) {
+        println!("⚠️ [ACTIVITY] Error registrando actividad: {}", e);
+    }
+
     Ok(BackupData {
         content: markdown_content,
         path: result_path,
@@ -197,6 +267,35 @@ pub async fn create_project_backup(
     })
 }
 
+/// Escribe un archivo creando los directorios padre si no existen. Sin
+/// `overwrite` falla si el archivo ya existe, comprobándolo al crearlo (no
+/// antes), así no se pisa uno creado entre medias.
+fn write_file_creating_dirs(
+    path: &std::path::Path,
+    contents: &[u8],
+    overwrite: bool,
+) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Error creando directorio: {}", e))?;
+    }
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true);
+    if overwrite {
+        options.create(true).truncate(true);
+    } else {
+        options.create_new(true);
+    }
+
+    let mut file = options.open(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::AlreadyExists => format!("Ya existe un archivo en: {}", path.display()),
+        _ => format!("Error escribiendo archivo: {}", e),
+    })?;
+    file.write_all(contents)
+        .map_err(|e| format!("Error escribiendo archivo: {}", e))
+}
+
 #[tauri::command]
 pub async fn write_file_to_path(
     file_path: String,
@@ -204,18 +303,7 @@ pub async fn write_file_to_path(
 ) -> Result<String, String> {
     println!("📝 [WRITE] Escribiendo archivo: {}", file_path);
     
-    use std::fs::write;
-    use std::path::Path;
-    
-    // Crear directorio padre si no existe
-    if let Some(parent) = Path::new(&file_path).parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Error creando directorio: {}", e))?;
-    }
-    
-    // Escribir archivo
-    write(&file_path, content)
-        .map_err(|e| format!("Error escribiendo archivo: {}", e))?;
+    write_file_creating_dirs(std::path::Path::new(&file_path), content.as_bytes(), true)?;
     
     println!("✅ [WRITE] Archivo escrito exitosamente: {}", file_path);
     
@@ -224,6 +312,7 @@ pub async fn write_file_to_path(
 
 #[tauri::command]
 pub async fn sync_project_to_backup(
+    db: State<'_, Database>,
     source_path: String,
     project_name: String,
 ) -> Result<String, String> {
@@ -324,12 +413,21 @@ coverage/
     let stdout = String::from_utf8_lossy(&output.stdout);
     println!("✅ [RSYNC] Sincronización completada exitosamente");
     println!("📊 [RSYNC] Output: {}", stdout);
+
+    log_path_activity(
+        &db,
+        &source_path,
+        ActivityType::BackupSynced,
+        "Proyecto sincronizado al backup",
+        json!({ "destination": backup_path }),
+    );
     
     Ok(format!("Proyecto sincronizado: {} -> {}", source_path, backup_path))
 }
 
 #[tauri::command]
 pub async fn sync_project(
+    db: State<'_, Database>,
     source_path: String,
     destination_path: String,
 ) -> Result<String, String> {
@@ -363,6 +461,14 @@ pub async fn sync_project(
     let stdout = String::from_utf8_lossy(&output.stdout);
     println!("rsync output:\n{}", stdout);
 
+    log_path_activity(
+        &db,
+        &source_path,
+        ActivityType::BackupSynced,
+        "Proyecto sincronizado",
+        json!({ "destination": destination_path }),
+    );
+
     Ok(format!(
         "Sincronización completada exitosamente a: {}",
         destination_path
@@ -372,12 +478,24 @@ pub async fn sync_project(
 // Comandos para manejar enlaces de proyectos
 #[tauri::command]
 pub async fn create_project_link(
-    db: State<'_, Database>,
+    app: tauri::AppHandle,
     link: CreateLinkDTO,
+    fetch_metadata: Option<bool>,
 ) -> Result<ProjectLink, String> {
     println!("🔗 [LINK] Creando enlace: {} - {}", link.title, link.url);
-    db.create_link(link)
-        .map_err(|e| format!("Error creating link: {}", e))
+    // Sin `fetch_metadata` solo se usa lo guardado: la app funciona sin conexión.
+    // Completar título, tipo y favicon puede descargar la página (cliente bloqueante)
+    let handle = app.clone();
+    let link = tauri::async_runtime::spawn_blocking(move || {
+        let db = handle.state::<Database>();
+        let link = links::prepare_link(&db, link, fetch_metadata.unwrap_or(false));
+        db.create_link(link)
+    })
+    .await
+    .map_err(|e| format!("Error creating link: {}", e))?
+    .map_err(|e| format!("Error creating link: {}", e))?;
+    links::start_link_check(app);
+    Ok(link)
 }
 
 #[tauri::command]
@@ -392,13 +510,17 @@ pub async fn get_project_links(
 
 #[tauri::command]
 pub async fn update_project_link(
+    app: tauri::AppHandle,
     db: State<'_, Database>,
     id: i64,
     link: UpdateLinkDTO,
 ) -> Result<ProjectLink, String> {
     println!("🔗 [LINK] Actualizando enlace ID: {}", id);
-    db.update_link(id, link)
-        .map_err(|e| format!("Error updating link: {}", e))
+    let link = db
+        .update_link(id, link)
+        .map_err(|e| format!("Error updating link: {}", e))?;
+    links::start_link_check(app);
+    Ok(link)
 }
 
 #[tauri::command]
@@ -411,6 +533,68 @@ pub async fn delete_project_link(
         .map_err(|e| format!("Error deleting link: {}", e))
 }
 
+/// Comprueba ahora todos los enlaces de un proyecto (o de todos si no se indica)
+#[tauri::command]
+pub async fn check_links(
+    app: tauri::AppHandle,
+    project_id: Option<i64>,
+) -> Result<LinkCheckSummary, String> {
+    match project_id {
+        Some(id) => println!("🔗 [LINK] Comprobando enlaces del proyecto ID: {}", id),
+        None => println!("🔗 [LINK] Comprobando enlaces de todos los proyectos"),
+    }
+    // El cliente HTTP es bloqueante: no puede ejecutarse en el runtime async
+    tauri::async_runtime::spawn_blocking(move || {
+        links::check_links(&app.state::<Database>(), project_id, 0)
+    })
+    .await
+    .map_err(|e| format!("Error comprobando enlaces: {}", e))?
+}
+
+/// Enlaces rotos de todos los proyectos según la última comprobación
+#[tauri::command]
+pub async fn get_broken_links(
+    db: State<'_, Database>,
+) -> Result<Vec<BrokenLink>, String> {
+    db.get_broken_links()
+        .map_err(|e| format!("Error getting broken links: {}", e))
+}
+
+fn read_bookmarks_file(path: &str) -> Result<Vec<BookmarkFolder>, String> {
+    let bytes = std::fs::read(path)
+        .map_err(|e| format!("Error leyendo archivo de marcadores: {}", e))?;
+    links::parse_bookmarks(&String::from_utf8_lossy(&bytes))
+}
+
+/// Carpetas de un archivo de marcadores, para elegir cuáles importar
+#[tauri::command]
+pub async fn preview_bookmarks(path: String) -> Result<Vec<BookmarkFolder>, String> {
+    println!("🔖 [BOOKMARKS] Leyendo marcadores: {}", path);
+    read_bookmarks_file(&path)
+}
+
+/// Importa las carpetas elegidas de un archivo de marcadores como enlaces de proyectos
+#[tauri::command]
+pub async fn import_bookmarks(
+    app: tauri::AppHandle,
+    db: State<'_, Database>,
+    path: String,
+    mappings: Vec<BookmarkMapping>,
+) -> Result<BookmarkImportSummary, String> {
+    println!("🔖 [BOOKMARKS] Importando {} carpetas de: {}", mappings.len(), path);
+    let folders = read_bookmarks_file(&path)?;
+    let summary = links::import_bookmarks(&db, &folders, &mappings)?;
+    println!(
+        "🔖 [BOOKMARKS] {} importados, {} duplicados, {} omitidos",
+        summary.imported, summary.duplicates, summary.skipped
+    );
+
+    if summary.imported > 0 {
+        links::start_link_check(app);
+    }
+    Ok(summary)
+}
+
 // Comandos para Analytics y Tracking
 #[tauri::command]
 pub async fn track_project_open(
@@ -453,16 +637,208 @@ pub async fn get_project_activities(
         .map_err(|e| format!("Error getting project activities: {}", e))
 }
 
+/// Historial de actividad filtrable por proyecto, tipos y rango de fechas
+#[tauri::command]
+pub async fn get_activities(
+    db: State<'_, Database>,
+    filter: ActivityFilter,
+) -> Result<Vec<ProjectActivity>, String> {
+    println!("📋 [ANALYTICS] Consultando actividad: {:?}", filter);
+    db.get_activities(&filter)
+        .map_err(|e| format!("Error getting activities: {}", e))
+}
+
+/// Actividad agregada por día (incluye la ya resumida por la política de retención)
+#[tauri::command]
+pub async fn get_daily_activity(
+    db: State<'_, Database>,
+    project_id: Option<i64>,
+    since: Option<String>,
+) -> Result<Vec<DailyActivitySummary>, String> {
+    println!("📈 [ANALYTICS] Obteniendo actividad diaria");
+    db.get_daily_activity(project_id, since)
+        .map_err(|e| format!("Error getting daily activity: {}", e))
+}
+
+/// Aplica ahora la política de retención configurada
+#[tauri::command]
+pub async fn prune_activities(
+    db: State<'_, Database>,
+    config_manager: State<'_, ConfigManager>,
+) -> Result<ActivityPruneResult, String> {
+    let retention_days = config_manager.get_config()?.advanced.analytics_retention_days;
+    println!("🧹 [ANALYTICS] Aplicando retención de {} días", retention_days);
+    db.prune_activities(retention_days)
+        .map_err(|e| format!("Error pruning activities: {}", e))
+}
+
+/// Borra toda la analítica registrada (privacidad)
+#[tauri::command]
+pub async fn purge_analytics(db: State<'_, Database>) -> Result<i64, String> {
+    println!("🧹 [ANALYTICS] Borrando toda la analítica");
+    db.purge_analytics()
+        .map_err(|e| format!("Error purging analytics: {}", e))
+}
+
 // ==================== COMANDOS PARA ARCHIVOS ADJUNTOS ====================
+// Tras añadir adjuntos se lanza la extracción de su texto en segundo plano
 
 #[tauri::command]
 pub async fn add_attachment(
+    app: tauri::AppHandle,
     db: State<'_, Database>,
     attachment: crate::models::project::CreateAttachmentDTO,
 ) -> Result<crate::models::project::ProjectAttachment, String> {
     println!("📎 [ATTACHMENT] Agregando archivo: {} ({} bytes)", attachment.filename, attachment.file_size);
-    db.add_attachment(attachment)
-        .map_err(|e| format!("Error adding attachment: {}", e))
+    let attachment = db
+        .add_attachment(attachment)
+        .map_err(|e| format!("Error adding attachment: {}", e))?;
+    attachments::start_text_extraction(app);
+    Ok(attachment)
+}
+
+#[tauri::command]
+pub async fn add_attachment_from_path(
+    app: tauri::AppHandle,
+    db: State<'_, Database>,
+    project_id: i64,
+    path: String,
+) -> Result<crate::models::project::ProjectAttachment, String> {
+    println!("📎 [ATTACHMENT] Agregando archivo desde ruta: {}", path);
+    let attachment = attachments::add_from_path(&db, project_id, &PathBuf::from(&path))?;
+    attachments::start_text_extraction(app);
+    Ok(attachment)
+}
+
+#[tauri::command]
+pub async fn add_attachments_from_paths(
+    app: tauri::AppHandle,
+    db: State<'_, Database>,
+    project_id: i64,
+    paths: Vec<String>,
+) -> Result<crate::models::project::AttachmentImportResult, String> {
+    println!("📎 [ATTACHMENT] Agregando {} rutas al proyecto ID: {}", paths.len(), project_id);
+    let result = attachments::import_paths(&db, project_id, &paths)?;
+    println!(
+        "✅ [ATTACHMENT] {} archivos agregados, {} omitidos",
+        result.added.len(),
+        result.skipped.len()
+    );
+    attachments::start_text_extraction(app);
+    Ok(result)
+}
+
+/// Busca en el texto extraído de los adjuntos (misma sintaxis que `search_projects`)
+#[tauri::command]
+pub async fn search_attachments(
+    db: State<'_, Database>,
+    query: String,
+    project_id: Option<i64>,
+    limit: Option<i64>,
+) -> Result<Vec<crate::models::project::AttachmentSearchHit>, SearchError> {
+    let parsed = search::parse(&query)?;
+    db.search_attachments(&parsed, project_id, limit)
+        .map_err(|e| SearchError::from(format!("Error searching attachments: {}", e)))
+}
+
+/// Vuelve a extraer el texto de un adjunto (ej: tras instalar `pdftotext`)
+#[tauri::command]
+pub async fn reindex_attachment(
+    app: tauri::AppHandle,
+    db: State<'_, Database>,
+    id: i64,
+) -> Result<crate::models::project::ProjectAttachment, String> {
+    println!("📎 [ATTACHMENT] Reindexando archivo ID: {}", id);
+    let attachment = db
+        .reindex_attachment(id)
+        .map_err(|e| format!("Error reindexing attachment: {}", e))?;
+    attachments::start_text_extraction(app);
+    Ok(attachment)
+}
+
+/// Sube un archivo como nueva versión de un adjunto existente
+#[tauri::command]
+pub async fn add_attachment_version(
+    app: tauri::AppHandle,
+    db: State<'_, Database>,
+    attachment_id: i64,
+    path: String,
+) -> Result<crate::models::project::ProjectAttachment, String> {
+    println!("📎 [ATTACHMENT] Nueva versión del archivo ID {}: {}", attachment_id, path);
+    let attachment = attachments::add_version_from_path(&db, attachment_id, &PathBuf::from(&path))?;
+    attachments::start_text_extraction(app);
+    Ok(attachment)
+}
+
+#[tauri::command]
+pub async fn get_attachment_versions(
+    db: State<'_, Database>,
+    attachment_id: i64,
+) -> Result<Vec<crate::models::project::ProjectAttachment>, String> {
+    db.get_attachment_versions(attachment_id)
+        .map_err(|e| format!("Error getting attachment versions: {}", e))
+}
+
+/// Restaura una versión anterior como nueva versión del documento
+#[tauri::command]
+pub async fn rollback_attachment(
+    app: tauri::AppHandle,
+    db: State<'_, Database>,
+    version_id: i64,
+) -> Result<crate::models::project::ProjectAttachment, String> {
+    println!("⏪ [ATTACHMENT] Restaurando versión ID: {}", version_id);
+    let attachment = db
+        .rollback_attachment(version_id)
+        .map_err(|e| format!("Error restoring attachment version: {}", e))?;
+    attachments::start_text_extraction(app);
+    Ok(attachment)
+}
+
+/// Escribe una versión de un adjunto en la carpeta local del proyecto.
+/// `relative_path` por defecto es el nombre del archivo; si el destino ya existe
+/// solo se sobrescribe con `overwrite`.
+#[tauri::command]
+pub async fn export_attachment(
+    db: State<'_, Database>,
+    id: i64,
+    relative_path: Option<String>,
+    overwrite: Option<bool>,
+) -> Result<String, String> {
+    let content = db
+        .get_attachment_content(id)
+        .map_err(|e| format!("Error leyendo adjunto: {}", e))?
+        .ok_or_else(|| "Adjunto no encontrado".to_string())?;
+    let project = db
+        .get_project(content.project_id)
+        .map_err(|e| format!("Error obteniendo proyecto: {}", e))?;
+
+    let relative_path = relative_path
+        .filter(|path| !path.trim().is_empty())
+        .unwrap_or_else(|| content.filename.clone());
+    let target = attachments::export_path(&project.local_path, &relative_path)?;
+    println!("📝 [WRITE] Exportando adjunto ID {} a: {}", id, target.display());
+
+    let bytes = STANDARD
+        .decode(content.file_data.trim())
+        .map_err(|_| "El adjunto no está codificado en base64 válido".to_string())?;
+    attachments::prepare_export_dir(&project.local_path, &target)?;
+    write_file_creating_dirs(&target, &bytes, overwrite.unwrap_or(false))?;
+
+    if let Err(e) = db.log_activity(
+        content.project_id,
+        ActivityType::AttachmentExported,
+        "Archivo adjunto exportado",
+        json!({
+            "attachment_id": id,
+            "filename": content.filename,
+            "path": target.display().to_string(),
+        }),
+    ) {
+        println!("⚠️ [ACTIVITY] Error registrando actividad: {}", e);
+    }
+
+    println!("✅ [WRITE] Archivo escrito exitosamente: {}", target.display());
+    Ok(target.display().to_string())
 }
 
 #[tauri::command]
@@ -571,6 +947,187 @@ pub async fn delete_todo(
         .map_err(|e| format!("Error deleting todo: {}", e))
 }
 
+// ==================== COMANDOS PARA TABLERO DE TODOS ====================
+
+/// Columnas del tablero y TODOs ordenados (crea las columnas predeterminadas la primera vez)
+#[tauri::command]
+pub async fn get_project_board(
+    db: State<'_, Database>,
+    project_id: i64,
+) -> Result<ProjectBoard, String> {
+    db.get_project_board(project_id)
+        .map_err(|e| format!("Error getting board: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_board_columns(
+    db: State<'_, Database>,
+    project_id: i64,
+) -> Result<Vec<BoardColumn>, String> {
+    db.get_board_columns(project_id)
+        .map_err(|e| format!("Error getting board columns: {}", e))
+}
+
+#[tauri::command]
+pub async fn create_board_column(
+    db: State<'_, Database>,
+    column: CreateBoardColumnDTO,
+) -> Result<BoardColumn, String> {
+    println!("📋 [BOARD] Creando columna '{}' para proyecto ID: {}", column.name, column.project_id);
+    db.create_board_column(column)
+        .map_err(|e| format!("Error creating board column: {}", e))
+}
+
+/// Renombrar una columna o cambiar cuál es la de terminados. Los TODOs de la
+/// columna que deja de serlo siguen completados, con su fecha.
+#[tauri::command]
+pub async fn update_board_column(
+    db: State<'_, Database>,
+    id: i64,
+    updates: UpdateBoardColumnDTO,
+) -> Result<BoardColumn, String> {
+    println!("📋 [BOARD] Actualizando columna ID: {}", id);
+    db.update_board_column(id, updates)
+        .map_err(|e| format!("Error updating board column: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_board_column(db: State<'_, Database>, id: i64) -> Result<(), String> {
+    println!("📋 [BOARD] Eliminando columna ID: {}", id);
+    db.delete_board_column(id)
+        .map_err(|e| format!("Error deleting board column: {}", e))
+}
+
+#[tauri::command]
+pub async fn reorder_board_columns(
+    db: State<'_, Database>,
+    project_id: i64,
+    column_ids: Vec<i64>,
+) -> Result<(), String> {
+    db.reorder_board_columns(project_id, column_ids)
+        .map_err(|e| format!("Error reordering board columns: {}", e))
+}
+
+/// Mover un TODO a una columna y posición (0 = arriba). Mantiene `is_completed` coherente.
+#[tauri::command]
+pub async fn move_todo(
+    db: State<'_, Database>,
+    todo_id: i64,
+    column_id: i64,
+    position: i64,
+) -> Result<ProjectTodo, String> {
+    println!("📋 [BOARD] Moviendo TODO ID: {} a columna {} (posición {})", todo_id, column_id, position);
+    db.move_todo(todo_id, column_id, position)
+        .map_err(|e| format!("Error moving todo: {}", e))
+}
+
+// ==================== COMANDOS PARA HITOS ====================
+
+#[tauri::command]
+pub async fn create_milestone(
+    db: State<'_, Database>,
+    milestone: CreateMilestoneDTO,
+) -> Result<ProjectMilestone, String> {
+    println!("🏁 [MILESTONE] Creando hito para proyecto ID: {}", milestone.project_id);
+    db.create_milestone(milestone)
+        .map_err(|e| format!("Error creating milestone: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_project_milestones(
+    db: State<'_, Database>,
+    project_id: i64,
+) -> Result<Vec<ProjectMilestone>, String> {
+    db.get_project_milestones(project_id)
+        .map_err(|e| format!("Error getting milestones: {}", e))
+}
+
+#[tauri::command]
+pub async fn update_milestone(
+    db: State<'_, Database>,
+    id: i64,
+    updates: UpdateMilestoneDTO,
+) -> Result<ProjectMilestone, String> {
+    println!("🏁 [MILESTONE] Actualizando hito ID: {}", id);
+    db.update_milestone(id, updates)
+        .map_err(|e| format!("Error updating milestone: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_milestone(db: State<'_, Database>, id: i64) -> Result<(), String> {
+    println!("🏁 [MILESTONE] Eliminando hito ID: {}", id);
+    db.delete_milestone(id)
+        .map_err(|e| format!("Error deleting milestone: {}", e))
+}
+
+/// Vincular un TODO a un hito (o desvincularlo con `milestone_id: null`)
+#[tauri::command]
+pub async fn set_todo_milestone(
+    db: State<'_, Database>,
+    todo_id: i64,
+    milestone_id: Option<i64>,
+) -> Result<(), String> {
+    db.set_todo_milestone(todo_id, milestone_id)
+        .map_err(|e| format!("Error linking todo to milestone: {}", e))
+}
+
+/// Hitos vencidos y próximos de todos los proyectos (por defecto, los próximos 14 días)
+#[tauri::command]
+pub async fn get_upcoming_milestones(
+    db: State<'_, Database>,
+    days_ahead: Option<i64>,
+) -> Result<Vec<UpcomingMilestone>, String> {
+    db.get_upcoming_milestones(days_ahead.unwrap_or(14))
+        .map_err(|e| format!("Error getting upcoming milestones: {}", e))
+}
+
+// ==================== COMANDOS PARA RECORDATORIOS ====================
+
+#[tauri::command]
+pub async fn create_reminder(
+    db: State<'_, Database>,
+    reminder: CreateReminderDTO,
+) -> Result<Reminder, String> {
+    println!("⏰ [REMINDER] Creando recordatorio para proyecto ID: {}", reminder.project_id);
+    db.create_reminder(reminder)
+        .map_err(|e| format!("Error creating reminder: {}", e))
+}
+
+/// Recordatorios de un proyecto, o de todos si no se indica `project_id`
+#[tauri::command]
+pub async fn get_reminders(
+    db: State<'_, Database>,
+    project_id: Option<i64>,
+    include_dismissed: Option<bool>,
+) -> Result<Vec<Reminder>, String> {
+    db.get_reminders(project_id, include_dismissed.unwrap_or(false))
+        .map_err(|e| format!("Error getting reminders: {}", e))
+}
+
+#[tauri::command]
+pub async fn snooze_reminder(
+    db: State<'_, Database>,
+    id: i64,
+    minutes: i64,
+) -> Result<Reminder, String> {
+    println!("⏰ [REMINDER] Posponiendo recordatorio ID: {} ({} min)", id, minutes);
+    db.snooze_reminder(id, minutes)
+        .map_err(|e| format!("Error snoozing reminder: {}", e))
+}
+
+#[tauri::command]
+pub async fn dismiss_reminder(db: State<'_, Database>, id: i64) -> Result<Reminder, String> {
+    println!("⏰ [REMINDER] Descartando recordatorio ID: {}", id);
+    db.dismiss_reminder(id)
+        .map_err(|e| format!("Error dismissing reminder: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_reminder(db: State<'_, Database>, id: i64) -> Result<(), String> {
+    db.delete_reminder(id)
+        .map_err(|e| format!("Error deleting reminder: {}", e))
+}
+
 // ==================== COMANDOS PARA ESTADOS Y FAVORITOS ====================
 
 #[tauri::command]
@@ -604,309 +1161,693 @@ pub async fn reorder_pinned_projects(
         .map_err(|e| format!("Error reordering pinned projects: {}", e))
 }
 
+// ==================== COMANDOS PARA VISTAS GUARDADAS ====================
+
+#[tauri::command]
+pub async fn create_smart_view(
+    db: State<'_, Database>,
+    view: CreateSmartViewDTO,
+) -> Result<SmartView, String> {
+    println!("🔖 [VIEWS] Creando vista guardada: {}", view.name);
+    db.create_smart_view(view)
+        .map_err(|e| format!("Error creating smart view: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_smart_views(db: State<'_, Database>) -> Result<Vec<SmartView>, String> {
+    db.get_smart_views()
+        .map_err(|e| format!("Error getting smart views: {}", e))
+}
+
+#[tauri::command]
+pub async fn update_smart_view(
+    db: State<'_, Database>,
+    id: i64,
+    updates: UpdateSmartViewDTO,
+) -> Result<SmartView, String> {
+    println!("🔖 [VIEWS] Actualizando vista guardada ID: {}", id);
+    db.update_smart_view(id, updates)
+        .map_err(|e| format!("Error updating smart view: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_smart_view(db: State<'_, Database>, id: i64) -> Result<(), String> {
+    println!("🔖 [VIEWS] Eliminando vista guardada ID: {}", id);
+    db.delete_smart_view(id)
+        .map_err(|e| format!("Error deleting smart view: {}", e))
+}
+
+/// Ejecutar una vista guardada y devolver los proyectos que cumplen su definición
+#[tauri::command]
+pub async fn run_smart_view(app: tauri::AppHandle, id: i64) -> Result<Vec<Project>, String> {
+    println!("🔖 [VIEWS] Ejecutando vista ID: {}", id);
+    // El filtro de cambios sin commitear lee el estado de git de cada proyecto
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<Database>()
+            .run_smart_view(id)
+            .map_err(|e| format!("Error evaluating smart view: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Error evaluating smart view: {}", e))?
+}
+
+/// Evaluar una definición sin guardarla (vista previa en el editor de vistas)
+#[tauri::command]
+pub async fn preview_smart_view(
+    app: tauri::AppHandle,
+    filter: SmartViewFilter,
+    sort_by: SmartViewSortField,
+    sort_direction: SortDirection,
+) -> Result<Vec<Project>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<Database>()
+            .query_projects(&filter, sort_by, sort_direction)
+            .map_err(|e| format!("Error evaluating smart view: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Error evaluating smart view: {}", e))?
+}
+
+// ==================== COMANDOS PARA RELACIONES ENTRE PROYECTOS ====================
+
+#[tauri::command]
+pub async fn create_project_relation(
+    db: State<'_, Database>,
+    relation: CreateRelationDTO,
+) -> Result<ProjectRelation, String> {
+    println!(
+        "🧩 [RELATIONS] {} {} {}",
+        relation.source_project_id,
+        relation.relation_type.as_str(),
+        relation.target_project_id
+    );
+    db.create_relation(relation)
+        .map_err(|e| format!("Error creating relation: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_project_relation(db: State<'_, Database>, id: i64) -> Result<(), String> {
+    println!("🧩 [RELATIONS] Eliminando relación ID: {}", id);
+    db.delete_relation(id)
+        .map_err(|e| format!("Error deleting relation: {}", e))
+}
+
+/// Relaciones de un proyecto en ambos sentidos (incluye las inversas)
+#[tauri::command]
+pub async fn get_project_relations(
+    db: State<'_, Database>,
+    project_id: i64,
+) -> Result<Vec<RelatedProject>, String> {
+    db.get_project_relations(project_id)
+        .map_err(|e| format!("Error getting relations: {}", e))
+}
+
+/// Proyectos que dependen de este; se usa para avisar antes de eliminarlo
+#[tauri::command]
+pub async fn get_project_dependents(
+    db: State<'_, Database>,
+    project_id: i64,
+) -> Result<Vec<RelatedProject>, String> {
+    db.get_project_dependents(project_id)
+        .map_err(|e| format!("Error getting dependents: {}", e))
+}
+
+/// Vecindario del proyecto (por defecto 2 saltos, máximo 5)
+#[tauri::command]
+pub async fn get_project_graph(
+    db: State<'_, Database>,
+    project_id: i64,
+    depth: Option<i64>,
+) -> Result<ProjectGraph, String> {
+    db.get_project_graph(project_id, depth.unwrap_or(2))
+        .map_err(|e| format!("Error getting project graph: {}", e))
+}
+
 // Git Commands
 #[tauri::command]
 pub async fn get_git_branch(path: String) -> Result<String, String> {
-    let output = Command::new("git")
-        .args(&["-C", &path, "rev-parse", "--abbrev-ref", "HEAD"])
-        .output()
-        .map_err(|e| format!("Failed to execute git command: {}", e))?;
-
-    if output.status.success() {
-        let branch = String::from_utf8_lossy(&output.stdout)
-            .trim()
-            .to_string();
-        Ok(branch)
-    } else {
-        Err("Not a git repository".to_string())
-    }
+    git::backend().current_branch(&path)
 }
 
+/// Estado en formato `git status --porcelain` (vacío si no hay cambios)
 #[tauri::command]
 pub async fn get_git_status(path: String) -> Result<String, String> {
-    let output = Command::new("git")
-        .args(&["-C", &path, "status", "--porcelain"])
-        .output()
-        .map_err(|e| format!("Failed to execute git command: {}", e))?;
+    let status = git::backend().status(&path)?;
+    Ok(status
+        .entries
+        .iter()
+        .map(|entry| format!("{}\n", entry.porcelain()))
+        .collect())
+}
 
-    if output.status.success() {
-        let status = String::from_utf8_lossy(&output.stdout).to_string();
-        Ok(status)
+/// Estado completo: rama, upstream, stashes y cada archivo con su estado en el
+/// índice y en el directorio de trabajo, renombrados, conflictos y submódulos
+#[tauri::command]
+pub async fn get_git_status_detailed(path: String) -> Result<GitStatus, String> {
+    git::backend().status(&path)
+}
+
+/// Ramas locales y remotas con su upstream y su último commit
+#[tauri::command]
+pub async fn get_git_branches(path: String) -> Result<Vec<GitBranch>, String> {
+    git::backend().branches(&path)
+}
+
+/// Crear una rama (desde `start_point` o desde HEAD) sin cambiar a ella
+#[tauri::command]
+pub async fn git_create_branch(
+    db: State<'_, Database>,
+    path: String,
+    name: String,
+    start_point: Option<String>,
+) -> Result<(), String> {
+    println!("🌿 [GIT] Creando rama: {}", name);
+
+    let name = name.trim();
+    git::backend().create_branch(&path, name, start_point.as_deref())?;
+    log_path_activity(
+        &db,
+        &path,
+        ActivityType::GitBranchCreated,
+        "Rama creada",
+        json!({ "branch": name, "start_point": start_point }),
+    );
+    Ok(())
+}
+
+/// Cambiar de rama. Con cambios sin commitear falla salvo con `auto_stash`
+#[tauri::command]
+pub async fn git_switch_branch(
+    db: State<'_, Database>,
+    path: String,
+    name: String,
+    auto_stash: bool,
+) -> Result<BranchSwitch, String> {
+    println!("🌿 [GIT] Cambiando a la rama: {}", name);
+
+    let switch = git::switch_branch(&path, &name, auto_stash)?;
+    let description = if switch.stashed && !switch.restored {
+        println!("⚠️ [GIT] Cambio de rama con los cambios pendientes en stash@{{0}}");
+        "Cambio de rama (los cambios siguen en el stash)"
     } else {
-        Err("Not a git repository".to_string())
-    }
+        println!("✅ [GIT] Rama actual: {}", switch.branch);
+        "Cambio de rama"
+    };
+    log_path_activity(
+        &db,
+        &path,
+        ActivityType::GitBranchSwitched,
+        description,
+        json!({
+            "branch": switch.branch,
+            "requested": name,
+            "auto_stash": switch.stashed,
+            "restored": switch.restored,
+        }),
+    );
+    Ok(switch)
 }
 
-#[derive(serde::Serialize)]
-pub struct GitCommit {
-    pub hash: String,
-    pub author: String,
-    pub date: String,
-    pub message: String,
+#[tauri::command]
+pub async fn git_rename_branch(
+    db: State<'_, Database>,
+    path: String,
+    old_name: String,
+    new_name: String,
+) -> Result<(), String> {
+    println!("🌿 [GIT] Renombrando rama: {} -> {}", old_name, new_name);
+
+    let new_name = new_name.trim();
+    git::backend().rename_branch(&path, &old_name, new_name)?;
+    log_path_activity(
+        &db,
+        &path,
+        ActivityType::GitBranchRenamed,
+        "Rama renombrada",
+        json!({ "old_name": old_name, "new_name": new_name }),
+    );
+    Ok(())
+}
+
+/// Borrar una rama local; sin `force` solo si está fusionada
+#[tauri::command]
+pub async fn git_delete_branch(
+    db: State<'_, Database>,
+    path: String,
+    name: String,
+    force: bool,
+) -> Result<(), String> {
+    println!("🗑️ [GIT] Borrando rama: {}", name);
+
+    git::backend().delete_branch(&path, &name, force)?;
+    log_path_activity(
+        &db,
+        &path,
+        ActivityType::GitBranchDeleted,
+        "Rama borrada",
+        json!({ "branch": name, "force": force }),
+    );
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn get_recent_commits(path: String, limit: usize) -> Result<Vec<GitCommit>, String> {
-    let limit_str = limit.to_string();
-    let output = Command::new("git")
-        .args(&[
-            "-C",
-            &path,
-            "log",
-            &format!("-{}", limit_str),
-            "--pretty=format:%H|%an|%ar|%s",
-        ])
-        .output()
-        .map_err(|e| format!("Failed to execute git command: {}", e))?;
-
-    if output.status.success() {
-        let commits_str = String::from_utf8_lossy(&output.stdout);
-        let commits: Vec<GitCommit> = commits_str
-            .lines()
-            .filter_map(|line| {
-                let parts: Vec<&str> = line.split('|').collect();
-                if parts.len() == 4 {
-                    Some(GitCommit {
-                        hash: parts[0][..7].to_string(), // Short hash
-                        author: parts[1].to_string(),
-                        date: parts[2].to_string(),
-                        message: parts[3].to_string(),
-                    })
-                } else {
-                    None
-                }
-            })
-            .collect();
-        Ok(commits)
-    } else {
-        Err("Not a git repository or no commits".to_string())
-    }
+    git::backend().recent_commits(&path, limit)
 }
 
-// ==================== COMANDOS GIT MEJORADOS ====================
+/// Diff sin commitear de un archivo (o de todos con `file` vacío), del directorio
+/// de trabajo o, con `staged`, de lo que entraría en el próximo commit
+#[tauri::command]
+pub async fn get_git_diff(
+    path: String,
+    file: Option<String>,
+    staged: bool,
+) -> Result<Vec<FileDiff>, String> {
+    let file = file.filter(|file| !file.trim().is_empty());
+    git::backend().diff(&path, file.as_deref(), staged)
+}
 
-/// Estructura para conteo de archivos modificados
-#[derive(serde::Serialize)]
-pub struct GitFileCount {
-    pub modified: usize,
-    pub staged: usize,
-    pub untracked: usize,
+/// Diff de un commit respecto a su padre
+#[tauri::command]
+pub async fn get_commit_diff(path: String, hash: String) -> Result<Vec<FileDiff>, String> {
+    git::backend().commit_diff(&path, hash.trim())
 }
 
-/// Obtener conteo de archivos modificados, staged y untracked
+/// Stashes guardados, del más reciente al más antiguo
 #[tauri::command]
-pub async fn get_git_file_count(path: String) -> Result<GitFileCount, String> {
-    let output = Command::new("git")
-        .args(&["-C", &path, "status", "--porcelain"])
-        .output()
-        .map_err(|e| format!("Failed to execute git command: {}", e))?;
+pub async fn get_git_stashes(path: String) -> Result<Vec<GitStash>, String> {
+    git::backend().stashes(&path)
+}
 
-    if !output.status.success() {
-        return Err("Not a git repository".to_string());
+/// Guardar los cambios en un stash (git stash push)
+#[tauri::command]
+pub async fn git_stash_save(
+    db: State<'_, Database>,
+    path: String,
+    message: Option<String>,
+    include_untracked: bool,
+) -> Result<(), String> {
+    println!("📦 [GIT] Guardando cambios en stash");
+
+    let message = message.filter(|message| !message.trim().is_empty());
+    git::backend().stash_save(&path, message.as_deref(), include_untracked)?;
+    println!("✅ [GIT] Stash creado");
+    log_path_activity(
+        &db,
+        &path,
+        ActivityType::GitStashSaved,
+        "Cambios guardados en un stash",
+        json!({ "message": message, "include_untracked": include_untracked }),
+    );
+    Ok(())
+}
+
+/// Datos del stash para la actividad. Se leen antes de operar: al borrarlo
+/// cambian las posiciones de los demás.
+fn stash_details(path: &str, index: usize) -> serde_json::Value {
+    let stash = git::backend()
+        .stashes(path)
+        .ok()
+        .and_then(|stashes| stashes.into_iter().find(|stash| stash.index == index));
+
+    match stash {
+        Some(stash) => json!({
+            "index": index,
+            "hash": stash.hash,
+            "branch": stash.branch,
+            "message": stash.message,
+        }),
+        None => json!({ "index": index }),
     }
+}
+
+#[tauri::command]
+pub async fn git_stash_apply(
+    db: State<'_, Database>,
+    path: String,
+    index: usize,
+) -> Result<(), String> {
+    println!("📦 [GIT] Aplicando stash@{{{}}}", index);
 
-    let status = String::from_utf8_lossy(&output.stdout);
-    let mut modified = 0;
-    let mut staged = 0;
-    let mut untracked = 0;
+    let details = stash_details(&path, index);
+    git::backend().stash_apply(&path, index)?;
+    log_path_activity(&db, &path, ActivityType::GitStashApplied, "Stash aplicado", details);
+    Ok(())
+}
 
-    for line in status.lines() {
-        if line.len() < 3 {
-            continue;
-        }
+/// Aplicar un stash y borrarlo (se conserva si hay conflictos)
+#[tauri::command]
+pub async fn git_stash_pop(
+    db: State<'_, Database>,
+    path: String,
+    index: usize,
+) -> Result<(), String> {
+    println!("📦 [GIT] Recuperando stash@{{{}}}", index);
 
-        let status_code = &line[0..2];
+    let details = stash_details(&path, index);
+    git::backend().stash_pop(&path, index)?;
+    log_path_activity(&db, &path, ActivityType::GitStashPopped, "Stash recuperado", details);
+    Ok(())
+}
 
-        // Primer carácter: staged (index)
-        // Segundo carácter: working tree
-        match status_code.chars().nth(0).unwrap_or(' ') {
-            'M' | 'A' | 'D' | 'R' | 'C' => staged += 1,
-            _ => {}
-        }
+#[tauri::command]
+pub async fn git_stash_drop(
+    db: State<'_, Database>,
+    path: String,
+    index: usize,
+) -> Result<(), String> {
+    println!("🗑️ [GIT] Borrando stash@{{{}}}", index);
 
-        match status_code.chars().nth(1).unwrap_or(' ') {
-            'M' | 'D' => modified += 1,
-            _ => {}
-        }
+    let details = stash_details(&path, index);
+    git::backend().stash_drop(&path, index)?;
+    log_path_activity(&db, &path, ActivityType::GitStashDropped, "Stash borrado", details);
+    Ok(())
+}
 
-        if status_code.starts_with("??") {
-            untracked += 1;
-        }
-    }
+/// Vista previa de un stash: cambios guardados y archivos sin seguimiento
+#[tauri::command]
+pub async fn get_git_stash_diff(path: String, index: usize) -> Result<Vec<FileDiff>, String> {
+    git::stash_diff(&path, index)
+}
 
-    Ok(GitFileCount {
-        modified,
-        staged,
-        untracked,
-    })
+// ==================== COMANDOS GIT MEJORADOS ====================
+
+/// Obtener conteo de archivos modificados, staged y untracked
+#[tauri::command]
+pub async fn get_git_file_count(path: String) -> Result<GitFileCount, String> {
+    let status = git::backend().status(&path)?;
+    Ok(GitFileCount::from_status(&status))
 }
 
 /// Obtener lista de archivos modificados
 #[tauri::command]
 pub async fn get_git_modified_files(path: String) -> Result<Vec<String>, String> {
-    let output = Command::new("git")
-        .args(&["-C", &path, "status", "--porcelain"])
-        .output()
-        .map_err(|e| format!("Failed to execute git command: {}", e))?;
+    let status = git::backend().status(&path)?;
+    Ok(status.entries.into_iter().map(|entry| entry.path).collect())
+}
 
-    if !output.status.success() {
-        return Err("Not a git repository".to_string());
-    }
+/// Stage archivos (git add)
+#[tauri::command]
+pub async fn git_add(
+    db: State<'_, Database>,
+    path: String,
+    files: Vec<String>,
+) -> Result<String, String> {
+    println!("📝 [GIT] Staging {} archivos", files.len());
 
-    let status = String::from_utf8_lossy(&output.stdout);
-    let files: Vec<String> = status
-        .lines()
-        .filter_map(|line| {
-            if line.len() >= 3 {
-                Some(line[3..].trim().to_string())
-            } else {
-                None
-            }
-        })
-        .collect();
+    git::backend().stage(&path, &files)?;
+    println!("✅ [GIT] Archivos staged exitosamente");
+    log_path_activity(
+        &db,
+        &path,
+        ActivityType::GitStaged,
+        "Archivos añadidos al stage",
+        json!({ "files": files }),
+    );
+    Ok("Archivos staged exitosamente".to_string())
+}
 
-    Ok(files)
+/// Archivos de una selección de hunks, sin repetir
+fn hunk_files(hunks: &[HunkSelection]) -> Vec<&str> {
+    let mut files: Vec<&str> = hunks.iter().map(|hunk| hunk.file.as_str()).collect();
+    files.sort_unstable();
+    files.dedup();
+    files
 }
 
-/// Stage archivos (git add)
+/// Stage de hunks o líneas sueltas del directorio de trabajo
 #[tauri::command]
-pub async fn git_add(path: String, files: Vec<String>) -> Result<String, String> {
-    println!("📝 [GIT] Staging {} archivos", files.len());
+pub async fn git_stage_hunks(
+    db: State<'_, Database>,
+    path: String,
+    hunks: Vec<HunkSelection>,
+) -> Result<(), String> {
+    println!("📝 [GIT] Staging {} hunks", hunks.len());
+
+    git::stage_hunks(&path, &hunks)?;
+    log_path_activity(
+        &db,
+        &path,
+        ActivityType::GitStaged,
+        "Cambios parciales añadidos al stage",
+        json!({ "files": hunk_files(&hunks), "hunks": hunks.len() }),
+    );
+    Ok(())
+}
 
-    let mut args = vec!["-C", &path, "add"];
-    let file_refs: Vec<&str> = files.iter().map(|s| s.as_str()).collect();
-    args.extend(file_refs);
+/// Quitar del stage hunks o líneas sueltas
+#[tauri::command]
+pub async fn git_unstage_hunks(
+    db: State<'_, Database>,
+    path: String,
+    hunks: Vec<HunkSelection>,
+) -> Result<(), String> {
+    println!("📝 [GIT] Unstaging {} hunks", hunks.len());
+
+    git::unstage_hunks(&path, &hunks)?;
+    log_path_activity(
+        &db,
+        &path,
+        ActivityType::GitUnstaged,
+        "Cambios parciales quitados del stage",
+        json!({ "files": hunk_files(&hunks), "hunks": hunks.len() }),
+    );
+    Ok(())
+}
 
-    let output = Command::new("git")
-        .args(&args)
-        .output()
-        .map_err(|e| format!("Failed to execute git add: {}", e))?;
+/// Quitar archivos del stage (git restore --staged)
+#[tauri::command]
+pub async fn git_unstage_files(
+    db: State<'_, Database>,
+    path: String,
+    files: Vec<String>,
+) -> Result<(), String> {
+    println!("📝 [GIT] Unstaging {} archivos", files.len());
+
+    git::backend().unstage(&path, &files)?;
+    log_path_activity(
+        &db,
+        &path,
+        ActivityType::GitUnstaged,
+        "Archivos quitados del stage",
+        json!({ "files": files }),
+    );
+    Ok(())
+}
 
-    if output.status.success() {
-        println!("✅ [GIT] Archivos staged exitosamente");
-        Ok("Archivos staged exitosamente".to_string())
-    } else {
-        let error = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Error staging files: {}", error))
-    }
+/// Lo que se perdería al descartar cambios, con el token para confirmarlo
+#[tauri::command]
+pub async fn git_discard_preview(
+    path: String,
+    files: Vec<String>,
+    hunks: Vec<HunkSelection>,
+) -> Result<DiscardPreview, String> {
+    git::discard_preview(&path, &files, &hunks)
+}
+
+/// Descartar cambios del directorio de trabajo (archivos enteros o hunks).
+/// Requiere el token de `git_discard_preview`: sin él no se borra nada.
+#[tauri::command]
+pub async fn git_discard_changes(
+    db: State<'_, Database>,
+    path: String,
+    files: Vec<String>,
+    hunks: Vec<HunkSelection>,
+    confirm_token: String,
+) -> Result<(), String> {
+    println!(
+        "🗑️ [GIT] Descartando cambios: {} archivos, {} hunks",
+        files.len(),
+        hunks.len()
+    );
+
+    git::discard(&path, &files, &hunks, &confirm_token)?;
+    println!("✅ [GIT] Cambios descartados");
+    log_path_activity(
+        &db,
+        &path,
+        ActivityType::GitDiscarded,
+        "Cambios descartados",
+        json!({ "files": files, "hunk_files": hunk_files(&hunks), "hunks": hunks.len() }),
+    );
+    Ok(())
 }
 
 /// Crear commit (git commit)
 #[tauri::command]
-pub async fn git_commit(path: String, message: String) -> Result<String, String> {
+pub async fn git_commit(
+    db: State<'_, Database>,
+    path: String,
+    message: String,
+) -> Result<String, String> {
     println!("💾 [GIT] Creando commit: {}", message);
 
-    let output = Command::new("git")
-        .args(&["-C", &path, "commit", "-m", &message])
-        .output()
-        .map_err(|e| format!("Failed to execute git commit: {}", e))?;
-
-    if output.status.success() {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        println!("✅ [GIT] Commit creado exitosamente");
-        Ok(stdout.to_string())
-    } else {
-        let error = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Error creating commit: {}", error))
-    }
+    let summary = git::backend().commit(&path, &message)?;
+    println!("✅ [GIT] Commit creado exitosamente");
+    log_path_activity(
+        &db,
+        &path,
+        ActivityType::GitCommit,
+        "Commit creado",
+        json!({ "message": message, "summary": summary }),
+    );
+    Ok(summary)
 }
 
 /// Push a remote (git push)
 #[tauri::command]
-pub async fn git_push(path: String) -> Result<String, String> {
+pub async fn git_push(db: State<'_, Database>, path: String) -> Result<String, String> {
     println!("🚀 [GIT] Pushing to remote");
 
-    let output = Command::new("git")
-        .args(&["-C", &path, "push"])
-        .output()
-        .map_err(|e| format!("Failed to execute git push: {}", e))?;
-
-    if output.status.success() {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        println!("✅ [GIT] Push exitoso");
-        Ok(format!("{}{}", stdout, stderr))
-    } else {
-        let error = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Error pushing: {}", error))
-    }
+    let output = git::backend().push(&path)?;
+    println!("✅ [GIT] Push exitoso");
+    log_path_activity(&db, &path, ActivityType::GitPush, "Push al remoto", serde_json::Value::Null);
+    Ok(output)
 }
 
 /// Pull from remote (git pull)
 #[tauri::command]
-pub async fn git_pull(path: String) -> Result<String, String> {
+pub async fn git_pull(db: State<'_, Database>, path: String) -> Result<String, String> {
     println!("⬇️ [GIT] Pulling from remote");
 
-    let output = Command::new("git")
-        .args(&["-C", &path, "pull"])
-        .output()
-        .map_err(|e| format!("Failed to execute git pull: {}", e))?;
-
-    if output.status.success() {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        println!("✅ [GIT] Pull exitoso");
-        Ok(stdout.to_string())
-    } else {
-        let error = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Error pulling: {}", error))
-    }
+    let output = git::backend().pull(&path)?;
+    println!("✅ [GIT] Pull exitoso");
+    log_path_activity(
+        &db,
+        &path,
+        ActivityType::GitPull,
+        "Pull desde el remoto",
+        json!({ "summary": output.lines().last().unwrap_or("") }),
+    );
+    Ok(output)
 }
 
 /// Obtener URL del remote origin
 #[tauri::command]
 pub async fn get_git_remote_url(path: String) -> Result<Option<String>, String> {
-    let output = Command::new("git")
-        .args(&["-C", &path, "remote", "get-url", "origin"])
-        .output()
-        .map_err(|e| format!("Failed to execute git command: {}", e))?;
-
-    if output.status.success() {
-        let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        if url.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(url))
-        }
-    } else {
-        Ok(None)
-    }
+    Ok(git::backend().remote_url(&path, "origin").unwrap_or(None))
 }
 
 /// Obtener commits ahead/behind respecto al remote
 #[tauri::command]
 pub async fn get_git_ahead_behind(path: String) -> Result<(u32, u32), String> {
     // Primero hacer fetch para tener info actualizada
-    let _ = Command::new("git")
-        .args(&["-C", &path, "fetch", "--quiet"])
-        .output();
-
-    let output = Command::new("git")
-        .args(&[
-            "-C",
-            &path,
-            "rev-list",
-            "--left-right",
-            "--count",
-            "HEAD...@{upstream}",
-        ])
-        .output()
-        .map_err(|e| format!("Failed to execute git command: {}", e))?;
-
-    if output.status.success() {
-        let result = String::from_utf8_lossy(&output.stdout);
-        let parts: Vec<&str> = result.trim().split_whitespace().collect();
-
-        if parts.len() == 2 {
-            let ahead = parts[0].parse::<u32>().unwrap_or(0);
-            let behind = parts[1].parse::<u32>().unwrap_or(0);
-            Ok((ahead, behind))
-        } else {
-            Ok((0, 0))
-        }
-    } else {
-        // No hay upstream configurado o no es un repo git
-        Ok((0, 0))
+    if let Err(e) = git::backend().fetch(&path) {
+        println!("⚠️ [GIT] Fetch fallido: {}", e);
     }
+
+    // No es un repo git: igual que sin upstream
+    Ok(git::backend()
+        .status(&path)
+        .map(|status| (status.ahead, status.behind))
+        .unwrap_or((0, 0)))
+}
+
+// ==================== COMANDOS PARA SALUD DE PROYECTOS ====================
+
+/// Calcula la salud de un proyecto combinando la base de datos y el estado de git.
+/// No hace `fetch`: ahead/behind se calcula con la última información del remoto.
+fn compute_project_health(db: &Database, project: &Project) -> Result<ProjectHealth, String> {
+    let signals = db
+        .get_health_signals(project.id)
+        .map_err(|e| format!("Error getting health signals: {}", e))?;
+
+    let path_exists =
+        !project.local_path.trim().is_empty() && std::path::Path::new(&project.local_path).is_dir();
+
+    let git = if path_exists {
+        // Si no es un repositorio git simplemente no hay factores de git
+        git::backend()
+            .status(&project.local_path)
+            .ok()
+            .map(|status| {
+                let count = GitFileCount::from_status(&status);
+                GitHealth {
+                    uncommitted_files: count.modified + count.staged + count.untracked,
+                    ahead: status.ahead,
+                    behind: status.behind,
+                }
+            })
+    } else {
+        None
+    };
+
+    Ok(health::compute(project, &signals, path_exists, git))
+}
+
+#[tauri::command]
+pub async fn get_project_health(
+    app: tauri::AppHandle,
+    project_id: i64,
+) -> Result<ProjectHealth, String> {
+    println!("🩺 [HEALTH] Calculando salud del proyecto ID: {}", project_id);
+    // El estado de git se lee de forma bloqueante: no puede ejecutarse en el runtime async
+    tauri::async_runtime::spawn_blocking(move || {
+        let db = app.state::<Database>();
+        let project = db
+            .get_project(project_id)
+            .map_err(|e| format!("Error getting project: {}", e))?;
+
+        compute_project_health(&db, &project)
+    })
+    .await
+    .map_err(|e| format!("Error calculando la salud: {}", e))?
+}
+
+/// Salud de todos los proyectos, de peor a mejor puntuación
+#[tauri::command]
+pub async fn get_projects_health(app: tauri::AppHandle) -> Result<Vec<ProjectHealth>, String> {
+    println!("🩺 [HEALTH] Calculando salud de todos los proyectos");
+    let mut health = tauri::async_runtime::spawn_blocking(move || {
+        let db = app.state::<Database>();
+        let projects = db
+            .get_all_projects()
+            .map_err(|e| format!("Error getting projects: {}", e))?;
+
+        projects
+            .iter()
+            .map(|project| compute_project_health(&db, project))
+            .collect::<Result<Vec<_>, String>>()
+    })
+    .await
+    .map_err(|e| format!("Error calculando la salud: {}", e))??;
+
+    health.sort_by(|a, b| {
+        a.score
+            .cmp(&b.score)
+            .then_with(|| a.project_name.to_lowercase().cmp(&b.project_name.to_lowercase()))
+    });
+
+    Ok(health)
+}
+
+// ==================== COMANDOS PARA ARCHIVADO DE PROYECTOS INACTIVOS ====================
+
+/// Vista previa: proyectos que la política de archivado archivaría ahora
+#[tauri::command]
+pub async fn get_stale_projects(
+    db: State<'_, Database>,
+    config_manager: State<'_, ConfigManager>,
+) -> Result<Vec<StaleProject>, String> {
+    let policy = config_manager.get_config()?.archive_policy;
+    println!(
+        "🗄️ [ARCHIVE] Buscando proyectos sin abrir en {} días ni commits en {} días",
+        policy.days_not_opened, policy.days_without_commits
+    );
+    archive::find_stale_projects(&db, &policy)
+}
+
+/// Archiva los proyectos inactivos (o solo los indicados de entre ellos)
+#[tauri::command]
+pub async fn archive_stale_projects(
+    db: State<'_, Database>,
+    config_manager: State<'_, ConfigManager>,
+    project_ids: Option<Vec<i64>>,
+) -> Result<Vec<StaleProject>, String> {
+    let policy = config_manager.get_config()?.archive_policy;
+    let archived = archive::archive_stale_projects(&db, &policy, project_ids.as_deref())?;
+    println!("🗄️ [ARCHIVE] {} proyectos archivados", archived.len());
+    Ok(archived)
 }
 
 // ==================== COMANDOS PARA CONFIGURACIÓN ====================
@@ -921,19 +1862,26 @@ pub async fn get_config(
 
 #[tauri::command]
 pub async fn update_config(
+    db: State<'_, Database>,
     config_manager: State<'_, ConfigManager>,
     config: AppConfig,
 ) -> Result<(), String> {
     println!("💾 [CONFIG] Actualizando configuración");
-    config_manager.update_config(config)
+    let enable_analytics = config.advanced.enable_analytics;
+    config_manager.update_config(config)?;
+    db.set_analytics_enabled(enable_analytics);
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn reset_config(
+    db: State<'_, Database>,
     config_manager: State<'_, ConfigManager>,
 ) -> Result<AppConfig, String> {
     println!("🔄 [CONFIG] Reseteando configuración a valores por defecto");
-    config_manager.reset_config()
+    let config = config_manager.reset_config()?;
+    db.set_analytics_enabled(config.advanced.enable_analytics);
+    Ok(config)
 }
 
 #[tauri::command]
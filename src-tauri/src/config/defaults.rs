@@ -12,6 +12,7 @@ impl Default for AppConfig {
             ui: UiConfig::default(),
             advanced: AdvancedConfig::default(),
             shortcuts: ShortcutsConfig::default(),
+            archive_policy: ArchivePolicyConfig::default(),
         }
     }
 }
@@ -40,6 +41,16 @@ impl Default for BackupConfig {
     }
 }
 
+impl Default for ArchivePolicyConfig {
+    fn default() -> Self {
+        Self {
+            mode: ArchivePolicyMode::Suggest,
+            days_not_opened: 90,
+            days_without_commits: 60,
+        }
+    }
+}
+
 impl Default for UiConfig {
     fn default() -> Self {
         Self {
@@ -56,6 +67,7 @@ impl Default for AdvancedConfig {
         Self {
             log_level: LogLevel::Info,
             enable_analytics: true,
+            analytics_retention_days: DEFAULT_ANALYTICS_RETENTION_DAYS,
             database_path: None,
             enable_auto_update: true,
         }
@@ -16,6 +16,9 @@ pub struct AppConfig {
     pub advanced: AdvancedConfig,
     /// Configuración de atajos de teclado
     pub shortcuts: ShortcutsConfig,
+    /// Política de archivado de proyectos inactivos
+    #[serde(default)]
+    pub archive_policy: ArchivePolicyConfig,
 }
 
 /// Configuración de plataforma (programas y comportamientos del OS)
@@ -79,6 +82,29 @@ pub struct BackupConfig {
     pub retention_days: u32,
 }
 
+/// Política de archivado de proyectos inactivos
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArchivePolicyConfig {
+    /// Qué hacer con los proyectos 'activo' que cumplen los criterios
+    pub mode: ArchivePolicyMode,
+    /// Días sin abrir el proyecto
+    pub days_not_opened: u32,
+    /// Días sin commits (los proyectos sin repositorio git solo se evalúan por apertura)
+    pub days_without_commits: u32,
+}
+
+/// Modo de la política de archivado
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchivePolicyMode {
+    /// Desactivada
+    Off,
+    /// Solo mostrar sugerencias
+    Suggest,
+    /// Archivar automáticamente
+    Auto,
+}
+
 /// Configuración de interfaz de usuario
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UiConfig {
@@ -109,8 +135,11 @@ pub enum ThemeMode {
 pub struct AdvancedConfig {
     /// Nivel de logging
     pub log_level: LogLevel,
-    /// Habilitar analytics/telemetría
+    /// Habilitar analytics/telemetría (registro de actividad y tiempo)
     pub enable_analytics: bool,
+    /// Días que se conserva la actividad detallada antes de resumirla por día (0 = siempre)
+    #[serde(default = "default_analytics_retention_days")]
+    pub analytics_retention_days: u32,
     /// Ruta personalizada de base de datos
     #[serde(skip_serializing_if = "Option::is_none")]
     pub database_path: Option<String>,
@@ -118,6 +147,13 @@ pub struct AdvancedConfig {
     pub enable_auto_update: bool,
 }
 
+/// Retención por defecto de la actividad detallada
+pub const DEFAULT_ANALYTICS_RETENTION_DAYS: u32 = 180;
+
+fn default_analytics_retention_days() -> u32 {
+    DEFAULT_ANALYTICS_RETENTION_DAYS
+}
+
 /// Nivel de logging
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "lowercase")]
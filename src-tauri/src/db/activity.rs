@@ -0,0 +1,345 @@
+use rusqlite::{params, Connection, OptionalExtension, Result, Row, ToSql};
+use serde_json::Value;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+use super::search::SqlParams;
+use super::Database;
+use crate::models::project::{
+    ActivityFilter, ActivityPruneResult, ActivityType, DailyActivitySummary, ProjectActivity,
+};
+
+/// Columnas de `project_activity` (alias `a`) con el nombre del proyecto si aún existe
+pub(super) const ACTIVITY_COLUMNS: &str =
+    "a.id, a.project_id, (SELECT name FROM projects WHERE id = a.project_id), a.activity_type,
+     a.description, a.duration_seconds, a.details, a.created_at";
+
+/// Límite por defecto de `get_activities`
+const DEFAULT_ACTIVITY_LIMIT: i64 = 100;
+
+pub(super) fn init_schema(conn: &Connection) -> Result<()> {
+    // Migración: detalles estructurados en JSON (ignorar si ya existe)
+    let _ = conn.execute("ALTER TABLE project_activity ADD COLUMN details TEXT", []);
+
+    // Migración: el historial debe sobrevivir al borrado del proyecto, así que se
+    // reconstruye la tabla sin la FK con ON DELETE CASCADE de versiones anteriores
+    let table_sql: Option<String> = conn
+        .query_row(
+            "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'project_activity'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if table_sql.is_some_and(|sql| sql.contains("REFERENCES projects")) {
+        conn.execute_batch(
+            "BEGIN;
+             CREATE TABLE project_activity_new (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                project_id INTEGER NOT NULL,
+                activity_type TEXT NOT NULL,
+                description TEXT,
+                duration_seconds INTEGER,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                details TEXT
+             );
+             INSERT INTO project_activity_new
+                (id, project_id, activity_type, description, duration_seconds, created_at, details)
+             SELECT id, project_id, activity_type, description, duration_seconds, created_at, details
+             FROM project_activity;
+             DROP TABLE project_activity;
+             ALTER TABLE project_activity_new RENAME TO project_activity;
+             COMMIT;",
+        )?;
+    }
+
+    // Resúmenes diarios de la actividad ya purgada por la política de retención
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS activity_daily_summary (
+            project_id INTEGER NOT NULL,
+            day TEXT NOT NULL,
+            activity_type TEXT NOT NULL,
+            count INTEGER NOT NULL DEFAULT 0,
+            total_duration_seconds INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (project_id, day, activity_type)
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_project_activity_project
+         ON project_activity (project_id, created_at)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_project_activity_created_at ON project_activity (created_at)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+pub(super) fn activity_from_row(row: &Row) -> Result<ProjectActivity> {
+    let details: Option<String> = row.get(6)?;
+    Ok(ProjectActivity {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        project_name: row.get(2)?,
+        activity_type: row.get(3)?,
+        description: row.get(4)?,
+        duration_seconds: row.get(5)?,
+        // Un JSON inválido no debe impedir leer el historial
+        details: details.and_then(|json| serde_json::from_str(&json).ok()),
+        created_at: row.get(7)?,
+    })
+}
+
+/// Resumen corto de un texto para los detalles de la actividad
+pub(super) fn preview(text: &str) -> String {
+    const MAX_CHARS: usize = 80;
+    let text = text.trim();
+    if text.chars().count() <= MAX_CHARS {
+        text.to_string()
+    } else {
+        format!("{}…", text.chars().take(MAX_CHARS).collect::<String>())
+    }
+}
+
+/// Proyecto al que pertenece una fila (`table` es siempre un nombre fijo del código)
+pub(super) fn owner_project_id(conn: &Connection, table: &str, id: i64) -> Result<Option<i64>> {
+    conn.query_row(
+        &format!("SELECT project_id FROM {} WHERE id = ?1", table),
+        params![id],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+impl Database {
+    // ==================== MÉTODOS PARA REGISTRO DE ACTIVIDAD ====================
+
+    pub fn set_analytics_enabled(&self, enabled: bool) {
+        self.analytics_enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn analytics_enabled(&self) -> bool {
+        self.analytics_enabled.load(Ordering::Relaxed)
+    }
+
+    /// Registra una actividad dentro de una operación que ya tiene la conexión.
+    /// No hace nada si la analítica está desactivada en la configuración.
+    pub(super) fn log_activity_internal(
+        &self,
+        conn: &Connection,
+        project_id: i64,
+        activity_type: ActivityType,
+        description: &str,
+        details: Value,
+    ) -> Result<()> {
+        // El último backup es un dato del proyecto (salud), se registre o no la actividad
+        if matches!(activity_type, ActivityType::BackupCreated | ActivityType::BackupSynced) {
+            conn.prepare_cached("UPDATE projects SET last_backup_at = CURRENT_TIMESTAMP WHERE id = ?1")?
+                .execute(params![project_id])?;
+        }
+
+        if !self.analytics_enabled() {
+            return Ok(());
+        }
+
+        let details = if details.is_null() {
+            None
+        } else {
+            Some(details.to_string())
+        };
+
+        conn.prepare_cached(
+            "INSERT INTO project_activity (project_id, activity_type, description, details)
+             VALUES (?1, ?2, ?3, ?4)",
+        )?
+        .execute(params![project_id, activity_type.as_str(), description, details])?;
+
+        Ok(())
+    }
+
+    /// Registra una actividad desde la capa de comandos (git, backups...)
+    pub fn log_activity(
+        &self,
+        project_id: i64,
+        activity_type: ActivityType,
+        description: &str,
+        details: Value,
+    ) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        self.log_activity_internal(&conn, project_id, activity_type, description, details)
+    }
+
+    /// Proyecto cuya ruta local es `path` (o la contiene), para asociar actividad de git.
+    /// Si hay proyectos anidados gana la ruta más larga.
+    pub fn find_project_id_by_path(&self, path: &str) -> Result<Option<i64>> {
+        let conn = self.conn.lock().unwrap();
+        let target = Path::new(path);
+
+        let mut stmt = conn.prepare_cached("SELECT id, local_path FROM projects")?;
+        let projects = stmt
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(projects
+            .into_iter()
+            .filter(|(_, local_path)| !local_path.is_empty() && target.starts_with(local_path))
+            .max_by_key(|(_, local_path)| Path::new(local_path).components().count())
+            .map(|(id, _)| id))
+    }
+
+    pub fn get_activities(&self, filter: &ActivityFilter) -> Result<Vec<ProjectActivity>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut clauses: Vec<String> = Vec::new();
+        let mut params: SqlParams = Vec::new();
+
+        if let Some(project_id) = filter.project_id {
+            clauses.push("a.project_id = ?".to_string());
+            params.push(Box::new(project_id));
+        }
+
+        if !filter.activity_types.is_empty() {
+            let placeholders = vec!["?"; filter.activity_types.len()].join(", ");
+            clauses.push(format!("a.activity_type IN ({})", placeholders));
+            for activity_type in &filter.activity_types {
+                params.push(Box::new(activity_type.clone()));
+            }
+        }
+
+        if let Some(since) = &filter.since {
+            clauses.push("a.created_at >= datetime(?)".to_string());
+            params.push(Box::new(since.clone()));
+        }
+
+        if let Some(until) = &filter.until {
+            clauses.push("a.created_at <= datetime(?)".to_string());
+            params.push(Box::new(until.clone()));
+        }
+
+        let where_sql = if clauses.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", clauses.join(" AND "))
+        };
+
+        params.push(Box::new(filter.limit.unwrap_or(DEFAULT_ACTIVITY_LIMIT).clamp(1, 1000)));
+        params.push(Box::new(filter.offset.unwrap_or(0).max(0)));
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM project_activity a {}
+             ORDER BY a.created_at DESC, a.id DESC
+             LIMIT ? OFFSET ?",
+            ACTIVITY_COLUMNS, where_sql
+        ))?;
+
+        let params_ref: Vec<&dyn ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let activities = stmt
+            .query_map(params_ref.as_slice(), activity_from_row)?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(activities)
+    }
+
+    /// Agrupa por día la actividad más antigua que `retention_days` en `activity_daily_summary`
+    /// y borra las filas detalladas. Con `retention_days == 0` se conserva todo.
+    pub fn prune_activities(&self, retention_days: u32) -> Result<ActivityPruneResult> {
+        if retention_days == 0 {
+            return Ok(ActivityPruneResult::default());
+        }
+
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let cutoff = format!("-{} days", retention_days);
+
+        let summarized_days: i64 = tx.query_row(
+            "SELECT COUNT(*) FROM (
+                SELECT DISTINCT project_id, date(created_at) FROM project_activity
+                WHERE created_at < datetime('now', ?1)
+             )",
+            params![cutoff],
+            |row| row.get(0),
+        )?;
+
+        tx.execute(
+            "INSERT INTO activity_daily_summary
+                (project_id, day, activity_type, count, total_duration_seconds)
+             SELECT project_id, date(created_at), activity_type, COUNT(*),
+                    COALESCE(SUM(duration_seconds), 0)
+             FROM project_activity
+             WHERE created_at < datetime('now', ?1)
+             GROUP BY project_id, date(created_at), activity_type
+             ON CONFLICT (project_id, day, activity_type) DO UPDATE SET
+                count = count + excluded.count,
+                total_duration_seconds = total_duration_seconds + excluded.total_duration_seconds",
+            params![cutoff],
+        )?;
+
+        let deleted_rows = tx.execute(
+            "DELETE FROM project_activity WHERE created_at < datetime('now', ?1)",
+            params![cutoff],
+        )?;
+
+        tx.commit()?;
+
+        Ok(ActivityPruneResult {
+            deleted_rows: deleted_rows as i64,
+            summarized_days,
+        })
+    }
+
+    /// Actividad agregada por día, combinando los resúmenes con las filas aún detalladas
+    pub fn get_daily_activity(
+        &self,
+        project_id: Option<i64>,
+        since: Option<String>,
+    ) -> Result<Vec<DailyActivitySummary>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT project_id, day, activity_type, SUM(count), SUM(total_duration_seconds)
+             FROM (
+                SELECT project_id, day, activity_type, count, total_duration_seconds
+                FROM activity_daily_summary
+                UNION ALL
+                SELECT project_id, date(created_at), activity_type, 1, COALESCE(duration_seconds, 0)
+                FROM project_activity
+             )
+             WHERE (?1 IS NULL OR project_id = ?1)
+               AND (?2 IS NULL OR day >= date(?2))
+             GROUP BY project_id, day, activity_type
+             ORDER BY day DESC, project_id, activity_type",
+        )?;
+
+        let summaries = stmt
+            .query_map(params![project_id, since], |row| {
+                Ok(DailyActivitySummary {
+                    project_id: row.get(0)?,
+                    day: row.get(1)?,
+                    activity_type: row.get(2)?,
+                    count: row.get(3)?,
+                    total_duration_seconds: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(summaries)
+    }
+
+    /// Borra toda la analítica: historial, resúmenes y contadores de uso de los proyectos.
+    /// `last_opened_at` no se toca: es un dato del proyecto que usan la búsqueda
+    /// (`opened:`), las vistas guardadas, la salud y el archivado de inactivos.
+    pub fn purge_analytics(&self) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+
+        let deleted = tx.execute("DELETE FROM project_activity", [])?;
+        tx.execute("DELETE FROM activity_daily_summary", [])?;
+        tx.execute("UPDATE projects SET opened_count = 0, total_time_seconds = 0", [])?;
+
+        tx.commit()?;
+        Ok(deleted as i64)
+    }
+}
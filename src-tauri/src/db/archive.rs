@@ -0,0 +1,61 @@
+use rusqlite::{params, Result};
+use serde_json::Value;
+
+use super::Database;
+use crate::models::project::StaleProject;
+
+impl Database {
+    // ==================== MÉTODOS PARA ARCHIVADO DE PROYECTOS INACTIVOS ====================
+
+    /// Proyectos 'activo' sin abrir en al menos `days_not_opened` días.
+    /// Los datos de git (`days_since_commit`) los completa quien evalúa la política.
+    pub fn get_stale_candidates(&self, days_not_opened: u32) -> Result<Vec<StaleProject>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, name, local_path, days_since_open, never_opened FROM (
+                SELECT id, name, local_path,
+                       CAST(julianday('now') - julianday(COALESCE(last_opened_at, created_at)) AS INTEGER)
+                           AS days_since_open,
+                       last_opened_at IS NULL AS never_opened
+                FROM projects
+                WHERE COALESCE(status, 'activo') = 'activo'
+             )
+             WHERE days_since_open >= ?1
+             ORDER BY days_since_open DESC",
+        )?;
+
+        let candidates = stmt
+            .query_map(params![days_not_opened], |row| {
+                Ok(StaleProject {
+                    project_id: row.get(0)?,
+                    project_name: row.get(1)?,
+                    local_path: row.get(2)?,
+                    days_since_open: row.get(3)?,
+                    never_opened: row.get(4)?,
+                    days_since_commit: None,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(candidates)
+    }
+
+    /// Archiva un proyecto por inactividad si sigue 'activo'. Devuelve `false` si ya no lo estaba.
+    /// `details` se añade al registro de actividad del cambio de estado.
+    pub fn archive_stale_project(&self, id: i64, details: Value) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+
+        let is_active: bool = conn.query_row(
+            "SELECT COALESCE(status, 'activo') = 'activo' FROM projects WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )?;
+        if !is_active {
+            return Ok(false);
+        }
+
+        self.set_project_status_internal(&conn, id, "archivado", Some(details))?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,611 @@
+use rusqlite::{params, Connection, OptionalExtension, Result, Row, ToSql};
+use serde_json::json;
+
+use super::search::{compile_query, SqlParams};
+use super::Database;
+use crate::attachments::{FileAttachment, TextExtraction};
+use crate::models::project::{
+    ActivityType, AttachmentContent, AttachmentSearchHit, AttachmentTextStatus, CreateAttachmentDTO,
+    ProjectAttachment,
+};
+use crate::search::{SearchQuery, Term};
+
+/// Columnas de `project_attachments` (alias `a`) sin el contenido, con el estado de
+/// extracción de texto y el número de versiones del documento
+const ATTACHMENT_COLUMNS: &str =
+    "a.id, a.project_id, a.filename, a.file_size, a.mime_type, a.created_at, a.content_hash,
+     COALESCE((SELECT status FROM attachment_text t WHERE t.attachment_id = a.id), 'pending'),
+     (SELECT error FROM attachment_text t WHERE t.attachment_id = a.id),
+     a.logical_name, a.version,
+     (SELECT COUNT(*) FROM project_attachments v
+      WHERE v.project_id = a.project_id AND v.logical_name = a.logical_name)";
+
+/// Número de columnas de `ATTACHMENT_COLUMNS`
+const ATTACHMENT_COLUMN_COUNT: usize = 12;
+
+/// Condición para quedarse solo con la última versión de cada documento
+const IS_LATEST_VERSION: &str =
+    "a.version = (SELECT MAX(v.version) FROM project_attachments v
+                  WHERE v.project_id = a.project_id AND v.logical_name = a.logical_name)";
+
+/// Marcadores de `snippet()`: caracteres de control que no aparecen en el texto indexado
+const SNIPPET_START: char = '\u{1}';
+const SNIPPET_END: char = '\u{2}';
+
+/// Límite por defecto de `search_attachments`
+const DEFAULT_SEARCH_LIMIT: i64 = 50;
+
+pub(super) fn init_schema(conn: &Connection) -> Result<()> {
+    // Migración: hash SHA-256 del contenido para detectar duplicados (ignorar si ya existe)
+    let _ = conn.execute("ALTER TABLE project_attachments ADD COLUMN content_hash TEXT", []);
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_project_attachments_hash
+         ON project_attachments (project_id, content_hash)",
+        [],
+    )?;
+
+    // Migración: versiones de un mismo documento bajo un nombre lógico estable
+    let _ = conn.execute("ALTER TABLE project_attachments ADD COLUMN logical_name TEXT", []);
+    let _ = conn.execute(
+        "ALTER TABLE project_attachments ADD COLUMN version INTEGER NOT NULL DEFAULT 1",
+        [],
+    );
+    // Los adjuntos anteriores con el mismo nombre pasan a ser versiones sucesivas
+    conn.execute(
+        "UPDATE project_attachments
+         SET logical_name = filename,
+             version = (SELECT COUNT(*) FROM project_attachments b
+                        WHERE b.project_id = project_attachments.project_id
+                          AND b.filename = project_attachments.filename
+                          AND b.id <= project_attachments.id)
+         WHERE logical_name IS NULL",
+        [],
+    )?;
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_project_attachments_version
+         ON project_attachments (project_id, logical_name, version)",
+        [],
+    )?;
+
+    // Estado de la extracción de texto; un adjunto sin fila está pendiente
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS attachment_text (
+            attachment_id INTEGER PRIMARY KEY,
+            status TEXT NOT NULL DEFAULT 'pending',
+            error TEXT,
+            extracted_at DATETIME,
+            FOREIGN KEY (attachment_id) REFERENCES project_attachments (id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Índice de texto completo (rowid = id del adjunto)
+    conn.execute(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS attachment_text_fts
+         USING fts5(content, tokenize = 'unicode61 remove_diacritics 2')",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TRIGGER IF NOT EXISTS trg_project_attachments_delete_fts
+         AFTER DELETE ON project_attachments
+         BEGIN
+            DELETE FROM attachment_text_fts WHERE rowid = old.id;
+         END",
+        [],
+    )?;
+
+    // Extracciones interrumpidas al cerrar la aplicación: volver a intentarlo
+    conn.execute(
+        "UPDATE attachment_text SET status = 'pending' WHERE status = 'processing'",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn attachment_from_row(row: &Row) -> Result<ProjectAttachment> {
+    Ok(ProjectAttachment {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        filename: row.get(2)?,
+        file_size: row.get(3)?,
+        mime_type: row.get(4)?,
+        created_at: row.get(5)?,
+        content_hash: row.get(6)?,
+        text_status: AttachmentTextStatus::parse(&row.get::<_, String>(7)?)
+            .unwrap_or(AttachmentTextStatus::Pending),
+        text_error: row.get(8)?,
+        logical_name: row.get(9)?,
+        version: row.get(10)?,
+        version_count: row.get(11)?,
+    })
+}
+
+/// Traduce las palabras y frases de la consulta a la sintaxis de FTS5.
+/// Las palabras buscan por prefijo; las negadas se excluyen con `NOT`.
+fn fts_query(query: &SearchQuery) -> Option<String> {
+    let quote = |text: &str| format!("\"{}\"", text.replace('"', "\"\""));
+
+    let mut include = Vec::new();
+    let mut exclude = Vec::new();
+    for clause in &query.clauses {
+        let term = match &clause.term {
+            Term::Word(word) => format!("{}*", quote(word)),
+            Term::Phrase(phrase) => quote(phrase),
+            _ => continue,
+        };
+        if clause.negated {
+            exclude.push(term);
+        } else {
+            include.push(term);
+        }
+    }
+
+    if include.is_empty() {
+        return None;
+    }
+
+    let mut fts = include.join(" ");
+    for term in exclude {
+        fts = format!("({}) NOT {}", fts, term);
+    }
+    Some(fts)
+}
+
+/// Escapa el fragmento como HTML y convierte los marcadores de coincidencia en `<mark>`
+fn snippet_html(snippet: &str) -> String {
+    let mut html = String::with_capacity(snippet.len());
+    for c in snippet.chars() {
+        match c {
+            SNIPPET_START => html.push_str("<mark>"),
+            SNIPPET_END => html.push_str("</mark>"),
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            c => html.push(c),
+        }
+    }
+    html
+}
+
+impl Database {
+    // ==================== MÉTODOS PARA ARCHIVOS ADJUNTOS ====================
+
+    /// Adjunto enviado en base64 por el frontend. El tipo MIME y el tamaño se
+    /// calculan del contenido; los valores del cliente se ignoran.
+    pub fn add_attachment(&self, attachment: CreateAttachmentDTO) -> Result<ProjectAttachment> {
+        let file = FileAttachment::from_base64(&attachment.filename, &attachment.file_data)
+            .map_err(rusqlite::Error::InvalidParameterName)?;
+
+        self.add_file_attachment(attachment.project_id, &file)
+            .map(|(attachment, _)| attachment)
+    }
+
+    /// Guarda un adjunto ya leído. Si el proyecto ya tiene un documento con el mismo
+    /// nombre, se guarda como nueva versión de ese documento. Si la última versión de
+    /// algún documento tiene el mismo contenido no se duplica: se devuelve esa y `false`.
+    pub fn add_file_attachment(
+        &self,
+        project_id: i64,
+        file: &FileAttachment,
+    ) -> Result<(ProjectAttachment, bool)> {
+        let conn = self.conn.lock().unwrap();
+        self.insert_attachment_version_internal(&conn, project_id, &file.filename, file, false)
+    }
+
+    /// Sube una nueva versión del documento al que pertenece `attachment_id`,
+    /// aunque el archivo tenga otro nombre. Si el contenido es igual al de la
+    /// última versión se devuelve esa y `false`.
+    pub fn add_attachment_version(
+        &self,
+        attachment_id: i64,
+        file: &FileAttachment,
+    ) -> Result<(ProjectAttachment, bool)> {
+        let conn = self.conn.lock().unwrap();
+
+        let (project_id, logical_name): (i64, String) = conn.query_row(
+            "SELECT project_id, logical_name FROM project_attachments WHERE id = ?1",
+            params![attachment_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        self.insert_attachment_version_internal(&conn, project_id, &logical_name, file, true)
+    }
+
+    fn insert_attachment_version_internal(
+        &self,
+        conn: &Connection,
+        project_id: i64,
+        logical_name: &str,
+        file: &FileAttachment,
+        same_document_only: bool,
+    ) -> Result<(ProjectAttachment, bool)> {
+        let existing = conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM project_attachments a
+                     WHERE a.project_id = ?1 AND a.content_hash = ?2 AND {}
+                       AND (?3 IS NULL OR a.logical_name = ?3)
+                     ORDER BY a.id LIMIT 1",
+                    ATTACHMENT_COLUMNS, IS_LATEST_VERSION
+                ),
+                params![
+                    project_id,
+                    file.content_hash,
+                    same_document_only.then_some(logical_name)
+                ],
+                attachment_from_row,
+            )
+            .optional()?;
+        if let Some(existing) = existing {
+            return Ok((existing, false));
+        }
+
+        let version: i64 = conn.query_row(
+            "SELECT COALESCE(MAX(version), 0) + 1 FROM project_attachments
+             WHERE project_id = ?1 AND logical_name = ?2",
+            params![project_id, logical_name],
+            |row| row.get(0),
+        )?;
+
+        conn.execute(
+            "INSERT INTO project_attachments
+                (project_id, filename, file_data, file_size, mime_type, content_hash, logical_name, version)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                project_id,
+                file.filename,
+                file.base64(),
+                file.file_size(),
+                file.mime_type,
+                file.content_hash,
+                logical_name,
+                version
+            ],
+        )?;
+
+        let attachment = conn.query_row(
+            &format!("SELECT {} FROM project_attachments a WHERE a.id = ?1", ATTACHMENT_COLUMNS),
+            params![conn.last_insert_rowid()],
+            attachment_from_row,
+        )?;
+
+        self.log_activity_internal(
+            conn,
+            attachment.project_id,
+            ActivityType::AttachmentAdded,
+            if version > 1 {
+                "Nueva versión de archivo adjunto"
+            } else {
+                "Archivo adjuntado"
+            },
+            json!({
+                "attachment_id": attachment.id,
+                "filename": attachment.filename,
+                "logical_name": attachment.logical_name,
+                "version": attachment.version,
+                "file_size": attachment.file_size,
+                "mime_type": attachment.mime_type,
+            }),
+        )?;
+
+        Ok((attachment, true))
+    }
+
+    /// Última versión de cada documento del proyecto
+    pub fn get_attachments(&self, project_id: i64) -> Result<Vec<ProjectAttachment>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM project_attachments a
+             WHERE a.project_id = ?1 AND {}
+             ORDER BY a.created_at DESC, a.id DESC",
+            ATTACHMENT_COLUMNS, IS_LATEST_VERSION
+        ))?;
+
+        let attachments = stmt
+            .query_map(params![project_id], attachment_from_row)?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(attachments)
+    }
+
+    /// Historial de versiones del documento al que pertenece `attachment_id`, de la más reciente a la más antigua
+    pub fn get_attachment_versions(&self, attachment_id: i64) -> Result<Vec<ProjectAttachment>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM project_attachments a
+             JOIN project_attachments d ON d.project_id = a.project_id AND d.logical_name = a.logical_name
+             WHERE d.id = ?1
+             ORDER BY a.version DESC",
+            ATTACHMENT_COLUMNS
+        ))?;
+
+        let versions = stmt
+            .query_map(params![attachment_id], attachment_from_row)?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(versions)
+    }
+
+    /// Restaura una versión anterior copiándola como nueva última versión, de modo
+    /// que el historial no se pierde. Si ya es la última versión no hace nada.
+    pub fn rollback_attachment(&self, version_id: i64) -> Result<ProjectAttachment> {
+        let conn = self.conn.lock().unwrap();
+
+        let (project_id, logical_name, restored_version, latest_version): (i64, String, i64, i64) =
+            conn.query_row(
+                "SELECT a.project_id, a.logical_name, a.version,
+                        (SELECT MAX(v.version) FROM project_attachments v
+                         WHERE v.project_id = a.project_id AND v.logical_name = a.logical_name)
+                 FROM project_attachments a WHERE a.id = ?1",
+                params![version_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )?;
+
+        if restored_version == latest_version {
+            return conn.query_row(
+                &format!("SELECT {} FROM project_attachments a WHERE a.id = ?1", ATTACHMENT_COLUMNS),
+                params![version_id],
+                attachment_from_row,
+            );
+        }
+
+        conn.execute(
+            "INSERT INTO project_attachments
+                (project_id, filename, file_data, file_size, mime_type, content_hash, logical_name, version)
+             SELECT project_id, filename, file_data, file_size, mime_type, content_hash, logical_name, ?2
+             FROM project_attachments WHERE id = ?1",
+            params![version_id, latest_version + 1],
+        )?;
+
+        let attachment = conn.query_row(
+            &format!("SELECT {} FROM project_attachments a WHERE a.id = ?1", ATTACHMENT_COLUMNS),
+            params![conn.last_insert_rowid()],
+            attachment_from_row,
+        )?;
+
+        self.log_activity_internal(
+            &conn,
+            project_id,
+            ActivityType::AttachmentRestored,
+            "Versión anterior de archivo restaurada",
+            json!({
+                "attachment_id": attachment.id,
+                "logical_name": logical_name,
+                "restored_version": restored_version,
+                "version": attachment.version,
+            }),
+        )?;
+
+        Ok(attachment)
+    }
+
+    /// Contenido de un adjunto para el protocolo `gestor://` y la exportación
+    pub fn get_attachment_content(&self, id: i64) -> Result<Option<AttachmentContent>> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.query_row(
+            "SELECT project_id, filename, mime_type, file_data FROM project_attachments WHERE id = ?1",
+            params![id],
+            |row| {
+                Ok(AttachmentContent {
+                    project_id: row.get(0)?,
+                    filename: row.get(1)?,
+                    mime_type: row.get(2)?,
+                    file_data: row.get(3)?,
+                })
+            },
+        )
+        .optional()
+    }
+
+    /// Identifica el contenido de un adjunto sin leerlo (caché del protocolo `gestor://`).
+    /// Cada versión es una fila nueva, así que el contenido de un id no cambia.
+    pub fn get_attachment_stamp(&self, id: i64) -> Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.query_row(
+            "SELECT COALESCE(content_hash, '') FROM project_attachments WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+        .optional()
+    }
+
+    /// Marca como en proceso el siguiente adjunto pendiente de extraer texto y devuelve su contenido
+    pub fn claim_pending_attachment_text(&self) -> Result<Option<(i64, AttachmentContent)>> {
+        let conn = self.conn.lock().unwrap();
+
+        let pending = conn
+            .query_row(
+                "SELECT a.id, a.project_id, a.filename, a.mime_type, a.file_data
+                 FROM project_attachments a
+                 LEFT JOIN attachment_text t ON t.attachment_id = a.id
+                 WHERE t.attachment_id IS NULL OR t.status = 'pending'
+                 ORDER BY a.id
+                 LIMIT 1",
+                [],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        AttachmentContent {
+                            project_id: row.get(1)?,
+                            filename: row.get(2)?,
+                            mime_type: row.get(3)?,
+                            file_data: row.get(4)?,
+                        },
+                    ))
+                },
+            )
+            .optional()?;
+
+        if let Some((id, _)) = &pending {
+            conn.execute(
+                "INSERT INTO attachment_text (attachment_id, status) VALUES (?1, 'processing')
+                 ON CONFLICT(attachment_id) DO UPDATE SET status = 'processing', error = NULL",
+                params![id],
+            )?;
+        }
+
+        Ok(pending)
+    }
+
+    /// Guarda el resultado de la extracción y actualiza el índice de texto completo.
+    /// Si el adjunto se eliminó mientras tanto no hace nada.
+    pub fn save_attachment_text(&self, id: i64, extraction: &TextExtraction) -> Result<()> {
+        let (status, error, text) = match extraction {
+            TextExtraction::Text(text) => (AttachmentTextStatus::Indexed, None, Some(text)),
+            TextExtraction::Unsupported(reason) => (AttachmentTextStatus::Unsupported, Some(reason), None),
+            TextExtraction::Failed(reason) => (AttachmentTextStatus::Failed, Some(reason), None),
+        };
+
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+
+        let updated = tx.execute(
+            "UPDATE attachment_text SET status = ?1, error = ?2, extracted_at = CURRENT_TIMESTAMP
+             WHERE attachment_id = ?3",
+            params![status.as_str(), error, id],
+        )?;
+        if updated == 0 {
+            return Ok(());
+        }
+
+        tx.execute("DELETE FROM attachment_text_fts WHERE rowid = ?1", params![id])?;
+        if let Some(text) = text {
+            tx.execute(
+                "INSERT INTO attachment_text_fts (rowid, content) VALUES (?1, ?2)",
+                params![id, text],
+            )?;
+        }
+
+        tx.commit()
+    }
+
+    /// Vuelve a dejar pendiente la extracción de un adjunto (ej: tras instalar `pdftotext`)
+    pub fn reindex_attachment(&self, id: i64) -> Result<ProjectAttachment> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "INSERT INTO attachment_text (attachment_id, status)
+             SELECT id, 'pending' FROM project_attachments WHERE id = ?1
+             ON CONFLICT(attachment_id) DO UPDATE SET status = 'pending', error = NULL",
+            params![id],
+        )?;
+
+        conn.query_row(
+            &format!("SELECT {} FROM project_attachments a WHERE a.id = ?1", ATTACHMENT_COLUMNS),
+            params![id],
+            attachment_from_row,
+        )
+    }
+
+    /// Adjuntos cuyo texto coincide con las palabras y frases de la consulta.
+    /// El resto de términos (`status:`, `tag:`...) filtran los proyectos como en `search_projects`.
+    pub fn search_attachments(
+        &self,
+        query: &SearchQuery,
+        project_id: Option<i64>,
+        limit: Option<i64>,
+    ) -> Result<Vec<AttachmentSearchHit>> {
+        let Some(fts) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        let mut params: SqlParams = vec![
+            Box::new(SNIPPET_START.to_string()),
+            Box::new(SNIPPET_END.to_string()),
+            Box::new(fts),
+        ];
+        // Las versiones anteriores no aparecen en los resultados
+        let mut clauses = vec![
+            "attachment_text_fts MATCH ?".to_string(),
+            IS_LATEST_VERSION.to_string(),
+        ];
+
+        if let Some(project_id) = project_id {
+            clauses.push("a.project_id = ?".to_string());
+            params.push(Box::new(project_id));
+        }
+
+        let filters = SearchQuery {
+            raw: String::new(),
+            clauses: query
+                .clauses
+                .iter()
+                .filter(|clause| !matches!(clause.term, Term::Word(_) | Term::Phrase(_)))
+                .cloned()
+                .collect(),
+        };
+        if !filters.clauses.is_empty() {
+            let (condition, filter_params) = compile_query(&filters);
+            clauses.push(format!("a.project_id IN (SELECT id FROM projects WHERE {})", condition));
+            params.extend(filter_params);
+        }
+
+        params.push(Box::new(limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, 500)));
+
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {}, p.name, snippet(attachment_text_fts, 0, ?, ?, '…', 16)
+             FROM attachment_text_fts
+             JOIN project_attachments a ON a.id = attachment_text_fts.rowid
+             JOIN projects p ON p.id = a.project_id
+             WHERE {}
+             ORDER BY attachment_text_fts.rank
+             LIMIT ?",
+            ATTACHMENT_COLUMNS,
+            clauses.join(" AND ")
+        ))?;
+
+        let params_ref: Vec<&dyn ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let hits = stmt
+            .query_map(params_ref.as_slice(), |row| {
+                Ok(AttachmentSearchHit {
+                    attachment: attachment_from_row(row)?,
+                    project_name: row.get(ATTACHMENT_COLUMN_COUNT)?,
+                    snippet: snippet_html(&row.get::<_, String>(ATTACHMENT_COLUMN_COUNT + 1)?),
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(hits)
+    }
+
+    /// Elimina el documento al que pertenece `id` con todas sus versiones
+    pub fn delete_attachment(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        let attachment: Option<(i64, String)> = conn
+            .query_row(
+                "SELECT project_id, logical_name FROM project_attachments WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        let Some((project_id, logical_name)) = attachment else {
+            return Ok(());
+        };
+
+        let versions = conn.execute(
+            "DELETE FROM project_attachments WHERE project_id = ?1 AND logical_name = ?2",
+            params![project_id, logical_name],
+        )?;
+
+        self.log_activity_internal(
+            &conn,
+            project_id,
+            ActivityType::AttachmentRemoved,
+            "Archivo eliminado",
+            json!({ "attachment_id": id, "filename": logical_name, "versions": versions }),
+        )?;
+
+        Ok(())
+    }
+}
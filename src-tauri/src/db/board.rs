@@ -0,0 +1,519 @@
+use rusqlite::{params, Connection, Result, Row};
+
+use serde_json::json;
+
+use super::activity::preview;
+use super::{Database, TODO_COLUMNS};
+use crate::models::project::{
+    ActivityType, BoardColumn, CreateBoardColumnDTO, ProjectBoard, ProjectTodo, UpdateBoardColumnDTO,
+};
+
+const BOARD_COLUMN_COLUMNS: &str = "id, project_id, name, position, is_done_column, created_at";
+
+/// Columnas que se crean al abrir por primera vez el tablero de un proyecto
+const DEFAULT_COLUMNS: [(&str, bool); 4] = [
+    ("Backlog", false),
+    ("En curso", false),
+    ("Revisión", false),
+    ("Hecho", true),
+];
+
+pub(super) fn init_schema(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS todo_columns (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            position INTEGER NOT NULL DEFAULT 0,
+            is_done_column BOOLEAN NOT NULL DEFAULT 0,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Migraciones: columna y posición de cada TODO (ignorar si ya existen)
+    let _ = conn.execute(
+        "ALTER TABLE project_todos ADD COLUMN column_id INTEGER REFERENCES todo_columns (id)",
+        [],
+    );
+    let _ = conn.execute(
+        "ALTER TABLE project_todos ADD COLUMN position INTEGER NOT NULL DEFAULT 0",
+        [],
+    );
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_todo_columns_project_id ON todo_columns (project_id, position)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_project_todos_column ON project_todos (column_id, position)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn column_from_row(row: &Row) -> Result<BoardColumn> {
+    Ok(BoardColumn {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        name: row.get(2)?,
+        position: row.get(3)?,
+        is_done_column: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+fn validate_column_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(rusqlite::Error::InvalidParameterName(
+            "El nombre de la columna no puede estar vacío".to_string(),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn get_column_internal(conn: &Connection, id: i64) -> Result<BoardColumn> {
+    conn.query_row(
+        &format!("SELECT {} FROM todo_columns WHERE id = ?1", BOARD_COLUMN_COLUMNS),
+        params![id],
+        column_from_row,
+    )
+}
+
+fn get_columns_internal(conn: &Connection, project_id: i64) -> Result<Vec<BoardColumn>> {
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT {} FROM todo_columns WHERE project_id = ?1 ORDER BY position, id",
+        BOARD_COLUMN_COLUMNS
+    ))?;
+
+    let columns = stmt
+        .query_map(params![project_id], column_from_row)?
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(columns)
+}
+
+/// Ids de los TODOs de una columna en su orden actual, opcionalmente sin `exclude`
+fn column_todo_ids(conn: &Connection, column_id: i64, exclude: Option<i64>) -> Result<Vec<i64>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT id FROM project_todos
+         WHERE column_id = ?1 AND (?2 IS NULL OR id != ?2)
+         ORDER BY position, created_at, id",
+    )?;
+
+    let ids = stmt
+        .query_map(params![column_id, exclude], |row| row.get(0))?
+        .collect::<Result<Vec<i64>>>()?;
+
+    Ok(ids)
+}
+
+/// Reescribe las posiciones de una columna como 0..n en el orden indicado
+fn write_positions(conn: &Connection, column_id: i64, ordered_ids: &[i64]) -> Result<()> {
+    let mut stmt = conn.prepare_cached(
+        "UPDATE project_todos SET column_id = ?1, position = ?2 WHERE id = ?3",
+    )?;
+    for (position, id) in ordered_ids.iter().enumerate() {
+        stmt.execute(params![column_id, position as i64, id])?;
+    }
+    Ok(())
+}
+
+/// Marca como completados los TODOs de la columna si es la de terminados,
+/// conservando su `completed_at`. Cambiar qué columna es la de terminados (o
+/// borrarla) no reabre nada: se perdería el historial de completado.
+fn sync_column_completion(conn: &Connection, column_id: i64) -> Result<()> {
+    if !get_column_internal(conn, column_id)?.is_done_column {
+        return Ok(());
+    }
+
+    conn.execute(
+        "UPDATE project_todos
+         SET is_completed = 1,
+             completed_at = COALESCE(completed_at, CURRENT_TIMESTAMP)
+         WHERE column_id = ?1",
+        params![column_id],
+    )?;
+    Ok(())
+}
+
+/// Devuelve las columnas del proyecto, creando las predeterminadas si aún no tiene.
+/// Los TODOs sin columna (anteriores al tablero) se reparten según estén completados o no.
+pub(super) fn ensure_columns(conn: &Connection, project_id: i64) -> Result<Vec<BoardColumn>> {
+    let mut columns = get_columns_internal(conn, project_id)?;
+
+    if columns.is_empty() {
+        for (position, (name, is_done_column)) in DEFAULT_COLUMNS.iter().enumerate() {
+            conn.execute(
+                "INSERT INTO todo_columns (project_id, name, position, is_done_column)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![project_id, name, position as i64, is_done_column],
+            )?;
+        }
+        columns = get_columns_internal(conn, project_id)?;
+    }
+
+    let unassigned: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM project_todos WHERE project_id = ?1 AND column_id IS NULL)",
+        params![project_id],
+        |row| row.get(0),
+    )?;
+
+    if unassigned {
+        let open_column = first_column(&columns, false);
+        let done_column = first_column(&columns, true).or(open_column);
+
+        if let (Some(open_column), Some(done_column)) = (open_column, done_column) {
+            conn.execute(
+                "UPDATE project_todos
+                 SET column_id = CASE WHEN is_completed THEN ?2 ELSE ?3 END,
+                     position = 1000000 + id
+                 WHERE project_id = ?1 AND column_id IS NULL",
+                params![project_id, done_column, open_column],
+            )?;
+
+            for column_id in [open_column, done_column] {
+                let ids = column_todo_ids(conn, column_id, None)?;
+                write_positions(conn, column_id, &ids)?;
+            }
+        }
+    }
+
+    Ok(columns)
+}
+
+/// Primera columna (por posición) de terminados o de pendientes
+fn first_column(columns: &[BoardColumn], is_done_column: bool) -> Option<i64> {
+    columns
+        .iter()
+        .find(|c| c.is_done_column == is_done_column)
+        .map(|c| c.id)
+}
+
+/// Mueve un TODO a `position` dentro de `column_id` (al final si es `None`) y
+/// sincroniza su estado de completado con el tipo de columna.
+/// No abre transacción: quien llama debe hacerlo.
+pub(super) fn move_todo_internal(
+    conn: &Connection,
+    todo_id: i64,
+    column_id: i64,
+    position: Option<i64>,
+) -> Result<()> {
+    let (project_id, old_column_id): (i64, Option<i64>) = conn.query_row(
+        "SELECT project_id, column_id FROM project_todos WHERE id = ?1",
+        params![todo_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    let column = get_column_internal(conn, column_id)?;
+    if column.project_id != project_id {
+        return Err(rusqlite::Error::InvalidParameterName(
+            "La columna no pertenece al proyecto del TODO".to_string(),
+        ));
+    }
+
+    if let Some(old_column_id) = old_column_id.filter(|old| *old != column_id) {
+        let remaining = column_todo_ids(conn, old_column_id, Some(todo_id))?;
+        write_positions(conn, old_column_id, &remaining)?;
+    }
+
+    let mut ids = column_todo_ids(conn, column_id, Some(todo_id))?;
+    let index = position
+        .map(|p| p.clamp(0, ids.len() as i64) as usize)
+        .unwrap_or(ids.len());
+    ids.insert(index, todo_id);
+    write_positions(conn, column_id, &ids)?;
+
+    conn.execute(
+        "UPDATE project_todos
+         SET is_completed = ?1,
+             completed_at = CASE WHEN ?1 THEN COALESCE(completed_at, CURRENT_TIMESTAMP) ELSE NULL END
+         WHERE id = ?2",
+        params![column.is_done_column, todo_id],
+    )?;
+
+    Ok(())
+}
+
+/// Completa o reabre un TODO moviéndolo a la columna correspondiente.
+/// Si ya está en una columna del tipo adecuado solo se actualiza el estado.
+pub(super) fn set_todo_completion_internal(conn: &Connection, todo_id: i64, is_completed: bool) -> Result<()> {
+    let project_id: i64 = conn.query_row(
+        "SELECT project_id FROM project_todos WHERE id = ?1",
+        params![todo_id],
+        |row| row.get(0),
+    )?;
+
+    let columns = ensure_columns(conn, project_id)?;
+    let current_column: Option<i64> = conn.query_row(
+        "SELECT column_id FROM project_todos WHERE id = ?1",
+        params![todo_id],
+        |row| row.get(0),
+    )?;
+    let current_is_done = columns
+        .iter()
+        .find(|c| Some(c.id) == current_column)
+        .map(|c| c.is_done_column);
+
+    match first_column(&columns, is_completed) {
+        Some(target) if current_is_done != Some(is_completed) => {
+            move_todo_internal(conn, todo_id, target, None)
+        }
+        _ => {
+            // Sin columna del tipo pedido: solo se cambia el estado
+            conn.execute(
+                "UPDATE project_todos
+                 SET is_completed = ?1,
+                     completed_at = CASE WHEN ?1 THEN COALESCE(completed_at, CURRENT_TIMESTAMP) ELSE NULL END
+                 WHERE id = ?2",
+                params![is_completed, todo_id],
+            )?;
+            Ok(())
+        }
+    }
+}
+
+impl Database {
+    // ==================== MÉTODOS PARA TABLERO DE TODOS ====================
+
+    pub fn get_board_columns(&self, project_id: i64) -> Result<Vec<BoardColumn>> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let columns = ensure_columns(&tx, project_id)?;
+        tx.commit()?;
+        Ok(columns)
+    }
+
+    pub fn get_project_board(&self, project_id: i64) -> Result<ProjectBoard> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let columns = ensure_columns(&tx, project_id)?;
+        tx.commit()?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM project_todos
+             WHERE project_id = ?1 AND column_id IS NOT NULL
+             ORDER BY (SELECT position FROM todo_columns WHERE id = project_todos.column_id),
+                      column_id, position, created_at",
+            TODO_COLUMNS
+        ))?;
+
+        let todos = stmt
+            .query_map(params![project_id], Self::todo_from_row)?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(ProjectBoard {
+            project_id,
+            columns,
+            todos,
+        })
+    }
+
+    pub fn create_board_column(&self, column: CreateBoardColumnDTO) -> Result<BoardColumn> {
+        let name = validate_column_name(&column.name)?;
+
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+
+        ensure_columns(&tx, column.project_id)?;
+
+        if column.is_done_column {
+            Self::clear_done_column_internal(&tx, column.project_id)?;
+        }
+
+        tx.execute(
+            "INSERT INTO todo_columns (project_id, name, position, is_done_column)
+             VALUES (?1, ?2, (SELECT COALESCE(MAX(position), -1) + 1 FROM todo_columns WHERE project_id = ?1), ?3)",
+            params![column.project_id, name, column.is_done_column],
+        )?;
+        let id = tx.last_insert_rowid();
+
+        self.log_activity_internal(
+            &tx,
+            column.project_id,
+            ActivityType::BoardUpdated,
+            "Columna creada",
+            json!({ "action": "column_created", "column_id": id, "name": name }),
+        )?;
+
+        tx.commit()?;
+        get_column_internal(&conn, id)
+    }
+
+    pub fn update_board_column(&self, id: i64, updates: UpdateBoardColumnDTO) -> Result<BoardColumn> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+
+        let column = get_column_internal(&tx, id)?;
+
+        if updates.name.is_none() && updates.is_done_column.is_none() {
+            return Err(rusqlite::Error::InvalidParameterCount(0, 1));
+        }
+
+        if let Some(name) = updates.name {
+            tx.execute(
+                "UPDATE todo_columns SET name = ?1 WHERE id = ?2",
+                params![validate_column_name(&name)?, id],
+            )?;
+        }
+
+        if let Some(is_done_column) = updates.is_done_column {
+            if is_done_column != column.is_done_column {
+                // Solo hay una columna de terminados por proyecto
+                if is_done_column {
+                    Self::clear_done_column_internal(&tx, column.project_id)?;
+                }
+                tx.execute(
+                    "UPDATE todo_columns SET is_done_column = ?1 WHERE id = ?2",
+                    params![is_done_column, id],
+                )?;
+                sync_column_completion(&tx, id)?;
+            }
+        }
+
+        let updated = get_column_internal(&tx, id)?;
+        self.log_activity_internal(
+            &tx,
+            column.project_id,
+            ActivityType::BoardUpdated,
+            "Columna actualizada",
+            json!({
+                "action": "column_updated",
+                "column_id": id,
+                "name": updated.name,
+                "is_done_column": updated.is_done_column,
+            }),
+        )?;
+
+        tx.commit()?;
+        Ok(updated)
+    }
+
+    /// Elimina una columna moviendo sus TODOs al final de la primera columna restante
+    pub fn delete_board_column(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+
+        let column = get_column_internal(&tx, id)?;
+        let fallback = get_columns_internal(&tx, column.project_id)?
+            .into_iter()
+            .find(|c| c.id != id)
+            .ok_or_else(|| {
+                rusqlite::Error::InvalidParameterName(
+                    "No se puede eliminar la última columna del tablero".to_string(),
+                )
+            })?;
+
+        let mut ids = column_todo_ids(&tx, fallback.id, None)?;
+        ids.extend(column_todo_ids(&tx, id, None)?);
+        write_positions(&tx, fallback.id, &ids)?;
+        sync_column_completion(&tx, fallback.id)?;
+
+        tx.execute("DELETE FROM todo_columns WHERE id = ?1", params![id])?;
+
+        self.log_activity_internal(
+            &tx,
+            column.project_id,
+            ActivityType::BoardUpdated,
+            "Columna eliminada",
+            json!({
+                "action": "column_deleted",
+                "column_id": id,
+                "name": column.name,
+                "moved_to_column_id": fallback.id,
+            }),
+        )?;
+
+        tx.commit()
+    }
+
+    /// Reordena las columnas según el orden de `column_ids`
+    pub fn reorder_board_columns(&self, project_id: i64, column_ids: Vec<i64>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+
+        for (position, id) in column_ids.iter().enumerate() {
+            tx.execute(
+                "UPDATE todo_columns SET position = ?1 WHERE id = ?2 AND project_id = ?3",
+                params![position as i64, id, project_id],
+            )?;
+        }
+
+        self.log_activity_internal(
+            &tx,
+            project_id,
+            ActivityType::BoardUpdated,
+            "Columnas reordenadas",
+            json!({ "action": "columns_reordered", "column_ids": column_ids }),
+        )?;
+
+        tx.commit()
+    }
+
+    /// Mueve un TODO a otra columna o posición de forma atómica
+    pub fn move_todo(&self, todo_id: i64, column_id: i64, position: i64) -> Result<ProjectTodo> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+
+        let before = Self::get_todo_internal(&tx, todo_id)?;
+        move_todo_internal(&tx, todo_id, column_id, Some(position))?;
+        let after = Self::get_todo_internal(&tx, todo_id)?;
+        self.log_todo_transition(&tx, &before, &after)?;
+
+        tx.commit()?;
+        Ok(after)
+    }
+
+    /// Registra en la actividad el cambio de columna/posición y de estado de un TODO
+    pub(super) fn log_todo_transition(
+        &self,
+        conn: &Connection,
+        before: &ProjectTodo,
+        after: &ProjectTodo,
+    ) -> Result<()> {
+        if before.column_id != after.column_id || before.position != after.position {
+            self.log_activity_internal(
+                conn,
+                after.project_id,
+                ActivityType::TodoMoved,
+                "TODO movido",
+                json!({
+                    "todo_id": after.id,
+                    "from_column_id": before.column_id,
+                    "to_column_id": after.column_id,
+                    "position": after.position,
+                }),
+            )?;
+        }
+
+        if before.is_completed != after.is_completed {
+            let (activity_type, description) = if after.is_completed {
+                (ActivityType::TodoCompleted, "TODO completado")
+            } else {
+                (ActivityType::TodoReopened, "TODO reabierto")
+            };
+            self.log_activity_internal(
+                conn,
+                after.project_id,
+                activity_type,
+                description,
+                json!({ "todo_id": after.id, "content": preview(&after.content) }),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Quita la marca de terminados de la columna actual. Sus TODOs siguen
+    /// completados (ver `sync_column_completion`).
+    fn clear_done_column_internal(conn: &Connection, project_id: i64) -> Result<()> {
+        conn.execute(
+            "UPDATE todo_columns SET is_done_column = 0 WHERE project_id = ?1 AND is_done_column = 1",
+            params![project_id],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use rusqlite::{params, Connection, Result};
+
+use super::Database;
+use crate::models::project::{ActivityType, HealthSignals};
+
+/// La fecha del último backup se guarda en el propio proyecto: el historial de
+/// actividad puede estar desactivado o resumido por la retención
+pub(super) fn init_schema(conn: &Connection) -> Result<()> {
+    let added = conn
+        .execute("ALTER TABLE projects ADD COLUMN last_backup_at DATETIME", [])
+        .is_ok();
+
+    // Migración: el último backup que haya en el historial o en sus resúmenes
+    if added {
+        conn.execute(
+            "UPDATE projects SET last_backup_at = (
+                SELECT MAX(created_at) FROM (
+                    SELECT created_at FROM project_activity
+                    WHERE project_id = projects.id AND activity_type IN (?1, ?2)
+                    UNION ALL
+                    SELECT day FROM activity_daily_summary
+                    WHERE project_id = projects.id AND activity_type IN (?1, ?2)
+                )
+            )",
+            params![
+                ActivityType::BackupCreated.as_str(),
+                ActivityType::BackupSynced.as_str()
+            ],
+        )?;
+    }
+
+    Ok(())
+}
+
+impl Database {
+    // ==================== MÉTODOS PARA SALUD DE PROYECTOS ====================
+
+    /// Datos de la base de datos que usa la puntuación de salud (ver `crate::health`)
+    pub fn get_health_signals(&self, project_id: i64) -> Result<HealthSignals> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.query_row(
+            "SELECT
+                CAST(julianday('now') - julianday(COALESCE(p.last_opened_at, p.created_at)) AS INTEGER),
+                p.last_opened_at IS NULL,
+                (SELECT COUNT(*) FROM project_todos t
+                 JOIN project_milestones m ON m.id = t.milestone_id
+                 WHERE t.project_id = p.id AND t.is_completed = 0 AND m.is_completed = 0
+                   AND m.due_date < date('now', 'localtime')),
+                CAST(julianday('now') - julianday(p.last_backup_at) AS INTEGER)
+             FROM projects p
+             WHERE p.id = ?1",
+            params![project_id],
+            |row| {
+                Ok(HealthSignals {
+                    days_since_open: row.get(0)?,
+                    never_opened: row.get(1)?,
+                    overdue_todos: row.get(2)?,
+                    days_since_backup: row.get(3)?,
+                })
+            },
+        )
+    }
+}
@@ -0,0 +1,338 @@
+use rusqlite::{params, Connection, OptionalExtension, Result, Row};
+use serde_json::json;
+
+use super::Database;
+use crate::links::{CachedMetadata, Favicon, LinkCheckResult};
+use crate::models::project::{
+    ActivityType, BrokenLink, CreateLinkDTO, LinkCheck, LinkSource, ProjectLink,
+};
+
+/// Enlaces con su favicon y el resultado de su última comprobación (alias `l`)
+pub(super) const LINK_SELECT: &str =
+    "SELECT l.id, l.project_id, l.link_type, l.title, l.url, l.created_at,
+            EXISTS (SELECT 1 FROM link_metadata m JOIN favicons f ON f.host = m.host
+                    WHERE m.url = l.url),
+            c.status_code, c.final_url, c.error, c.is_broken, c.checked_at
+     FROM project_links l
+     LEFT JOIN link_checks c ON c.url = l.url";
+
+/// Todas las URLs comprobables: enlaces del proyecto y campos de URL del proyecto
+const PROJECT_URLS: &str =
+    "SELECT project_id, 'link' AS source, id AS link_id, title, url FROM project_links
+     UNION ALL
+     SELECT id, 'documentation', NULL, 'Documentación', documentation_url FROM projects
+     UNION ALL
+     SELECT id, 'ai_documentation', NULL, 'Documentación IA', ai_documentation_url FROM projects
+     UNION ALL
+     SELECT id, 'drive', NULL, 'Google Drive', drive_link FROM projects";
+
+pub(super) fn init_schema(conn: &Connection) -> Result<()> {
+    // Resultado de la última comprobación de cada URL
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS link_checks (
+            url TEXT PRIMARY KEY,
+            status_code INTEGER,
+            final_url TEXT,
+            error TEXT,
+            is_broken BOOLEAN NOT NULL DEFAULT 0,
+            checked_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_link_checks_broken ON link_checks (is_broken)",
+        [],
+    )?;
+
+    // Caché de metadatos de las páginas enlazadas (título), para no descargarlas de nuevo
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS link_metadata (
+            url TEXT PRIMARY KEY,
+            host TEXT,
+            title TEXT,
+            error TEXT,
+            fetched_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    // Favicons por dominio
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS favicons (
+            host TEXT PRIMARY KEY,
+            mime_type TEXT NOT NULL,
+            data BLOB NOT NULL,
+            fetched_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+pub(super) fn link_from_row(row: &Row) -> Result<ProjectLink> {
+    Ok(ProjectLink {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        link_type: row.get(2)?,
+        title: row.get(3)?,
+        url: row.get(4)?,
+        created_at: row.get(5)?,
+        has_favicon: row.get(6)?,
+        check: check_from_row(row, 7)?,
+    })
+}
+
+/// Comprobación a partir de la columna `offset` (`status_code, final_url, error, is_broken, checked_at`)
+fn check_from_row(row: &Row, offset: usize) -> Result<Option<LinkCheck>> {
+    let Some(checked_at) = row.get::<_, Option<String>>(offset + 4)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(LinkCheck {
+        status_code: row.get(offset)?,
+        final_url: row.get(offset + 1)?,
+        error: row.get(offset + 2)?,
+        is_broken: row.get(offset + 3)?,
+        checked_at,
+    }))
+}
+
+impl Database {
+    /// Crea varios enlaces de un proyecto en una sola transacción, con una única
+    /// entrada de actividad. Los enlaces ya deben venir deduplicados.
+    pub fn import_links(&self, project_id: i64, links: &[CreateLinkDTO]) -> Result<usize> {
+        if links.is_empty() {
+            return Ok(0);
+        }
+
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO project_links (project_id, link_type, title, url)
+                 VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for link in links {
+                stmt.execute(params![project_id, link.link_type, link.title, link.url])?;
+            }
+        }
+
+        self.log_activity_internal(
+            &tx,
+            project_id,
+            ActivityType::LinksImported,
+            "Marcadores importados",
+            json!({ "count": links.len() }),
+        )?;
+
+        tx.commit()?;
+        Ok(links.len())
+    }
+
+    // ==================== MÉTODOS PARA COMPROBACIÓN DE ENLACES ====================
+
+    /// URLs pendientes de comprobar: nunca comprobadas o comprobadas hace más de
+    /// `older_than_hours` horas (0 para todas). Las más antiguas primero.
+    pub fn link_urls_to_check(&self, project_id: Option<i64>, older_than_hours: i64) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT u.url, MIN(c.checked_at)
+             FROM ({}) u
+             LEFT JOIN link_checks c ON c.url = u.url
+             WHERE TRIM(COALESCE(u.url, '')) <> ''
+               AND (?1 IS NULL OR u.project_id = ?1)
+               AND (c.checked_at IS NULL OR c.checked_at <= datetime('now', ?2))
+             GROUP BY u.url
+             ORDER BY MIN(c.checked_at) IS NOT NULL, MIN(c.checked_at)",
+            PROJECT_URLS
+        ))?;
+
+        let urls = stmt
+            .query_map(
+                params![project_id, format!("-{} hours", older_than_hours.max(0))],
+                |row| row.get(0),
+            )?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(urls)
+    }
+
+    pub fn save_link_check(&self, url: &str, result: &LinkCheckResult) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "INSERT INTO link_checks (url, status_code, final_url, error, is_broken, checked_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, CURRENT_TIMESTAMP)
+             ON CONFLICT(url) DO UPDATE SET
+                status_code = excluded.status_code,
+                final_url = excluded.final_url,
+                error = excluded.error,
+                is_broken = excluded.is_broken,
+                checked_at = excluded.checked_at",
+            params![url, result.status_code, result.final_url, result.error, result.is_broken],
+        )?;
+
+        Ok(())
+    }
+
+    /// Elimina las comprobaciones de URLs que ya no usa ningún proyecto
+    pub fn prune_link_checks(&self) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            &format!(
+                "DELETE FROM link_checks
+                 WHERE url NOT IN (SELECT url FROM ({}) WHERE url IS NOT NULL)",
+                PROJECT_URLS
+            ),
+            [],
+        )
+    }
+
+    /// Enlaces rotos de todos los proyectos según la última comprobación
+    pub fn get_broken_links(&self) -> Result<Vec<BrokenLink>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT u.project_id, p.name, u.source, u.link_id, u.title, u.url,
+                    c.status_code, c.final_url, c.error, c.is_broken, c.checked_at
+             FROM ({}) u
+             JOIN link_checks c ON c.url = u.url
+             JOIN projects p ON p.id = u.project_id
+             WHERE c.is_broken = 1
+             ORDER BY p.name COLLATE NOCASE, u.source, u.title",
+            PROJECT_URLS
+        ))?;
+
+        let links = stmt
+            .query_map([], |row| {
+                Ok(BrokenLink {
+                    project_id: row.get(0)?,
+                    project_name: row.get(1)?,
+                    source: LinkSource::parse(&row.get::<_, String>(2)?).unwrap_or(LinkSource::Link),
+                    link_id: row.get(3)?,
+                    title: row.get(4)?,
+                    url: row.get(5)?,
+                    check: check_from_row(row, 6)?.ok_or(rusqlite::Error::InvalidColumnType(
+                        10,
+                        "checked_at".to_string(),
+                        rusqlite::types::Type::Null,
+                    ))?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(links)
+    }
+
+    // ==================== MÉTODOS PARA METADATOS DE ENLACES ====================
+
+    pub fn get_link_metadata(&self, url: &str) -> Result<Option<CachedMetadata>> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.query_row(
+            "SELECT title, error, (julianday('now') - julianday(fetched_at)) * 24
+             FROM link_metadata WHERE url = ?1",
+            params![url],
+            |row| {
+                Ok(CachedMetadata {
+                    title: row.get(0)?,
+                    error: row.get(1)?,
+                    age_hours: row.get(2)?,
+                })
+            },
+        )
+        .optional()
+    }
+
+    pub fn save_link_metadata(
+        &self,
+        url: &str,
+        host: Option<&str>,
+        title: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        // Un fallo no borra el título obtenido en una descarga anterior
+        conn.execute(
+            "INSERT INTO link_metadata (url, host, title, error, fetched_at)
+             VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)
+             ON CONFLICT(url) DO UPDATE SET
+                host = excluded.host,
+                title = COALESCE(excluded.title, link_metadata.title),
+                error = excluded.error,
+                fetched_at = excluded.fetched_at",
+            params![url, host, title, error],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn has_favicon(&self, host: &str) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM favicons WHERE host = ?1)",
+            params![host],
+            |row| row.get(0),
+        )
+    }
+
+    pub fn save_favicon(&self, host: &str, favicon: &Favicon) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "INSERT INTO favicons (host, mime_type, data, fetched_at)
+             VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP)
+             ON CONFLICT(host) DO UPDATE SET
+                mime_type = excluded.mime_type,
+                data = excluded.data,
+                fetched_at = excluded.fetched_at",
+            params![host, favicon.mime_type, favicon.data],
+        )?;
+
+        Ok(())
+    }
+
+    /// Identifica el favicon de un enlace sin leerlo (caché del protocolo `gestor://`)
+    pub fn get_link_favicon_stamp(&self, link_id: i64) -> Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.query_row(
+            "SELECT f.host || '/' || f.fetched_at || '/' || length(f.data)
+             FROM project_links l
+             JOIN link_metadata m ON m.url = l.url
+             JOIN favicons f ON f.host = m.host
+             WHERE l.id = ?1",
+            params![link_id],
+            |row| row.get(0),
+        )
+        .optional()
+    }
+
+    /// Favicon del dominio de un enlace, para el protocolo `gestor://`
+    pub fn get_link_favicon(&self, link_id: i64) -> Result<Option<Favicon>> {
+        let conn = self.conn.lock().unwrap();
+
+        conn.query_row(
+            "SELECT f.mime_type, f.data
+             FROM project_links l
+             JOIN link_metadata m ON m.url = l.url
+             JOIN favicons f ON f.host = m.host
+             WHERE l.id = ?1",
+            params![link_id],
+            |row| {
+                Ok(Favicon {
+                    mime_type: row.get(0)?,
+                    data: row.get(1)?,
+                })
+            },
+        )
+        .optional()
+    }
+}
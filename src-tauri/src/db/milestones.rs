@@ -0,0 +1,342 @@
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, OptionalExtension, Result, Row, ToSql};
+use serde_json::json;
+
+use super::Database;
+use crate::models::project::{
+    ActivityType, CreateMilestoneDTO, ProjectMilestone, UpcomingMilestone, UpdateMilestoneDTO,
+};
+
+/// Columnas de `project_milestones` (alias `m`) con el progreso calculado desde los TODOs
+const MILESTONE_COLUMNS: &str =
+    "m.id, m.project_id, m.title, m.description, m.due_date, m.is_completed, m.completed_at,
+     m.created_at, m.updated_at,
+     (SELECT COUNT(*) FROM project_todos t WHERE t.milestone_id = m.id),
+     (SELECT COUNT(*) FROM project_todos t WHERE t.milestone_id = m.id AND t.is_completed = 1),
+     (m.is_completed = 0 AND m.due_date IS NOT NULL AND m.due_date < date('now', 'localtime'))";
+
+pub(super) fn init_schema(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS project_milestones (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            title TEXT NOT NULL,
+            description TEXT,
+            due_date TEXT,
+            is_completed BOOLEAN DEFAULT 0,
+            completed_at DATETIME,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Migración: vincular TODOs a un hito (ignorar si ya existe)
+    let _ = conn.execute(
+        "ALTER TABLE project_todos ADD COLUMN milestone_id INTEGER REFERENCES project_milestones (id)",
+        [],
+    );
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_project_todos_milestone_id ON project_todos (milestone_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_project_milestones_due_date
+         ON project_milestones (is_completed, due_date)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn milestone_from_row(row: &Row) -> Result<ProjectMilestone> {
+    let is_completed: bool = row.get(5)?;
+    let total_todos: i64 = row.get(9)?;
+    let completed_todos: i64 = row.get(10)?;
+
+    // Sin TODOs vinculados el progreso solo refleja si el hito está completado
+    let progress = if total_todos > 0 {
+        completed_todos as f64 / total_todos as f64
+    } else if is_completed {
+        1.0
+    } else {
+        0.0
+    };
+
+    Ok(ProjectMilestone {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        title: row.get(2)?,
+        description: row.get(3)?,
+        due_date: row.get(4)?,
+        is_completed,
+        completed_at: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+        total_todos,
+        completed_todos,
+        progress,
+        is_overdue: row.get(11)?,
+    })
+}
+
+/// Normaliza la fecha límite a YYYY-MM-DD; una cadena vacía significa "sin fecha"
+fn normalize_due_date(due_date: &str) -> Result<Option<String>> {
+    let due_date = due_date.trim();
+    if due_date.is_empty() {
+        return Ok(None);
+    }
+
+    // Se aceptan también fechas con hora (ej: "2025-03-01T00:00:00")
+    let date_part = due_date.get(..10).unwrap_or(due_date);
+    NaiveDate::parse_from_str(date_part, "%Y-%m-%d")
+        .map(|date| Some(date.format("%Y-%m-%d").to_string()))
+        .map_err(|_| {
+            rusqlite::Error::InvalidParameterName(format!(
+                "Fecha límite inválida '{}': se espera YYYY-MM-DD",
+                due_date
+            ))
+        })
+}
+
+fn validate_title(title: &str) -> Result<String> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(rusqlite::Error::InvalidParameterName(
+            "El título del hito no puede estar vacío".to_string(),
+        ));
+    }
+    Ok(title.to_string())
+}
+
+impl Database {
+    // ==================== MÉTODOS PARA HITOS ====================
+
+    pub fn create_milestone(&self, milestone: CreateMilestoneDTO) -> Result<ProjectMilestone> {
+        let title = validate_title(&milestone.title)?;
+        let due_date = match milestone.due_date.as_deref() {
+            Some(date) => normalize_due_date(date)?,
+            None => None,
+        };
+
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "INSERT INTO project_milestones (project_id, title, description, due_date)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![milestone.project_id, title, milestone.description, due_date],
+        )?;
+
+        let id = conn.last_insert_rowid();
+        self.log_activity_internal(
+            &conn,
+            milestone.project_id,
+            ActivityType::MilestoneCreated,
+            "Hito creado",
+            json!({ "milestone_id": id, "title": title, "due_date": due_date }),
+        )?;
+
+        Self::get_milestone_internal(&conn, id)
+    }
+
+    pub fn get_project_milestones(&self, project_id: i64) -> Result<Vec<ProjectMilestone>> {
+        let conn = self.conn.lock().unwrap();
+
+        // Pendientes primero, por fecha límite (las que no tienen fecha al final)
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM project_milestones m
+             WHERE m.project_id = ?1
+             ORDER BY m.is_completed ASC, m.due_date IS NULL, m.due_date ASC, m.created_at ASC",
+            MILESTONE_COLUMNS
+        ))?;
+
+        let milestones = stmt
+            .query_map(params![project_id], milestone_from_row)?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(milestones)
+    }
+
+    fn get_milestone_internal(conn: &Connection, id: i64) -> Result<ProjectMilestone> {
+        conn.query_row(
+            &format!("SELECT {} FROM project_milestones m WHERE m.id = ?1", MILESTONE_COLUMNS),
+            params![id],
+            milestone_from_row,
+        )
+    }
+
+    pub fn update_milestone(&self, id: i64, updates: UpdateMilestoneDTO) -> Result<ProjectMilestone> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut set_clauses = Vec::new();
+        let mut params: Vec<Box<dyn ToSql>> = Vec::new();
+
+        if let Some(title) = updates.title {
+            set_clauses.push("title = ?");
+            params.push(Box::new(validate_title(&title)?));
+        }
+
+        if let Some(description) = updates.description {
+            set_clauses.push("description = ?");
+            params.push(Box::new(description));
+        }
+
+        if let Some(due_date) = updates.due_date {
+            set_clauses.push("due_date = ?");
+            params.push(Box::new(normalize_due_date(&due_date)?));
+        }
+
+        if let Some(is_completed) = updates.is_completed {
+            set_clauses.push("is_completed = ?");
+            params.push(Box::new(is_completed));
+
+            if is_completed {
+                set_clauses.push("completed_at = COALESCE(completed_at, CURRENT_TIMESTAMP)");
+            } else {
+                set_clauses.push("completed_at = NULL");
+            }
+        }
+
+        if set_clauses.is_empty() {
+            return Err(rusqlite::Error::InvalidParameterCount(0, 1));
+        }
+
+        set_clauses.push("updated_at = CURRENT_TIMESTAMP");
+        params.push(Box::new(id));
+
+        let query = format!(
+            "UPDATE project_milestones SET {} WHERE id = ?",
+            set_clauses.join(", ")
+        );
+
+        let before = Self::get_milestone_internal(&conn, id)?;
+
+        let params_ref: Vec<&dyn ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        conn.execute(&query, params_ref.as_slice())?;
+
+        let after = Self::get_milestone_internal(&conn, id)?;
+
+        let (activity_type, description) = if !before.is_completed && after.is_completed {
+            (ActivityType::MilestoneCompleted, "Hito completado")
+        } else {
+            (ActivityType::MilestoneUpdated, "Hito actualizado")
+        };
+        self.log_activity_internal(
+            &conn,
+            after.project_id,
+            activity_type,
+            description,
+            json!({
+                "milestone_id": id,
+                "title": after.title,
+                "due_date": after.due_date,
+                "is_completed": after.is_completed,
+            }),
+        )?;
+
+        Ok(after)
+    }
+
+    /// Elimina el hito y sus recordatorios; sus TODOs se conservan pero quedan sin vincular
+    pub fn delete_milestone(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+
+        let milestone: Option<(i64, String)> = tx
+            .query_row(
+                "SELECT project_id, title FROM project_milestones WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        tx.execute(
+            "UPDATE project_todos SET milestone_id = NULL WHERE milestone_id = ?1",
+            params![id],
+        )?;
+        tx.execute("DELETE FROM reminders WHERE milestone_id = ?1", params![id])?;
+        tx.execute("DELETE FROM project_milestones WHERE id = ?1", params![id])?;
+
+        if let Some((project_id, title)) = milestone {
+            self.log_activity_internal(
+                &tx,
+                project_id,
+                ActivityType::MilestoneDeleted,
+                "Hito eliminado",
+                json!({ "milestone_id": id, "title": title }),
+            )?;
+        }
+
+        tx.commit()
+    }
+
+    /// Vincula (o desvincula con `None`) un TODO a un hito del mismo proyecto
+    pub fn set_todo_milestone(&self, todo_id: i64, milestone_id: Option<i64>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        if let Some(milestone_id) = milestone_id {
+            let same_project: bool = conn.query_row(
+                "SELECT EXISTS (
+                    SELECT 1 FROM project_todos t
+                    JOIN project_milestones m ON m.project_id = t.project_id
+                    WHERE t.id = ?1 AND m.id = ?2
+                )",
+                params![todo_id, milestone_id],
+                |row| row.get(0),
+            )?;
+
+            if !same_project {
+                return Err(rusqlite::Error::InvalidParameterName(
+                    "El TODO y el hito deben pertenecer al mismo proyecto".to_string(),
+                ));
+            }
+        }
+
+        let project_id: i64 = conn
+            .prepare_cached(
+                "UPDATE project_todos SET milestone_id = ?1 WHERE id = ?2 RETURNING project_id",
+            )?
+            .query_row(params![milestone_id, todo_id], |row| row.get(0))?;
+
+        self.log_activity_internal(
+            &conn,
+            project_id,
+            ActivityType::TodoUpdated,
+            "TODO vinculado a hito",
+            json!({ "todo_id": todo_id, "milestone_id": milestone_id }),
+        )?;
+
+        Ok(())
+    }
+
+    /// Hitos pendientes de todos los proyectos: los vencidos y los que vencen en los próximos `days_ahead` días
+    pub fn get_upcoming_milestones(&self, days_ahead: i64) -> Result<Vec<UpcomingMilestone>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {}, p.name,
+                    CAST(julianday(m.due_date) - julianday(date('now', 'localtime')) AS INTEGER)
+             FROM project_milestones m
+             JOIN projects p ON p.id = m.project_id
+             WHERE m.is_completed = 0
+               AND m.due_date IS NOT NULL
+               AND m.due_date <= date('now', 'localtime', ?1)
+             ORDER BY m.due_date ASC, p.name COLLATE NOCASE",
+            MILESTONE_COLUMNS
+        ))?;
+
+        let milestones = stmt
+            .query_map(params![format!("+{} days", days_ahead.max(0))], |row| {
+                Ok(UpcomingMilestone {
+                    milestone: milestone_from_row(row)?,
+                    project_name: row.get(12)?,
+                    days_until_due: row.get(13)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(milestones)
+    }
+}
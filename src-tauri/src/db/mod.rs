@@ -1,17 +1,62 @@
-use rusqlite::{params, Connection, Result};
+use rusqlite::{params, Connection, OptionalExtension, Result, Row};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
 use std::sync::Mutex;
-
-use crate::models::project::{CreateProjectDTO, CreateLinkDTO, Project, ProjectLink, UpdateProjectDTO, UpdateLinkDTO, ProjectAttachment, CreateAttachmentDTO, JournalEntry, CreateJournalEntryDTO, UpdateJournalEntryDTO, ProjectTodo, CreateTodoDTO, UpdateTodoDTO};
+use serde_json::json;
+
+mod activity;
+mod archive;
+mod attachments;
+mod board;
+mod health;
+mod links;
+mod milestones;
+mod relations;
+mod reminders;
+mod search;
+mod smart_views;
+#[cfg(test)]
+pub(crate) mod test_support;
+
+use crate::images::{self, ProcessedImage};
+use crate::models::project::{ActivityType, CreateProjectDTO, CreateLinkDTO, Project, ProjectLink, UpdateProjectDTO, UpdateLinkDTO, JournalEntry, CreateJournalEntryDTO, UpdateJournalEntryDTO, ProjectTodo, CreateTodoDTO, UpdateTodoDTO};
+
+/// Columnas de `projects` para lecturas individuales (incluye la imagen completa y la miniatura)
+const PROJECT_COLUMNS: &str =
+    "id, name, description, local_path, documentation_url, ai_documentation_url, drive_link, notes,
+     (SELECT image_data FROM project_images WHERE project_id = projects.id),
+     created_at, updated_at, last_opened_at, opened_count, total_time_seconds,
+     status, status_changed_at, is_pinned, pinned_order,
+     EXISTS (SELECT 1 FROM project_images WHERE project_id = projects.id),
+     (SELECT NULLIF(thumbnail_data, '') FROM project_images WHERE project_id = projects.id)";
+
+/// Columnas de `projects` para listados: solo la miniatura, la imagen completa se omite
+const PROJECT_LIST_COLUMNS: &str =
+    "id, name, description, local_path, documentation_url, ai_documentation_url, drive_link, notes,
+     NULL,
+     created_at, updated_at, last_opened_at, opened_count, total_time_seconds,
+     status, status_changed_at, is_pinned, pinned_order,
+     EXISTS (SELECT 1 FROM project_images WHERE project_id = projects.id),
+     (SELECT NULLIF(thumbnail_data, '') FROM project_images WHERE project_id = projects.id)";
+
+const TODO_COLUMNS: &str =
+    "id, project_id, content, is_completed, created_at, completed_at, milestone_id, column_id, position";
 
 pub struct Database {
     conn: Mutex<Connection>,
+    /// Refleja `AdvancedConfig.enable_analytics`; si está desactivado no se registra actividad
+    analytics_enabled: AtomicBool,
 }
 
 impl Database {
     pub fn new(db_path: PathBuf) -> Result<Self> {
         let conn = Connection::open(db_path)?;
 
+        // Las FOREIGN KEY (y sus ON DELETE CASCADE) dependen de esta opción de la conexión;
+        // el SQLite incluido la activa por defecto, pero no hay que contar con ello
+        conn.execute_batch("PRAGMA foreign_keys = ON")?;
+
         conn.execute(
             "CREATE TABLE IF NOT EXISTS projects (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -67,8 +112,7 @@ impl Database {
                 activity_type TEXT NOT NULL,
                 description TEXT,
                 duration_seconds INTEGER,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
             )",
             [],
         )?;
@@ -151,17 +195,134 @@ impl Database {
             [],
         )?;
 
+        // Las imágenes viven en su propia tabla para que los listados no lean los blobs
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS project_images (
+                project_id INTEGER PRIMARY KEY,
+                image_data TEXT NOT NULL,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // Migración: mover imágenes guardadas en la columna antigua projects.image_data
+        conn.execute(
+            "INSERT OR IGNORE INTO project_images (project_id, image_data)
+             SELECT id, image_data FROM projects
+             WHERE image_data IS NOT NULL AND image_data != ''",
+            [],
+        )?;
+        conn.execute(
+            "UPDATE projects SET image_data = NULL WHERE image_data IS NOT NULL",
+            [],
+        )?;
+
+        // Migración: miniaturas para los listados (ignorar si ya existe)
+        let _ = conn.execute("ALTER TABLE project_images ADD COLUMN thumbnail_data TEXT", []);
+        Self::generate_missing_thumbnails(&conn)?;
+
+        // Índice para la carga de enlaces por proyecto
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_project_links_project_id
+             ON project_links (project_id, created_at)",
+            [],
+        )?;
+
+        smart_views::init_schema(&conn)?;
+        relations::init_schema(&conn)?;
+        milestones::init_schema(&conn)?;
+        reminders::init_schema(&conn)?;
+        board::init_schema(&conn)?;
+        activity::init_schema(&conn)?;
+        health::init_schema(&conn)?;
+        attachments::init_schema(&conn)?;
+        links::init_schema(&conn)?;
+
         Ok(Database {
             conn: Mutex::new(conn),
+            analytics_enabled: AtomicBool::new(true),
         })
     }
 
+    /// Construye un `Project` a partir de una fila seleccionada con
+    /// `PROJECT_COLUMNS` o `PROJECT_LIST_COLUMNS`
+    fn project_from_row(row: &Row) -> Result<Project> {
+        Ok(Project {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            description: row.get(2)?,
+            local_path: row.get(3)?,
+            documentation_url: row.get(4)?,
+            ai_documentation_url: row.get(5)?,
+            drive_link: row.get(6)?,
+            notes: row.get(7)?,
+            image_data: row.get(8)?,
+            has_image: row.get(18)?,
+            thumbnail_data: row.get(19)?,
+            links: None, // Los enlaces se cargan por separado
+            created_at: row.get(9)?,
+            updated_at: row.get(10)?,
+            last_opened_at: row.get(11)?,
+            opened_count: row.get(12)?,
+            total_time_seconds: row.get(13)?,
+            status: row.get(14)?,
+            status_changed_at: row.get(15)?,
+            is_pinned: row.get(16)?,
+            pinned_order: row.get(17)?,
+        })
+    }
+
+    /// Carga en una sola consulta los enlaces de varios proyectos y los agrupa por proyecto
+    fn get_links_for_projects(conn: &Connection, project_ids: &[i64]) -> Result<HashMap<i64, Vec<ProjectLink>>> {
+        let mut links_by_project: HashMap<i64, Vec<ProjectLink>> = HashMap::new();
+        if project_ids.is_empty() {
+            return Ok(links_by_project);
+        }
+
+        let ids_json = serde_json::to_string(project_ids)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "{} WHERE l.project_id IN (SELECT value FROM json_each(?1))
+             ORDER BY l.project_id, l.created_at DESC",
+            links::LINK_SELECT
+        ))?;
+
+        let rows = stmt.query_map(params![ids_json], links::link_from_row)?;
+
+        for link in rows {
+            let link = link?;
+            links_by_project.entry(link.project_id).or_default().push(link);
+        }
+
+        Ok(links_by_project)
+    }
+
+    /// Adjunta los enlaces a una lista de proyectos usando una única consulta
+    fn attach_links(conn: &Connection, projects: &mut [Project]) -> Result<()> {
+        let ids: Vec<i64> = projects.iter().map(|p| p.id).collect();
+        let mut links_by_project = Self::get_links_for_projects(conn, &ids)?;
+
+        for project in projects.iter_mut() {
+            project.links = Some(links_by_project.remove(&project.id).unwrap_or_default());
+        }
+
+        Ok(())
+    }
+
     pub fn create_project(&self, project: CreateProjectDTO) -> Result<Project> {
+        // Procesar la imagen antes de bloquear la conexión
+        let image = match project.image_data.as_deref() {
+            Some(image_data) => Self::process_project_image(image_data)?,
+            None => None,
+        };
+
         let conn = self.conn.lock().unwrap();
 
         conn.execute(
-            "INSERT INTO projects (name, description, local_path, documentation_url, ai_documentation_url, drive_link, notes, image_data)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT INTO projects (name, description, local_path, documentation_url, ai_documentation_url, drive_link, notes)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
             params![
                 project.name,
                 project.description,
@@ -169,41 +330,28 @@ impl Database {
                 project.documentation_url,
                 project.ai_documentation_url,
                 project.drive_link,
-                project.notes,
-                project.image_data
+                project.notes
             ],
         )?;
 
         let id = conn.last_insert_rowid();
 
+        if let Some(image) = &image {
+            Self::set_project_image_internal(&conn, id, Some(image))?;
+        }
+
+        self.log_activity_internal(
+            &conn,
+            id,
+            ActivityType::ProjectCreated,
+            "Proyecto creado",
+            json!({ "name": project.name, "local_path": project.local_path }),
+        )?;
+
         let project = conn.query_row(
-            "SELECT id, name, description, local_path, documentation_url, ai_documentation_url, drive_link, notes, image_data,
-                    created_at, updated_at, last_opened_at, opened_count, total_time_seconds,
-                    status, status_changed_at, is_pinned, pinned_order FROM projects WHERE id = ?1",
+            &format!("SELECT {} FROM projects WHERE id = ?1", PROJECT_COLUMNS),
             params![id],
-            |row| {
-                Ok(Project {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    description: row.get(2)?,
-                    local_path: row.get(3)?,
-                    documentation_url: row.get(4)?,
-                    ai_documentation_url: row.get(5)?,
-                    drive_link: row.get(6)?,
-                    notes: row.get(7)?,
-                    image_data: row.get(8)?,
-                    links: None, // Los enlaces se cargan por separado
-                    created_at: row.get(9)?,
-                    updated_at: row.get(10)?,
-                    last_opened_at: row.get(11)?,
-                    opened_count: row.get(12)?,
-                    total_time_seconds: row.get(13)?,
-                    status: row.get(14)?,
-                    status_changed_at: row.get(15)?,
-                    is_pinned: row.get(16)?,
-                    pinned_order: row.get(17)?,
-                })
-            },
+            Self::project_from_row,
         )?;
 
         Ok(project)
@@ -212,65 +360,17 @@ impl Database {
     pub fn get_all_projects(&self) -> Result<Vec<Project>> {
         let conn = self.conn.lock().unwrap();
 
-        let mut stmt = conn.prepare(
-            "SELECT id, name, description, local_path, documentation_url, ai_documentation_url, drive_link, notes, image_data,
-                    created_at, updated_at, last_opened_at, opened_count, total_time_seconds,
-                    status, status_changed_at, is_pinned, pinned_order
-             FROM projects
-             ORDER BY is_pinned DESC, pinned_order ASC, updated_at DESC"
-        )?;
-
-        let mut projects = Vec::new();
-        let project_rows = stmt.query_map([], |row| {
-            Ok((
-                row.get::<_, i64>(0)?,  // id
-                row.get::<_, String>(1)?,  // name
-                row.get::<_, String>(2)?,  // description
-                row.get::<_, String>(3)?,  // local_path
-                row.get::<_, Option<String>>(4)?,  // documentation_url
-                row.get::<_, Option<String>>(5)?,  // ai_documentation_url
-                row.get::<_, Option<String>>(6)?,  // drive_link
-                row.get::<_, Option<String>>(7)?,  // notes
-                row.get::<_, Option<String>>(8)?,  // image_data
-                row.get::<_, String>(9)?,  // created_at
-                row.get::<_, String>(10)?,  // updated_at
-                row.get::<_, Option<String>>(11)?,  // last_opened_at
-                row.get::<_, Option<i64>>(12)?,  // opened_count
-                row.get::<_, Option<i64>>(13)?,  // total_time_seconds
-                row.get::<_, Option<String>>(14)?,  // status
-                row.get::<_, Option<String>>(15)?,  // status_changed_at
-                row.get::<_, Option<bool>>(16)?,  // is_pinned
-                row.get::<_, Option<i64>>(17)?,  // pinned_order
-            ))
-        })?
-        .collect::<Result<Vec<_>>>()?;
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM projects
+             ORDER BY is_pinned DESC, pinned_order ASC, updated_at DESC",
+            PROJECT_LIST_COLUMNS
+        ))?;
 
-        // Para cada proyecto, obtener sus enlaces
-        for (id, name, description, local_path, documentation_url, ai_documentation_url, drive_link, notes, image_data, created_at, updated_at, last_opened_at, opened_count, total_time_seconds, status, status_changed_at, is_pinned, pinned_order) in project_rows {
-            let links = self.get_project_links_internal(id, &conn).unwrap_or_else(|_| Vec::new());
+        let mut projects = stmt
+            .query_map([], Self::project_from_row)?
+            .collect::<Result<Vec<_>>>()?;
 
-            projects.push(Project {
-                id,
-                name,
-                description,
-                local_path,
-                documentation_url,
-                ai_documentation_url,
-                drive_link,
-                notes,
-                image_data,
-                links: Some(links),
-                created_at,
-                updated_at,
-                last_opened_at,
-                opened_count,
-                total_time_seconds,
-                status,
-                status_changed_at,
-                is_pinned,
-                pinned_order,
-            });
-        }
+        Self::attach_links(&conn, &mut projects)?;
 
         Ok(projects)
     }
@@ -293,38 +393,16 @@ impl Database {
         println!("🔒 [DB] Conexión obtenida exitosamente");
 
         let result = conn.query_row(
-            "SELECT id, name, description, local_path, documentation_url, ai_documentation_url, drive_link, notes, image_data,
-                    created_at, updated_at, last_opened_at, opened_count, total_time_seconds,
-                    status, status_changed_at, is_pinned, pinned_order FROM projects WHERE id = ?1",
+            &format!("SELECT {} FROM projects WHERE id = ?1", PROJECT_COLUMNS),
             params![id],
             |row| {
                 println!("📊 [DB] Leyendo fila de base de datos...");
-                let project_id = row.get::<_, i64>(0)?;
+                let mut project = Self::project_from_row(row)?;
 
                 // Obtener enlaces del proyecto
-                let links = self.get_project_links_internal(project_id, &conn).unwrap_or_else(|_| Vec::new());
-
-                let project = Project {
-                    id: project_id,
-                    name: row.get(1)?,
-                    description: row.get(2)?,
-                    local_path: row.get(3)?,
-                    documentation_url: row.get(4)?,
-                    ai_documentation_url: row.get(5)?,
-                    drive_link: row.get(6)?,
-                    notes: row.get(7)?,
-                    image_data: row.get(8)?,
-                    links: Some(links),
-                    created_at: row.get(9)?,
-                    updated_at: row.get(10)?,
-                    last_opened_at: row.get(11)?,
-                    opened_count: row.get(12)?,
-                    total_time_seconds: row.get(13)?,
-                    status: row.get(14)?,
-                    status_changed_at: row.get(15)?,
-                    is_pinned: row.get(16)?,
-                    pinned_order: row.get(17)?,
-                };
+                let links = self.get_project_links_internal(project.id, &conn).unwrap_or_else(|_| Vec::new());
+                project.links = Some(links);
+
                 println!("✅ [DB] Proyecto leído de BD: '{}'", project.name);
                 Ok(project)
             },
@@ -344,6 +422,12 @@ impl Database {
 
     pub fn update_project(&self, id: i64, updates: UpdateProjectDTO) -> Result<Project> {
         println!("🗄️ [DB] Iniciando update_project en base de datos para ID: {}", id);
+
+        // Procesar la imagen antes de bloquear la conexión
+        let image = match updates.image_data.as_deref() {
+            Some(image_data) => Some(Self::process_project_image(image_data)?),
+            None => None,
+        };
         
         // Intentar obtener la conexión con timeout
         let conn = match self.conn.try_lock() {
@@ -390,10 +474,10 @@ impl Database {
             query_parts.push("notes = ?");
             params.push(Box::new(notes));
         }
-        if let Some(image_data) = updates.image_data {
-            query_parts.push("image_data = ?");
-            params.push(Box::new(image_data));
-        }
+        let mut changed_fields: Vec<&str> = query_parts
+            .iter()
+            .filter_map(|part| part.split(' ').next())
+            .collect();
 
         query_parts.push("updated_at = CURRENT_TIMESTAMP");
         params.push(Box::new(id));
@@ -407,8 +491,11 @@ impl Database {
         println!("🗄️ [DB] Número de parámetros: {}", params.len());
 
         let params_ref: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
-        
-        match conn.execute(&query, params_ref.as_slice()) {
+
+        // Campos, imagen y actividad se aplican juntos o no se aplica nada
+        let tx = conn.unchecked_transaction()?;
+
+        match tx.execute(&query, params_ref.as_slice()) {
             Ok(rows_affected) => {
                 println!("🗄️ [DB] UPDATE ejecutado exitosamente, filas afectadas: {}", rows_affected);
             }
@@ -418,6 +505,23 @@ impl Database {
             }
         }
 
+        if let Some(image) = &image {
+            Self::set_project_image_internal(&tx, id, image.as_ref())?;
+            changed_fields.push("image");
+        }
+
+        if !changed_fields.is_empty() {
+            self.log_activity_internal(
+                &tx,
+                id,
+                ActivityType::ProjectUpdated,
+                "Proyecto editado",
+                json!({ "fields": changed_fields }),
+            )?;
+        }
+
+        tx.commit()?;
+
         // Liberar la conexión antes de llamar a get_project
         println!("🔓 [DB] Liberando conexión después del UPDATE");
         drop(conn); // Liberar explícitamente la conexión
@@ -437,75 +541,107 @@ impl Database {
 
     pub fn delete_project(&self, id: i64) -> Result<()> {
         let conn = self.conn.lock().unwrap();
+
+        // El registro se conserva en el historial aunque el proyecto desaparezca
+        let name: Option<String> = conn
+            .query_row("SELECT name FROM projects WHERE id = ?1", params![id], |row| row.get(0))
+            .optional()?;
+        if let Some(name) = name {
+            self.log_activity_internal(
+                &conn,
+                id,
+                ActivityType::ProjectDeleted,
+                "Proyecto eliminado",
+                json!({ "name": name }),
+            )?;
+        }
+        // Imágenes, enlaces, TODOs, relaciones, hitos, recordatorios... se borran en cascada
         conn.execute("DELETE FROM projects WHERE id = ?1", params![id])?;
         Ok(())
     }
 
-    pub fn search_projects(&self, query: &str) -> Result<Vec<Project>> {
+    /// Obtener solo la imagen de un proyecto (los listados no la incluyen)
+    pub fn get_project_image(&self, id: i64) -> Result<Option<String>> {
         let conn = self.conn.lock().unwrap();
-        let search_pattern = format!("%{}%", query);
 
-        let mut stmt = conn.prepare(
-            "SELECT id, name, description, local_path, documentation_url, ai_documentation_url, drive_link, notes, image_data,
-                    created_at, updated_at, last_opened_at, opened_count, total_time_seconds,
-                    status, status_changed_at, is_pinned, pinned_order FROM projects
-             WHERE name LIKE ?1 OR description LIKE ?1 OR local_path LIKE ?1 OR notes LIKE ?1
-             ORDER BY is_pinned DESC, pinned_order ASC, updated_at DESC"
-        )?;
+        conn.query_row(
+            "SELECT image_data FROM project_images WHERE project_id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+        .optional()
+    }
 
-        let mut projects = Vec::new();
-        let project_rows = stmt.query_map(params![search_pattern], |row| {
-            Ok((
-                row.get::<_, i64>(0)?,  // id
-                row.get::<_, String>(1)?,  // name
-                row.get::<_, String>(2)?,  // description
-                row.get::<_, String>(3)?,  // local_path
-                row.get::<_, Option<String>>(4)?,  // documentation_url
-                row.get::<_, Option<String>>(5)?,  // ai_documentation_url
-                row.get::<_, Option<String>>(6)?,  // drive_link
-                row.get::<_, Option<String>>(7)?,  // notes
-                row.get::<_, Option<String>>(8)?,  // image_data
-                row.get::<_, String>(9)?,  // created_at
-                row.get::<_, String>(10)?,  // updated_at
-                row.get::<_, Option<String>>(11)?,  // last_opened_at
-                row.get::<_, Option<i64>>(12)?,  // opened_count
-                row.get::<_, Option<i64>>(13)?,  // total_time_seconds
-                row.get::<_, Option<String>>(14)?,  // status
-                row.get::<_, Option<String>>(15)?,  // status_changed_at
-                row.get::<_, Option<bool>>(16)?,  // is_pinned
-                row.get::<_, Option<i64>>(17)?,  // pinned_order
-            ))
-        })?
-        .collect::<Result<Vec<_>>>()?;
+    /// Identifica la imagen de un proyecto sin leerla (caché del protocolo `gestor://`)
+    pub fn get_project_image_stamp(&self, id: i64) -> Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
 
-        // Para cada proyecto, obtener sus enlaces
-        for (id, name, description, local_path, documentation_url, ai_documentation_url, drive_link, notes, image_data, created_at, updated_at, last_opened_at, opened_count, total_time_seconds, status, status_changed_at, is_pinned, pinned_order) in project_rows {
-            let links = self.get_project_links_internal(id, &conn).unwrap_or_else(|_| Vec::new());
+        conn.query_row(
+            "SELECT COALESCE(updated_at, '') || '/' || octet_length(image_data)
+             FROM project_images WHERE project_id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+        .optional()
+    }
 
-            projects.push(Project {
-                id,
-                name,
-                description,
-                local_path,
-                documentation_url,
-                ai_documentation_url,
-                drive_link,
-                notes,
-                image_data,
-                links: Some(links),
-                created_at,
-                updated_at,
-                last_opened_at,
-                opened_count,
-                total_time_seconds,
-                status,
-                status_changed_at,
-                is_pinned,
-                pinned_order,
-            });
+    /// Valida la imagen recibida y genera su miniatura; una cadena vacía significa eliminarla
+    fn process_project_image(image_data: &str) -> Result<Option<ProcessedImage>> {
+        if image_data.trim().is_empty() {
+            return Ok(None);
         }
+        images::process_image(image_data)
+            .map(Some)
+            .map_err(rusqlite::Error::InvalidParameterName)
+    }
 
-        Ok(projects)
+    /// Guarda o elimina (con `None`) la imagen de un proyecto
+    fn set_project_image_internal(
+        conn: &Connection,
+        project_id: i64,
+        image: Option<&ProcessedImage>,
+    ) -> Result<()> {
+        match image {
+            None => {
+                conn.execute("DELETE FROM project_images WHERE project_id = ?1", params![project_id])?;
+            }
+            Some(image) => {
+                conn.execute(
+                    "INSERT INTO project_images (project_id, image_data, thumbnail_data) VALUES (?1, ?2, ?3)
+                     ON CONFLICT(project_id) DO UPDATE SET
+                        image_data = excluded.image_data,
+                        thumbnail_data = excluded.thumbnail_data,
+                        updated_at = CURRENT_TIMESTAMP",
+                    params![project_id, image.original, image.thumbnail],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Genera las miniaturas de imágenes guardadas antes de existir las miniaturas.
+    /// Las imágenes que no se pueden leer quedan con miniatura vacía para no reintentarlo.
+    fn generate_missing_thumbnails(conn: &Connection) -> Result<()> {
+        let pending = conn
+            .prepare("SELECT project_id, image_data FROM project_images WHERE thumbnail_data IS NULL")?
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<_>>>()?;
+
+        for (project_id, image_data) in pending {
+            let thumbnail = match images::process_image(&image_data) {
+                Ok(image) => image.thumbnail,
+                Err(e) => {
+                    println!("⚠️ [DB] Imagen inválida del proyecto {}: {}", project_id, e);
+                    String::new()
+                }
+            };
+            conn.execute(
+                "UPDATE project_images SET thumbnail_data = ?1 WHERE project_id = ?2",
+                params![thumbnail, project_id],
+            )?;
+        }
+
+        Ok(())
     }
 
     // Métodos para manejar enlaces de proyectos
@@ -520,20 +656,19 @@ impl Database {
 
         let id = conn.last_insert_rowid();
         
-        let mut stmt = conn.prepare(
-            "SELECT id, project_id, link_type, title, url, created_at FROM project_links WHERE id = ?1"
+        let link = conn.query_row(
+            &format!("{} WHERE l.id = ?1", links::LINK_SELECT),
+            params![id],
+            links::link_from_row,
+        )?;
+
+        self.log_activity_internal(
+            &conn,
+            link.project_id,
+            ActivityType::LinkAdded,
+            "Enlace añadido",
+            json!({ "link_id": link.id, "title": link.title, "url": link.url }),
         )?;
-        
-        let link = stmt.query_row(params![id], |row| {
-            Ok(ProjectLink {
-                id: row.get(0)?,
-                project_id: row.get(1)?,
-                link_type: row.get(2)?,
-                title: row.get(3)?,
-                url: row.get(4)?,
-                created_at: row.get(5)?,
-            })
-        })?;
 
         Ok(link)
     }
@@ -544,22 +679,13 @@ impl Database {
     }
 
     fn get_project_links_internal(&self, project_id: i64, conn: &Connection) -> Result<Vec<ProjectLink>> {
-        let mut stmt = conn.prepare(
-            "SELECT id, project_id, link_type, title, url, created_at FROM project_links 
-             WHERE project_id = ?1 ORDER BY created_at DESC"
-        )?;
+        let mut stmt = conn.prepare_cached(&format!(
+            "{} WHERE l.project_id = ?1 ORDER BY l.created_at DESC",
+            links::LINK_SELECT
+        ))?;
 
         let links = stmt
-            .query_map(params![project_id], |row| {
-                Ok(ProjectLink {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    link_type: row.get(2)?,
-                    title: row.get(3)?,
-                    url: row.get(4)?,
-                    created_at: row.get(5)?,
-                })
-            })?
+            .query_map(params![project_id], links::link_from_row)?
             .collect::<Result<Vec<_>>>()?;
 
         Ok(links)
@@ -605,27 +731,46 @@ impl Database {
         conn.execute(&query, &*params)?;
 
         // Obtener el enlace actualizado
-        let mut stmt = conn.prepare(
-            "SELECT id, project_id, link_type, title, url, created_at FROM project_links WHERE id = ?1"
+        let updated_link = conn.query_row(
+            &format!("{} WHERE l.id = ?1", links::LINK_SELECT),
+            params![id],
+            links::link_from_row,
+        )?;
+
+        self.log_activity_internal(
+            &conn,
+            updated_link.project_id,
+            ActivityType::LinkUpdated,
+            "Enlace editado",
+            json!({ "link_id": id, "title": updated_link.title, "url": updated_link.url }),
         )?;
-        
-        let updated_link = stmt.query_row(params![id], |row| {
-            Ok(ProjectLink {
-                id: row.get(0)?,
-                project_id: row.get(1)?,
-                link_type: row.get(2)?,
-                title: row.get(3)?,
-                url: row.get(4)?,
-                created_at: row.get(5)?,
-            })
-        })?;
 
         Ok(updated_link)
     }
 
     pub fn delete_link(&self, id: i64) -> Result<()> {
         let conn = self.conn.lock().unwrap();
+
+        let link: Option<(i64, String, String)> = conn
+            .query_row(
+                "SELECT project_id, title, url FROM project_links WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+
         conn.execute("DELETE FROM project_links WHERE id = ?1", params![id])?;
+
+        if let Some((project_id, title, url)) = link {
+            self.log_activity_internal(
+                &conn,
+                project_id,
+                ActivityType::LinkRemoved,
+                "Enlace eliminado",
+                json!({ "link_id": id, "title": title, "url": url }),
+            )?;
+        }
+
         Ok(())
     }
 
@@ -642,16 +787,23 @@ impl Database {
         )?;
 
         // Crear registro de actividad
-        conn.execute(
-            "INSERT INTO project_activity (project_id, activity_type, description)
-             VALUES (?1, 'opened', 'Proyecto abierto')",
-            params![id],
+        self.log_activity_internal(
+            &conn,
+            id,
+            ActivityType::Opened,
+            "Proyecto abierto",
+            serde_json::Value::Null,
         )?;
 
         Ok(())
     }
 
     pub fn add_project_time(&self, id: i64, seconds: i64) -> Result<()> {
+        // El tiempo registrado es solo analítica
+        if !self.analytics_enabled() {
+            return Ok(());
+        }
+
         let conn = self.conn.lock().unwrap();
 
         conn.execute(
@@ -661,11 +813,19 @@ impl Database {
             params![id, seconds],
         )?;
 
+        if seconds > 0 {
+            conn.execute(
+                "INSERT INTO project_activity (project_id, activity_type, description, duration_seconds)
+                 VALUES (?1, ?2, 'Tiempo registrado', ?3)",
+                params![id, ActivityType::TimeTracked.as_str(), seconds],
+            )?;
+        }
+
         Ok(())
     }
 
     pub fn get_project_stats(&self) -> Result<crate::models::project::ProjectStats> {
-        use crate::models::project::{ProjectStats, ProjectActivity};
+        use crate::models::project::ProjectStats;
 
         let conn = self.conn.lock().unwrap();
 
@@ -702,23 +862,14 @@ impl Database {
         ).ok();
 
         // Actividades recientes (últimas 20)
-        let mut stmt = conn.prepare(
-            "SELECT id, project_id, activity_type, description, duration_seconds, created_at
-             FROM project_activity
-             ORDER BY created_at DESC
-             LIMIT 20"
-        )?;
-
-        let activities = stmt.query_map([], |row| {
-            Ok(ProjectActivity {
-                id: row.get(0)?,
-                project_id: row.get(1)?,
-                activity_type: row.get(2)?,
-                description: row.get(3)?,
-                duration_seconds: row.get(4)?,
-                created_at: row.get(5)?,
-            })
-        })?
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM project_activity a
+             ORDER BY a.created_at DESC
+             LIMIT 20",
+            activity::ACTIVITY_COLUMNS
+        ))?;
+
+        let activities = stmt.query_map([], activity::activity_from_row)?
         .collect::<Result<Vec<_>>>()?;
 
         Ok(ProjectStats {
@@ -731,114 +882,22 @@ impl Database {
     }
 
     pub fn get_project_activities(&self, project_id: i64, limit: i64) -> Result<Vec<crate::models::project::ProjectActivity>> {
-        use crate::models::project::ProjectActivity;
-
         let conn = self.conn.lock().unwrap();
 
-        let mut stmt = conn.prepare(
-            "SELECT id, project_id, activity_type, description, duration_seconds, created_at
-             FROM project_activity
-             WHERE project_id = ?1
-             ORDER BY created_at DESC
-             LIMIT ?2"
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM project_activity a
+             WHERE a.project_id = ?1
+             ORDER BY a.created_at DESC
+             LIMIT ?2",
+            activity::ACTIVITY_COLUMNS
+        ))?;
 
-        let activities = stmt.query_map(params![project_id, limit], |row| {
-            Ok(ProjectActivity {
-                id: row.get(0)?,
-                project_id: row.get(1)?,
-                activity_type: row.get(2)?,
-                description: row.get(3)?,
-                duration_seconds: row.get(4)?,
-                created_at: row.get(5)?,
-            })
-        })?
+        let activities = stmt.query_map(params![project_id, limit], activity::activity_from_row)?
         .collect::<Result<Vec<_>>>()?;
 
         Ok(activities)
     }
 
-    // ==================== MÉTODOS PARA ARCHIVOS ADJUNTOS ====================
-
-    pub fn add_attachment(&self, attachment: CreateAttachmentDTO) -> Result<ProjectAttachment> {
-        const MAX_FILE_SIZE: i64 = 5 * 1024 * 1024; // 5MB en bytes
-
-        // Validar tamaño
-        if attachment.file_size > MAX_FILE_SIZE {
-            return Err(rusqlite::Error::InvalidParameterName(
-                "File size exceeds 5MB limit".to_string(),
-            ));
-        }
-
-        let conn = self.conn.lock().unwrap();
-
-        conn.execute(
-            "INSERT INTO project_attachments (project_id, filename, file_data, file_size, mime_type)
-             VALUES (?1, ?2, ?3, ?4, ?5)",
-            params![
-                attachment.project_id,
-                attachment.filename,
-                attachment.file_data,
-                attachment.file_size,
-                attachment.mime_type
-            ],
-        )?;
-
-        let id = conn.last_insert_rowid();
-
-        let attachment = conn.query_row(
-            "SELECT id, project_id, filename, file_data, file_size, mime_type, created_at
-             FROM project_attachments WHERE id = ?1",
-            params![id],
-            |row| {
-                Ok(ProjectAttachment {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    filename: row.get(2)?,
-                    file_data: row.get(3)?,
-                    file_size: row.get(4)?,
-                    mime_type: row.get(5)?,
-                    created_at: row.get(6)?,
-                })
-            },
-        )?;
-
-        Ok(attachment)
-    }
-
-    pub fn get_attachments(&self, project_id: i64) -> Result<Vec<ProjectAttachment>> {
-        let conn = self.conn.lock().unwrap();
-
-        let mut stmt = conn.prepare(
-            "SELECT id, project_id, filename, file_data, file_size, mime_type, created_at
-             FROM project_attachments WHERE project_id = ?1 ORDER BY created_at DESC",
-        )?;
-
-        let attachments = stmt
-            .query_map(params![project_id], |row| {
-                Ok(ProjectAttachment {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    filename: row.get(2)?,
-                    file_data: row.get(3)?,
-                    file_size: row.get(4)?,
-                    mime_type: row.get(5)?,
-                    created_at: row.get(6)?,
-                })
-            })?
-            .collect::<Result<Vec<_>>>()?;
-
-        Ok(attachments)
-    }
-
-    pub fn delete_attachment(&self, id: i64) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
-
-        conn.execute("DELETE FROM project_attachments WHERE id = ?1", params![id])?;
-
-        Ok(())
-    }
-
     // ==================== MÉTODOS PARA PROJECT JOURNAL ====================
 
     pub fn create_journal_entry(&self, entry: CreateJournalEntryDTO) -> Result<JournalEntry> {
@@ -868,6 +927,18 @@ impl Database {
             },
         )?;
 
+        self.log_activity_internal(
+            &conn,
+            journal_entry.project_id,
+            ActivityType::JournalEntryAdded,
+            "Entrada de diario añadida",
+            json!({
+                "entry_id": journal_entry.id,
+                "preview": activity::preview(&journal_entry.content),
+                "tags": journal_entry.tags.as_deref().and_then(|t| serde_json::from_str::<serde_json::Value>(t).ok()),
+            }),
+        )?;
+
         Ok(journal_entry)
     }
 
@@ -945,70 +1016,105 @@ impl Database {
             },
         )?;
 
+        self.log_activity_internal(
+            &conn,
+            entry.project_id,
+            ActivityType::JournalEntryUpdated,
+            "Entrada de diario editada",
+            json!({ "entry_id": entry.id, "preview": activity::preview(&entry.content) }),
+        )?;
+
         Ok(entry)
     }
 
     pub fn delete_journal_entry(&self, id: i64) -> Result<()> {
         let conn = self.conn.lock().unwrap();
 
+        let project_id = activity::owner_project_id(&conn, "project_journal", id)?;
+
         conn.execute("DELETE FROM project_journal WHERE id = ?1", params![id])?;
 
+        if let Some(project_id) = project_id {
+            self.log_activity_internal(
+                &conn,
+                project_id,
+                ActivityType::JournalEntryRemoved,
+                "Entrada de diario eliminada",
+                json!({ "entry_id": id }),
+            )?;
+        }
+
         Ok(())
     }
 
     // ==================== MÉTODOS PARA PROJECT TODOS ====================
 
+    fn todo_from_row(row: &Row) -> Result<ProjectTodo> {
+        Ok(ProjectTodo {
+            id: row.get(0)?,
+            project_id: row.get(1)?,
+            content: row.get(2)?,
+            is_completed: row.get(3)?,
+            created_at: row.get(4)?,
+            completed_at: row.get(5)?,
+            milestone_id: row.get(6)?,
+            column_id: row.get(7)?,
+            position: row.get(8)?,
+        })
+    }
+
     pub fn create_todo(&self, todo: CreateTodoDTO) -> Result<ProjectTodo> {
         let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
 
-        conn.execute(
-            "INSERT INTO project_todos (project_id, content)
-             VALUES (?1, ?2)",
-            params![todo.project_id, todo.content],
+        let columns = board::ensure_columns(&tx, todo.project_id)?;
+
+        tx.execute(
+            "INSERT INTO project_todos (project_id, content, milestone_id)
+             VALUES (?1, ?2, ?3)",
+            params![todo.project_id, todo.content, todo.milestone_id],
         )?;
 
-        let id = conn.last_insert_rowid();
+        let id = tx.last_insert_rowid();
 
-        let todo = conn.query_row(
-            "SELECT id, project_id, content, is_completed, created_at, completed_at
-             FROM project_todos WHERE id = ?1",
-            params![id],
-            |row| {
-                Ok(ProjectTodo {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    content: row.get(2)?,
-                    is_completed: row.get(3)?,
-                    created_at: row.get(4)?,
-                    completed_at: row.get(5)?,
-                })
-            },
+        // Al final de la columna indicada o de la primera del tablero
+        if let Some(column_id) = todo.column_id.or_else(|| columns.first().map(|c| c.id)) {
+            board::move_todo_internal(&tx, id, column_id, None)?;
+        }
+
+        self.log_activity_internal(
+            &tx,
+            todo.project_id,
+            ActivityType::TodoCreated,
+            "TODO creado",
+            json!({ "todo_id": id, "content": activity::preview(&todo.content) }),
         )?;
 
-        Ok(todo)
+        tx.commit()?;
+
+        Self::get_todo_internal(&conn, id)
+    }
+
+    fn get_todo_internal(conn: &Connection, id: i64) -> Result<ProjectTodo> {
+        conn.query_row(
+            &format!("SELECT {} FROM project_todos WHERE id = ?1", TODO_COLUMNS),
+            params![id],
+            Self::todo_from_row,
+        )
     }
 
     pub fn get_project_todos(&self, project_id: i64) -> Result<Vec<ProjectTodo>> {
         let conn = self.conn.lock().unwrap();
 
-        let mut stmt = conn.prepare(
-            "SELECT id, project_id, content, is_completed, created_at, completed_at
-             FROM project_todos
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM project_todos
              WHERE project_id = ?1
              ORDER BY is_completed ASC, created_at DESC",
-        )?;
+            TODO_COLUMNS
+        ))?;
 
         let todos = stmt
-            .query_map(params![project_id], |row| {
-                Ok(ProjectTodo {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    content: row.get(2)?,
-                    is_completed: row.get(3)?,
-                    created_at: row.get(4)?,
-                    completed_at: row.get(5)?,
-                })
-            })?
+            .query_map(params![project_id], Self::todo_from_row)?
             .collect::<Result<Vec<_>>>()?;
 
         Ok(todos)
@@ -1017,65 +1123,66 @@ impl Database {
     pub fn update_todo(&self, id: i64, updates: UpdateTodoDTO) -> Result<ProjectTodo> {
         let conn = self.conn.lock().unwrap();
 
-        let mut set_clauses = Vec::new();
-        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
-
-        if let Some(content) = updates.content {
-            set_clauses.push("content = ?");
-            params.push(Box::new(content));
+        if updates.content.is_none() && updates.is_completed.is_none() && updates.column_id.is_none() {
+            return Err(rusqlite::Error::InvalidParameterCount(0, 1));
         }
 
-        if let Some(is_completed) = updates.is_completed {
-            set_clauses.push("is_completed = ?");
-            params.push(Box::new(is_completed));
+        let tx = conn.unchecked_transaction()?;
+        let before = Self::get_todo_internal(&tx, id)?;
 
-            // Si se completó, actualizar completed_at
-            if is_completed {
-                set_clauses.push("completed_at = CURRENT_TIMESTAMP");
-            } else {
-                set_clauses.push("completed_at = NULL");
-            }
+        if let Some(content) = updates.content {
+            tx.execute(
+                "UPDATE project_todos SET content = ?1 WHERE id = ?2",
+                params![content, id],
+            )?;
+            self.log_activity_internal(
+                &tx,
+                before.project_id,
+                ActivityType::TodoUpdated,
+                "TODO editado",
+                json!({ "todo_id": id, "content": activity::preview(&content) }),
+            )?;
         }
 
-        if set_clauses.is_empty() {
-            return Err(rusqlite::Error::InvalidParameterCount(0, 1));
+        // El estado de completado sigue a la columna: si se indican ambos, manda la columna
+        if let Some(column_id) = updates.column_id {
+            board::move_todo_internal(&tx, id, column_id, None)?;
+        } else if let Some(is_completed) = updates.is_completed {
+            board::set_todo_completion_internal(&tx, id, is_completed)?;
         }
 
-        params.push(Box::new(id));
+        let after = Self::get_todo_internal(&tx, id)?;
+        self.log_todo_transition(&tx, &before, &after)?;
 
-        let query = format!(
-            "UPDATE project_todos SET {} WHERE id = ?",
-            set_clauses.join(", ")
-        );
+        tx.commit()?;
 
-        let params_ref: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
-        conn.execute(&query, params_ref.as_slice())?;
-
-        // Obtener el TODO actualizado
-        let todo = conn.query_row(
-            "SELECT id, project_id, content, is_completed, created_at, completed_at
-             FROM project_todos WHERE id = ?1",
-            params![id],
-            |row| {
-                Ok(ProjectTodo {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    content: row.get(2)?,
-                    is_completed: row.get(3)?,
-                    created_at: row.get(4)?,
-                    completed_at: row.get(5)?,
-                })
-            },
-        )?;
-
-        Ok(todo)
+        Ok(after)
     }
 
     pub fn delete_todo(&self, id: i64) -> Result<()> {
         let conn = self.conn.lock().unwrap();
 
+        let todo = conn
+            .query_row(
+                &format!("SELECT {} FROM project_todos WHERE id = ?1", TODO_COLUMNS),
+                params![id],
+                Self::todo_from_row,
+            )
+            .optional()?;
+
+        conn.execute("DELETE FROM reminders WHERE todo_id = ?1", params![id])?;
         conn.execute("DELETE FROM project_todos WHERE id = ?1", params![id])?;
 
+        if let Some(todo) = todo {
+            self.log_activity_internal(
+                &conn,
+                todo.project_id,
+                ActivityType::TodoDeleted,
+                "TODO eliminado",
+                json!({ "todo_id": id, "content": activity::preview(&todo.content) }),
+            )?;
+        }
+
         Ok(())
     }
 
@@ -1083,6 +1190,38 @@ impl Database {
 
     pub fn update_project_status(&self, id: i64, status: String) -> Result<()> {
         let conn = self.conn.lock().unwrap();
+        self.set_project_status_internal(&conn, id, &status, None)
+    }
+
+    /// Cambia el estado registrando el cambio; `extra_details` se añade a los detalles de la actividad
+    fn set_project_status_internal(
+        &self,
+        conn: &Connection,
+        id: i64,
+        status: &str,
+        extra_details: Option<serde_json::Value>,
+    ) -> Result<()> {
+        let previous: String = conn.query_row(
+            "SELECT COALESCE(status, 'activo') FROM projects WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )?;
+
+        if previous != status {
+            let mut details = json!({ "from": previous, "to": status });
+            if let Some(serde_json::Value::Object(extra)) = extra_details {
+                for (key, value) in extra {
+                    details[key] = value;
+                }
+            }
+            self.log_activity_internal(
+                conn,
+                id,
+                ActivityType::StatusChanged,
+                "Estado cambiado",
+                details,
+            )?;
+        }
 
         conn.execute(
             "UPDATE projects
@@ -1130,6 +1269,13 @@ impl Database {
             )?;
         }
 
+        let (activity_type, description) = if new_pinned {
+            (ActivityType::Pinned, "Proyecto fijado")
+        } else {
+            (ActivityType::Unpinned, "Proyecto desfijado")
+        };
+        self.log_activity_internal(&conn, id, activity_type, description, serde_json::Value::Null)?;
+
         Ok(new_pinned)
     }
 
@@ -0,0 +1,312 @@
+use rusqlite::{params, Connection, OptionalExtension, Result, Row};
+use serde_json::json;
+use std::collections::HashSet;
+
+use super::Database;
+use crate::models::project::{
+    ActivityType, CreateRelationDTO, ProjectGraph, ProjectGraphNode, ProjectRelation, RelatedProject,
+    RelationDirection, RelationType,
+};
+
+const RELATION_COLUMNS: &str = "id, source_project_id, target_project_id, relation_type, created_at";
+
+/// Profundidad máxima permitida al pedir el vecindario de un proyecto
+const MAX_GRAPH_DEPTH: i64 = 5;
+
+pub(super) fn init_schema(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS project_relations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            source_project_id INTEGER NOT NULL,
+            target_project_id INTEGER NOT NULL,
+            relation_type TEXT NOT NULL CHECK (relation_type IN ('depends-on', 'related-to', 'fork-of')),
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE (source_project_id, target_project_id, relation_type),
+            FOREIGN KEY (source_project_id) REFERENCES projects (id) ON DELETE CASCADE,
+            FOREIGN KEY (target_project_id) REFERENCES projects (id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Búsquedas inversas ("quién depende de este proyecto")
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_project_relations_target
+         ON project_relations (target_project_id, relation_type)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn relation_from_row(row: &Row) -> Result<ProjectRelation> {
+    let relation_type: String = row.get(3)?;
+    Ok(ProjectRelation {
+        id: row.get(0)?,
+        source_project_id: row.get(1)?,
+        target_project_id: row.get(2)?,
+        relation_type: RelationType::parse(&relation_type).ok_or_else(|| {
+            rusqlite::Error::FromSqlConversionFailure(
+                3,
+                rusqlite::types::Type::Text,
+                format!("Tipo de relación desconocido: {}", relation_type).into(),
+            )
+        })?,
+        created_at: row.get(4)?,
+    })
+}
+
+fn relation_details(relation: &ProjectRelation) -> serde_json::Value {
+    json!({
+        "relation_id": relation.id,
+        "source_project_id": relation.source_project_id,
+        "target_project_id": relation.target_project_id,
+        "relation_type": relation.relation_type.as_str(),
+    })
+}
+
+fn project_exists(conn: &Connection, id: i64) -> Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM projects WHERE id = ?1)",
+        params![id],
+        |row| row.get(0),
+    )
+}
+
+/// `true` si `from` ya alcanza a `to` siguiendo relaciones dirigidas del mismo tipo
+fn reaches(conn: &Connection, from: i64, to: i64, relation_type: RelationType) -> Result<bool> {
+    conn.query_row(
+        "WITH RECURSIVE reachable(id) AS (
+            SELECT ?1
+            UNION
+            SELECT r.target_project_id FROM project_relations r
+            JOIN reachable ON r.source_project_id = reachable.id
+            WHERE r.relation_type = ?3
+        )
+        SELECT EXISTS (SELECT 1 FROM reachable WHERE id = ?2)",
+        params![from, to, relation_type.as_str()],
+        |row| row.get(0),
+    )
+}
+
+impl Database {
+    // ==================== MÉTODOS PARA RELACIONES ENTRE PROYECTOS ====================
+
+    pub fn create_relation(&self, relation: CreateRelationDTO) -> Result<ProjectRelation> {
+        let conn = self.conn.lock().unwrap();
+
+        let CreateRelationDTO {
+            mut source_project_id,
+            mut target_project_id,
+            relation_type,
+        } = relation;
+
+        if source_project_id == target_project_id {
+            return Err(rusqlite::Error::InvalidParameterName(
+                "Un proyecto no puede relacionarse consigo mismo".to_string(),
+            ));
+        }
+
+        for id in [source_project_id, target_project_id] {
+            if !project_exists(&conn, id)? {
+                return Err(rusqlite::Error::InvalidParameterName(format!(
+                    "El proyecto {} no existe",
+                    id
+                )));
+            }
+        }
+
+        match relation_type {
+            // Simétrica: se guarda siempre con el id menor como origen para evitar duplicados A↔B
+            RelationType::RelatedTo => {
+                if source_project_id > target_project_id {
+                    std::mem::swap(&mut source_project_id, &mut target_project_id);
+                }
+            }
+            // Dirigidas: no se permiten ciclos (A depende de B y B depende de A)
+            RelationType::DependsOn | RelationType::ForkOf => {
+                if reaches(&conn, target_project_id, source_project_id, relation_type)? {
+                    return Err(rusqlite::Error::InvalidParameterName(format!(
+                        "La relación '{}' crearía un ciclo",
+                        relation_type.as_str()
+                    )));
+                }
+            }
+        }
+
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM project_relations
+                            WHERE source_project_id = ?1 AND target_project_id = ?2 AND relation_type = ?3)",
+            params![source_project_id, target_project_id, relation_type.as_str()],
+            |row| row.get(0),
+        )?;
+        if exists {
+            return Err(rusqlite::Error::InvalidParameterName(
+                "La relación ya existe".to_string(),
+            ));
+        }
+
+        conn.execute(
+            "INSERT INTO project_relations (source_project_id, target_project_id, relation_type)
+             VALUES (?1, ?2, ?3)",
+            params![source_project_id, target_project_id, relation_type.as_str()],
+        )?;
+
+        let id = conn.last_insert_rowid();
+        let relation = conn.query_row(
+            &format!("SELECT {} FROM project_relations WHERE id = ?1", RELATION_COLUMNS),
+            params![id],
+            relation_from_row,
+        )?;
+
+        self.log_activity_internal(
+            &conn,
+            relation.source_project_id,
+            ActivityType::RelationAdded,
+            "Relación añadida",
+            relation_details(&relation),
+        )?;
+
+        Ok(relation)
+    }
+
+    pub fn delete_relation(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        let relation = conn
+            .query_row(
+                &format!("SELECT {} FROM project_relations WHERE id = ?1", RELATION_COLUMNS),
+                params![id],
+                relation_from_row,
+            )
+            .optional()?;
+
+        conn.execute("DELETE FROM project_relations WHERE id = ?1", params![id])?;
+
+        if let Some(relation) = relation {
+            self.log_activity_internal(
+                &conn,
+                relation.source_project_id,
+                ActivityType::RelationRemoved,
+                "Relación eliminada",
+                relation_details(&relation),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Relaciones de un proyecto en ambos sentidos, con el nombre del otro proyecto
+    pub fn get_project_relations(&self, project_id: i64) -> Result<Vec<RelatedProject>> {
+        let conn = self.conn.lock().unwrap();
+        Self::get_related_projects_internal(&conn, project_id, None)
+    }
+
+    /// Proyectos que dependen de `project_id` (relaciones `depends-on` entrantes)
+    pub fn get_project_dependents(&self, project_id: i64) -> Result<Vec<RelatedProject>> {
+        let conn = self.conn.lock().unwrap();
+        let mut dependents =
+            Self::get_related_projects_internal(&conn, project_id, Some(RelationType::DependsOn))?;
+        dependents.retain(|r| r.direction == RelationDirection::Incoming);
+        Ok(dependents)
+    }
+
+    fn get_related_projects_internal(
+        conn: &Connection,
+        project_id: i64,
+        relation_type: Option<RelationType>,
+    ) -> Result<Vec<RelatedProject>> {
+        let mut stmt = conn.prepare_cached(
+            "SELECT r.id, r.source_project_id, r.target_project_id, r.relation_type, r.created_at,
+                    p.id, p.name, p.status
+             FROM project_relations r
+             JOIN projects p ON p.id = CASE WHEN r.source_project_id = ?1
+                                            THEN r.target_project_id
+                                            ELSE r.source_project_id END
+             WHERE (r.source_project_id = ?1 OR r.target_project_id = ?1)
+               AND (?2 IS NULL OR r.relation_type = ?2)
+             ORDER BY r.relation_type, p.name COLLATE NOCASE",
+        )?;
+
+        let related = stmt
+            .query_map(params![project_id, relation_type.map(|t| t.as_str())], |row| {
+                let relation = relation_from_row(row)?;
+                let direction = if relation.source_project_id == project_id {
+                    RelationDirection::Outgoing
+                } else {
+                    RelationDirection::Incoming
+                };
+                Ok(RelatedProject {
+                    relation,
+                    direction,
+                    project_id: row.get(5)?,
+                    project_name: row.get(6)?,
+                    project_status: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(related)
+    }
+
+    /// Vecindario de un proyecto hasta `depth` saltos, recorriendo las relaciones en ambos sentidos
+    pub fn get_project_graph(&self, project_id: i64, depth: i64) -> Result<ProjectGraph> {
+        let conn = self.conn.lock().unwrap();
+        let depth = depth.clamp(1, MAX_GRAPH_DEPTH);
+
+        let mut stmt = conn.prepare_cached(
+            "WITH RECURSIVE neighborhood(id, depth) AS (
+                SELECT ?1, 0
+                UNION
+                SELECT CASE WHEN r.source_project_id = n.id
+                            THEN r.target_project_id
+                            ELSE r.source_project_id END,
+                       n.depth + 1
+                FROM project_relations r
+                JOIN neighborhood n ON r.source_project_id = n.id OR r.target_project_id = n.id
+                WHERE n.depth < ?2
+            )
+            SELECT p.id, p.name, p.status, MIN(n.depth) AS depth
+            FROM neighborhood n
+            JOIN projects p ON p.id = n.id
+            GROUP BY p.id
+            ORDER BY depth, p.name COLLATE NOCASE",
+        )?;
+
+        let nodes = stmt
+            .query_map(params![project_id, depth], |row| {
+                Ok(ProjectGraphNode {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    status: row.get(2)?,
+                    depth: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        if nodes.is_empty() {
+            return Err(rusqlite::Error::QueryReturnedNoRows);
+        }
+
+        let node_ids: HashSet<i64> = nodes.iter().map(|n| n.id).collect();
+        let ids_json = serde_json::to_string(&node_ids)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM project_relations
+             WHERE source_project_id IN (SELECT value FROM json_each(?1))
+               AND target_project_id IN (SELECT value FROM json_each(?1))
+             ORDER BY id",
+            RELATION_COLUMNS
+        ))?;
+
+        let edges = stmt
+            .query_map(params![ids_json], relation_from_row)?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(ProjectGraph {
+            root_id: project_id,
+            nodes,
+            edges,
+        })
+    }
+}
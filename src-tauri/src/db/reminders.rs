@@ -0,0 +1,300 @@
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Result, Row};
+use serde_json::json;
+
+use super::activity::preview;
+use super::Database;
+use crate::models::project::{ActivityType, CreateReminderDTO, Reminder, ReminderStatus};
+
+/// Columnas de `reminders` (alias `r`) junto con el nombre del proyecto (alias `p`)
+const REMINDER_COLUMNS: &str =
+    "r.id, r.project_id, p.name, r.todo_id, r.milestone_id, r.message, r.remind_at, r.status,
+     r.fired_at, r.snooze_count, r.created_at";
+
+/// Formato de las fechas en SQLite (`CURRENT_TIMESTAMP`, en UTC)
+const SQLITE_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+pub(super) fn init_schema(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS reminders (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL,
+            todo_id INTEGER,
+            milestone_id INTEGER,
+            message TEXT NOT NULL,
+            remind_at DATETIME NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            fired_at DATETIME,
+            snooze_count INTEGER NOT NULL DEFAULT 0,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (project_id) REFERENCES projects (id) ON DELETE CASCADE,
+            FOREIGN KEY (todo_id) REFERENCES project_todos (id) ON DELETE CASCADE,
+            FOREIGN KEY (milestone_id) REFERENCES project_milestones (id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // El planificador consulta constantemente los pendientes ya vencidos
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_reminders_status_remind_at ON reminders (status, remind_at)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn reminder_from_row(row: &Row) -> Result<Reminder> {
+    let status: String = row.get(7)?;
+    Ok(Reminder {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        project_name: row.get(2)?,
+        todo_id: row.get(3)?,
+        milestone_id: row.get(4)?,
+        message: row.get(5)?,
+        remind_at: row.get(6)?,
+        status: ReminderStatus::parse(&status).ok_or_else(|| {
+            rusqlite::Error::FromSqlConversionFailure(
+                7,
+                rusqlite::types::Type::Text,
+                format!("Estado de recordatorio desconocido: {}", status).into(),
+            )
+        })?,
+        fired_at: row.get(8)?,
+        snooze_count: row.get(9)?,
+        created_at: row.get(10)?,
+    })
+}
+
+/// Convierte la fecha recibida del frontend al formato UTC que usa SQLite.
+/// Acepta RFC 3339 o una fecha local "YYYY-MM-DD HH:MM[:SS]" (también con `T`).
+fn normalize_remind_at(value: &str) -> Result<String> {
+    let value = value.trim();
+
+    let utc: Option<DateTime<Utc>> = DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            let normalized = value.replacen('T', " ", 1);
+            ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"]
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(&normalized, format).ok())
+                .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+                .map(|local| local.with_timezone(&Utc))
+        });
+
+    utc.map(|dt| dt.format(SQLITE_DATETIME_FORMAT).to_string())
+        .ok_or_else(|| {
+            rusqlite::Error::InvalidParameterName(format!(
+                "Fecha de recordatorio inválida '{}': se espera RFC 3339 o YYYY-MM-DD HH:MM",
+                value
+            ))
+        })
+}
+
+impl Database {
+    // ==================== MÉTODOS PARA RECORDATORIOS ====================
+
+    pub fn create_reminder(&self, reminder: CreateReminderDTO) -> Result<Reminder> {
+        let message = reminder.message.trim().to_string();
+        if message.is_empty() {
+            return Err(rusqlite::Error::InvalidParameterName(
+                "El mensaje del recordatorio no puede estar vacío".to_string(),
+            ));
+        }
+        let remind_at = normalize_remind_at(&reminder.remind_at)?;
+
+        let conn = self.conn.lock().unwrap();
+
+        // El TODO o hito, si se indica, debe pertenecer al proyecto
+        if let Some(todo_id) = reminder.todo_id {
+            let belongs: bool = conn.query_row(
+                "SELECT EXISTS (SELECT 1 FROM project_todos WHERE id = ?1 AND project_id = ?2)",
+                params![todo_id, reminder.project_id],
+                |row| row.get(0),
+            )?;
+            if !belongs {
+                return Err(rusqlite::Error::InvalidParameterName(
+                    "El TODO no pertenece al proyecto".to_string(),
+                ));
+            }
+        }
+        if let Some(milestone_id) = reminder.milestone_id {
+            let belongs: bool = conn.query_row(
+                "SELECT EXISTS (SELECT 1 FROM project_milestones WHERE id = ?1 AND project_id = ?2)",
+                params![milestone_id, reminder.project_id],
+                |row| row.get(0),
+            )?;
+            if !belongs {
+                return Err(rusqlite::Error::InvalidParameterName(
+                    "El hito no pertenece al proyecto".to_string(),
+                ));
+            }
+        }
+
+        conn.execute(
+            "INSERT INTO reminders (project_id, todo_id, milestone_id, message, remind_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                reminder.project_id,
+                reminder.todo_id,
+                reminder.milestone_id,
+                message,
+                remind_at
+            ],
+        )?;
+
+        let id = conn.last_insert_rowid();
+        self.log_activity_internal(
+            &conn,
+            reminder.project_id,
+            ActivityType::ReminderCreated,
+            "Recordatorio creado",
+            json!({
+                "reminder_id": id,
+                "message": preview(&message),
+                "remind_at": remind_at,
+                "todo_id": reminder.todo_id,
+                "milestone_id": reminder.milestone_id,
+            }),
+        )?;
+
+        Self::get_reminder_internal(&conn, id)
+    }
+
+    fn get_reminder_internal(conn: &Connection, id: i64) -> Result<Reminder> {
+        conn.query_row(
+            &format!(
+                "SELECT {} FROM reminders r JOIN projects p ON p.id = r.project_id WHERE r.id = ?1",
+                REMINDER_COLUMNS
+            ),
+            params![id],
+            reminder_from_row,
+        )
+    }
+
+    /// Recordatorios de un proyecto (o de todos con `None`). Los descartados solo con `include_dismissed`.
+    pub fn get_reminders(&self, project_id: Option<i64>, include_dismissed: bool) -> Result<Vec<Reminder>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM reminders r
+             JOIN projects p ON p.id = r.project_id
+             WHERE (?1 IS NULL OR r.project_id = ?1)
+               AND (?2 OR r.status != 'dismissed')
+             ORDER BY r.remind_at ASC",
+            REMINDER_COLUMNS
+        ))?;
+
+        let reminders = stmt
+            .query_map(params![project_id, include_dismissed], reminder_from_row)?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(reminders)
+    }
+
+    /// Marca como disparados los recordatorios pendientes ya vencidos y los devuelve.
+    /// Lo usa el planificador; cada recordatorio se devuelve una sola vez.
+    pub fn take_due_reminders(&self) -> Result<Vec<Reminder>> {
+        let conn = self.conn.lock().unwrap();
+
+        let ids = conn
+            .prepare_cached(
+                "UPDATE reminders SET status = 'fired', fired_at = CURRENT_TIMESTAMP
+                 WHERE status = 'pending' AND remind_at <= CURRENT_TIMESTAMP
+                 RETURNING id",
+            )?
+            .query_map([], |row| row.get::<_, i64>(0))?
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut reminders = ids
+            .into_iter()
+            .map(|id| Self::get_reminder_internal(&conn, id))
+            .collect::<Result<Vec<_>>>()?;
+        reminders.sort_by(|a, b| a.remind_at.cmp(&b.remind_at));
+
+        Ok(reminders)
+    }
+
+    /// Pospone el recordatorio `minutes` minutos a partir de ahora y lo vuelve a dejar pendiente
+    pub fn snooze_reminder(&self, id: i64, minutes: i64) -> Result<Reminder> {
+        if minutes <= 0 {
+            return Err(rusqlite::Error::InvalidParameterName(
+                "Los minutos de aplazamiento deben ser positivos".to_string(),
+            ));
+        }
+
+        let conn = self.conn.lock().unwrap();
+
+        let rows = conn.execute(
+            "UPDATE reminders
+             SET remind_at = datetime('now', ?1), status = 'pending', fired_at = NULL,
+                 snooze_count = snooze_count + 1
+             WHERE id = ?2",
+            params![format!("+{} minutes", minutes), id],
+        )?;
+        if rows == 0 {
+            return Err(rusqlite::Error::QueryReturnedNoRows);
+        }
+
+        let reminder = Self::get_reminder_internal(&conn, id)?;
+        self.log_activity_internal(
+            &conn,
+            reminder.project_id,
+            ActivityType::ReminderSnoozed,
+            "Recordatorio pospuesto",
+            json!({ "reminder_id": id, "minutes": minutes, "remind_at": reminder.remind_at }),
+        )?;
+
+        Ok(reminder)
+    }
+
+    pub fn dismiss_reminder(&self, id: i64) -> Result<Reminder> {
+        let conn = self.conn.lock().unwrap();
+
+        let rows = conn.execute(
+            "UPDATE reminders SET status = 'dismissed' WHERE id = ?1",
+            params![id],
+        )?;
+        if rows == 0 {
+            return Err(rusqlite::Error::QueryReturnedNoRows);
+        }
+
+        let reminder = Self::get_reminder_internal(&conn, id)?;
+        self.log_activity_internal(
+            &conn,
+            reminder.project_id,
+            ActivityType::ReminderDismissed,
+            "Recordatorio descartado",
+            json!({ "reminder_id": id, "message": preview(&reminder.message) }),
+        )?;
+
+        Ok(reminder)
+    }
+
+    pub fn delete_reminder(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        let reminder: Option<(i64, String)> = conn
+            .query_row(
+                "SELECT project_id, message FROM reminders WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        conn.execute("DELETE FROM reminders WHERE id = ?1", params![id])?;
+
+        if let Some((project_id, message)) = reminder {
+            self.log_activity_internal(
+                &conn,
+                project_id,
+                ActivityType::ReminderDeleted,
+                "Recordatorio eliminado",
+                json!({ "reminder_id": id, "message": preview(&message) }),
+            )?;
+        }
+
+        Ok(())
+    }
+}
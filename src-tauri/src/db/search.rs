@@ -0,0 +1,149 @@
+use rusqlite::{Result, ToSql};
+use std::path::Path;
+
+use super::{Database, PROJECT_LIST_COLUMNS};
+use crate::models::project::Project;
+use crate::search::{Clause, DateField, DateFilter, SearchQuery, Term};
+
+/// Parámetros posicionales de una consulta construida dinámicamente
+pub(super) type SqlParams = Vec<Box<dyn ToSql>>;
+
+/// Escapa `%`, `_` y `\` para usarlos en `LIKE ... ESCAPE '\'`
+fn like_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+fn expand_home(path: &str) -> String {
+    if path == "~" || path.starts_with("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return format!("{}{}", home.display(), &path[1..]);
+        }
+    }
+    path.to_string()
+}
+
+fn date_column(field: DateField) -> &'static str {
+    match field {
+        DateField::Opened => "last_opened_at",
+        DateField::Created => "created_at",
+        DateField::Updated => "updated_at",
+    }
+}
+
+/// Traduce una cláusula a SQL parametrizado
+fn compile_clause(clause: &Clause, params: &mut SqlParams) -> String {
+    let sql = match &clause.term {
+        Term::Word(text) | Term::Phrase(text) => {
+            let pattern = format!("%{}%", like_escape(text));
+            for _ in 0..4 {
+                params.push(Box::new(pattern.clone()));
+            }
+            "(name LIKE ? ESCAPE '\\' OR description LIKE ? ESCAPE '\\'
+              OR local_path LIKE ? ESCAPE '\\' OR COALESCE(notes, '') LIKE ? ESCAPE '\\')"
+                .to_string()
+        }
+        Term::Status(statuses) => {
+            let placeholders = vec!["?"; statuses.len()].join(", ");
+            for status in statuses {
+                params.push(Box::new(status.clone()));
+            }
+            format!("COALESCE(status, 'activo') IN ({})", placeholders)
+        }
+        Term::Tag(tag) => {
+            // Las etiquetas se guardan como JSON en project_journal.tags: ["bug", "tip"]
+            params.push(Box::new(format!("%\"{}\"%", like_escape(tag))));
+            "EXISTS (SELECT 1 FROM project_journal
+                     WHERE project_journal.project_id = projects.id
+                       AND project_journal.tags LIKE ? ESCAPE '\\')"
+                .to_string()
+        }
+        Term::Pinned(pinned) => {
+            params.push(Box::new(*pinned));
+            "COALESCE(is_pinned, 0) = ?".to_string()
+        }
+        Term::Date(field, filter) => {
+            let column = date_column(*field);
+            match filter {
+                DateFilter::Within(age) => {
+                    params.push(Box::new(age.sqlite_modifier()));
+                    format!("{} >= datetime('now', ?)", column)
+                }
+                DateFilter::OlderThan(age) => {
+                    params.push(Box::new(age.sqlite_modifier()));
+                    format!("({0} IS NULL OR {0} < datetime('now', ?))", column)
+                }
+                DateFilter::Never => format!("{} IS NULL", column),
+            }
+        }
+        Term::Path(path) => {
+            let expanded = expand_home(path);
+            let pattern = if Path::new(&expanded).is_absolute() {
+                format!("{}%", like_escape(&expanded))
+            } else {
+                format!("%{}%", like_escape(&expanded))
+            };
+            params.push(Box::new(pattern));
+            "local_path LIKE ? ESCAPE '\\'".to_string()
+        }
+        Term::Name(name) => {
+            params.push(Box::new(format!("%{}%", like_escape(name))));
+            "name LIKE ? ESCAPE '\\'".to_string()
+        }
+    };
+
+    if clause.negated {
+        format!("NOT COALESCE({}, 0)", sql)
+    } else {
+        sql
+    }
+}
+
+/// Traduce la consulta a una condición WHERE (sin la palabra clave) y sus parámetros.
+/// Las consultas de solo texto mantienen la búsqueda clásica sobre la cadena completa.
+pub(super) fn compile_query(query: &SearchQuery) -> (String, SqlParams) {
+    let mut params: SqlParams = Vec::new();
+
+    if query.is_plain_text() {
+        let pattern = format!("%{}%", query.raw);
+        for _ in 0..4 {
+            params.push(Box::new(pattern.clone()));
+        }
+        return (
+            "(name LIKE ? OR description LIKE ? OR local_path LIKE ? OR notes LIKE ?)".to_string(),
+            params,
+        );
+    }
+
+    let conditions: Vec<String> = query
+        .clauses
+        .iter()
+        .map(|clause| compile_clause(clause, &mut params))
+        .collect();
+
+    (conditions.join(" AND "), params)
+}
+
+impl Database {
+    pub fn search_projects(&self, query: &SearchQuery) -> Result<Vec<Project>> {
+        let conn = self.conn.lock().unwrap();
+
+        let (condition, params) = compile_query(query);
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT {} FROM projects
+             WHERE {}
+             ORDER BY is_pinned DESC, pinned_order ASC, updated_at DESC",
+            PROJECT_LIST_COLUMNS, condition
+        ))?;
+
+        let params_ref: Vec<&dyn ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let mut projects = stmt
+            .query_map(params_ref.as_slice(), Self::project_from_row)?
+            .collect::<Result<Vec<_>>>()?;
+
+        Self::attach_links(&conn, &mut projects)?;
+
+        Ok(projects)
+    }
+}
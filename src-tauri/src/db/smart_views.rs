@@ -0,0 +1,350 @@
+use rusqlite::{params, Connection, Result, Row, ToSql};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::search::{compile_query, SqlParams};
+use super::{Database, PROJECT_LIST_COLUMNS};
+use crate::models::project::{
+    CreateSmartViewDTO, Project, RelativePeriod, SmartView, SmartViewFilter, SmartViewSortField,
+    SortDirection, UpdateSmartViewDTO,
+};
+use crate::{git, search};
+
+const SMART_VIEW_COLUMNS: &str = "id, name, filter, sort_by, sort_direction, created_at, updated_at";
+
+/// Crear la tabla de vistas guardadas y sembrar las vistas predeterminadas la primera vez
+pub(super) fn init_schema(conn: &Connection) -> Result<()> {
+    let table_exists: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'smart_views')",
+        [],
+        |row| row.get(0),
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS smart_views (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            filter TEXT NOT NULL,
+            sort_by TEXT NOT NULL,
+            sort_direction TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    if !table_exists {
+        let defaults = [
+            (
+                "Activos sin abrir (14 días)",
+                SmartViewFilter {
+                    statuses: vec!["activo".to_string()],
+                    not_opened_in_days: Some(14),
+                    ..Default::default()
+                },
+                SmartViewSortField::LastOpenedAt,
+                SortDirection::Asc,
+            ),
+            (
+                "Favoritos con cambios sin commitear",
+                SmartViewFilter {
+                    pinned: Some(true),
+                    has_uncommitted_changes: Some(true),
+                    ..Default::default()
+                },
+                SmartViewSortField::PinnedOrder,
+                SortDirection::Asc,
+            ),
+            (
+                "Completados este mes",
+                SmartViewFilter {
+                    statuses: vec!["completado".to_string()],
+                    status_changed_in: Some(RelativePeriod::ThisMonth),
+                    ..Default::default()
+                },
+                SmartViewSortField::StatusChangedAt,
+                SortDirection::Desc,
+            ),
+        ];
+
+        for (name, filter, sort_by, sort_direction) in defaults {
+            conn.execute(
+                "INSERT OR IGNORE INTO smart_views (name, filter, sort_by, sort_direction)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![name, to_json(&filter)?, enum_to_sql(&sort_by)?, enum_to_sql(&sort_direction)?],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String> {
+    serde_json::to_string(value).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+fn from_json<T: DeserializeOwned>(json: &str, column: usize) -> Result<T> {
+    serde_json::from_str(json).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(column, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+/// Los enums se guardan con su nombre serializado (ej: "last_opened_at")
+fn enum_to_sql<T: Serialize>(value: &T) -> Result<String> {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(s)) => Ok(s),
+        Ok(other) => Ok(other.to_string()),
+        Err(e) => Err(rusqlite::Error::ToSqlConversionFailure(Box::new(e))),
+    }
+}
+
+fn enum_from_sql<T: DeserializeOwned>(value: String, column: usize) -> Result<T> {
+    serde_json::from_value(serde_json::Value::String(value)).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(column, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+fn smart_view_from_row(row: &Row) -> Result<SmartView> {
+    Ok(SmartView {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        filter: from_json(&row.get::<_, String>(2)?, 2)?,
+        sort_by: enum_from_sql(row.get(3)?, 3)?,
+        sort_direction: enum_from_sql(row.get(4)?, 4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+/// Inicio del periodo como expresión SQLite (en UTC, igual que CURRENT_TIMESTAMP)
+fn period_start_sql(period: RelativePeriod) -> &'static str {
+    match period {
+        RelativePeriod::Today => "datetime('now', 'start of day')",
+        // Lunes de la semana actual
+        RelativePeriod::ThisWeek => "datetime('now', '-6 days', 'weekday 1', 'start of day')",
+        RelativePeriod::ThisMonth => "datetime('now', 'start of month')",
+        RelativePeriod::ThisYear => "datetime('now', 'start of year')",
+    }
+}
+
+fn sort_column_sql(field: SmartViewSortField) -> &'static str {
+    match field {
+        SmartViewSortField::Name => "name COLLATE NOCASE",
+        SmartViewSortField::CreatedAt => "created_at",
+        SmartViewSortField::UpdatedAt => "updated_at",
+        SmartViewSortField::LastOpenedAt => "last_opened_at",
+        SmartViewSortField::OpenedCount => "COALESCE(opened_count, 0)",
+        SmartViewSortField::StatusChangedAt => "status_changed_at",
+        SmartViewSortField::PinnedOrder => "COALESCE(is_pinned, 0) DESC, pinned_order",
+    }
+}
+
+/// Traduce el filtro a cláusulas WHERE parametrizadas. El texto admite el lenguaje de consulta.
+/// `has_uncommitted_changes` no se puede resolver en SQL: lo aplica `query_projects` después.
+fn compile_filter(filter: &SmartViewFilter) -> Result<(Vec<String>, SqlParams)> {
+    let mut clauses: Vec<String> = Vec::new();
+    let mut params: SqlParams = Vec::new();
+
+    if let Some(text) = filter.text.as_ref().filter(|t| !t.trim().is_empty()) {
+        let query = search::parse(text)
+            .map_err(|e| rusqlite::Error::InvalidParameterName(e.to_string()))?;
+        let (condition, query_params) = compile_query(&query);
+        clauses.push(condition);
+        params.extend(query_params);
+    }
+
+    if !filter.statuses.is_empty() {
+        let placeholders = vec!["?"; filter.statuses.len()].join(", ");
+        clauses.push(format!("COALESCE(status, 'activo') IN ({})", placeholders));
+        for status in &filter.statuses {
+            params.push(Box::new(status.clone()));
+        }
+    }
+
+    if let Some(pinned) = filter.pinned {
+        clauses.push("COALESCE(is_pinned, 0) = ?".to_string());
+        params.push(Box::new(pinned));
+    }
+
+    if let Some(days) = filter.opened_within_days {
+        clauses.push("last_opened_at >= datetime('now', ?)".to_string());
+        params.push(Box::new(format!("-{} days", days.max(0))));
+    }
+
+    if let Some(days) = filter.not_opened_in_days {
+        clauses.push("(last_opened_at IS NULL OR last_opened_at < datetime('now', ?))".to_string());
+        params.push(Box::new(format!("-{} days", days.max(0))));
+    }
+
+    if let Some(period) = filter.status_changed_in {
+        clauses.push(format!("status_changed_at >= {}", period_start_sql(period)));
+    }
+
+    Ok((clauses, params))
+}
+
+impl Database {
+    // ==================== MÉTODOS PARA VISTAS GUARDADAS ====================
+
+    pub fn create_smart_view(&self, view: CreateSmartViewDTO) -> Result<SmartView> {
+        compile_filter(&view.filter)?;
+        let conn = self.conn.lock().unwrap();
+
+        conn.execute(
+            "INSERT INTO smart_views (name, filter, sort_by, sort_direction)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                view.name.trim(),
+                to_json(&view.filter)?,
+                enum_to_sql(&view.sort_by)?,
+                enum_to_sql(&view.sort_direction)?
+            ],
+        )?;
+
+        let id = conn.last_insert_rowid();
+        Self::get_smart_view_internal(&conn, id)
+    }
+
+    pub fn get_smart_views(&self) -> Result<Vec<SmartView>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM smart_views ORDER BY name COLLATE NOCASE",
+            SMART_VIEW_COLUMNS
+        ))?;
+
+        let views = stmt
+            .query_map([], smart_view_from_row)?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(views)
+    }
+
+    pub fn get_smart_view(&self, id: i64) -> Result<SmartView> {
+        let conn = self.conn.lock().unwrap();
+        Self::get_smart_view_internal(&conn, id)
+    }
+
+    fn get_smart_view_internal(conn: &Connection, id: i64) -> Result<SmartView> {
+        conn.query_row(
+            &format!("SELECT {} FROM smart_views WHERE id = ?1", SMART_VIEW_COLUMNS),
+            params![id],
+            smart_view_from_row,
+        )
+    }
+
+    pub fn update_smart_view(&self, id: i64, updates: UpdateSmartViewDTO) -> Result<SmartView> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut set_clauses = Vec::new();
+        let mut params: Vec<Box<dyn ToSql>> = Vec::new();
+
+        if let Some(name) = updates.name {
+            set_clauses.push("name = ?");
+            params.push(Box::new(name.trim().to_string()));
+        }
+
+        if let Some(filter) = updates.filter {
+            compile_filter(&filter)?;
+            set_clauses.push("filter = ?");
+            params.push(Box::new(to_json(&filter)?));
+        }
+
+        if let Some(sort_by) = updates.sort_by {
+            set_clauses.push("sort_by = ?");
+            params.push(Box::new(enum_to_sql(&sort_by)?));
+        }
+
+        if let Some(sort_direction) = updates.sort_direction {
+            set_clauses.push("sort_direction = ?");
+            params.push(Box::new(enum_to_sql(&sort_direction)?));
+        }
+
+        if set_clauses.is_empty() {
+            return Err(rusqlite::Error::InvalidParameterCount(0, 1));
+        }
+
+        set_clauses.push("updated_at = CURRENT_TIMESTAMP");
+        params.push(Box::new(id));
+
+        let query = format!(
+            "UPDATE smart_views SET {} WHERE id = ?",
+            set_clauses.join(", ")
+        );
+
+        let params_ref: Vec<&dyn ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        conn.execute(&query, params_ref.as_slice())?;
+
+        Self::get_smart_view_internal(&conn, id)
+    }
+
+    pub fn delete_smart_view(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM smart_views WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// Ejecuta una vista guardada
+    pub fn run_smart_view(&self, id: i64) -> Result<Vec<Project>> {
+        let view = self.get_smart_view(id)?;
+        self.query_projects(&view.filter, view.sort_by, view.sort_direction)
+    }
+
+    /// Evalúa un filtro de vista guardada. Los criterios que dependen de git
+    /// (`has_uncommitted_changes`) se comprueban sobre el resultado de la consulta.
+    /// Con ese criterio se lee el estado de git de cada proyecto: no llamar desde
+    /// el runtime async.
+    pub fn query_projects(
+        &self,
+        filter: &SmartViewFilter,
+        sort_by: SmartViewSortField,
+        sort_direction: SortDirection,
+    ) -> Result<Vec<Project>> {
+        let mut projects = self.query_projects_sql(filter, sort_by, sort_direction)?;
+
+        // El estado de git no está en la base de datos; se consulta sin bloquear la conexión
+        if let Some(expected) = filter.has_uncommitted_changes {
+            projects.retain(|p| git::has_uncommitted_changes(&p.local_path) == expected);
+        }
+
+        Ok(projects)
+    }
+
+    fn query_projects_sql(
+        &self,
+        filter: &SmartViewFilter,
+        sort_by: SmartViewSortField,
+        sort_direction: SortDirection,
+    ) -> Result<Vec<Project>> {
+        let conn = self.conn.lock().unwrap();
+
+        let (clauses, params) = compile_filter(filter)?;
+        let where_sql = if clauses.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", clauses.join(" AND "))
+        };
+        let direction = match sort_direction {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        };
+
+        let query = format!(
+            "SELECT {} FROM projects {} ORDER BY {} {}, name COLLATE NOCASE ASC",
+            PROJECT_LIST_COLUMNS,
+            where_sql,
+            sort_column_sql(sort_by),
+            direction
+        );
+
+        let mut stmt = conn.prepare_cached(&query)?;
+        let params_ref: Vec<&dyn ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let mut projects = stmt
+            .query_map(params_ref.as_slice(), Self::project_from_row)?
+            .collect::<Result<Vec<_>>>()?;
+
+        Self::attach_links(&conn, &mut projects)?;
+
+        Ok(projects)
+    }
+}
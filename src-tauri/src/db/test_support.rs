@@ -0,0 +1,24 @@
+use std::path::PathBuf;
+
+use super::Database;
+use crate::models::project::{CreateProjectDTO, Project};
+
+/// Base de datos en memoria con el esquema completo: desaparece al soltarla
+pub fn memory_database() -> Database {
+    Database::new(PathBuf::from(":memory:")).expect("Error creando la base de datos en memoria")
+}
+
+/// Proyecto mínimo en `/tmp`, con la URL de documentación opcional
+pub fn create_project(db: &Database, name: &str, documentation_url: Option<String>) -> Project {
+    db.create_project(CreateProjectDTO {
+        name: name.to_string(),
+        description: String::new(),
+        local_path: "/tmp".to_string(),
+        documentation_url,
+        ai_documentation_url: None,
+        drive_link: None,
+        notes: None,
+        image_data: None,
+    })
+    .expect("Error creando el proyecto de prueba")
+}
@@ -0,0 +1,111 @@
+//! Ramas locales y remotas.
+//!
+//! Cambiar de rama se niega con cambios sin commitear (los archivos sin
+//! seguimiento no cuentan, como en `git switch`) salvo con auto-stash: los
+//! cambios se guardan en un stash, se cambia de rama y se recuperan encima.
+//! Si al recuperarlos hay conflictos, el stash se conserva para no perder nada.
+
+use serde::Serialize;
+
+use super::{backend, FileState, GitCommit};
+
+/// Rama local (`main`) o remota (`origin/main`)
+#[derive(Debug, Clone, Serialize)]
+pub struct GitBranch {
+    pub name: String,
+    pub remote: bool,
+    /// Rama actual (solo locales)
+    pub current: bool,
+    /// Rama remota que sigue (ej: `origin/main`)
+    pub upstream: Option<String>,
+    /// El upstream está configurado pero ya no existe (ej: se borró tras un merge)
+    pub upstream_gone: bool,
+    pub ahead: u32,
+    pub behind: u32,
+    pub last_commit: Option<GitCommit>,
+}
+
+/// Resultado de cambiar de rama
+#[derive(Debug, Clone, Serialize)]
+pub struct BranchSwitch {
+    /// Rama local en la que se ha quedado
+    pub branch: String,
+    /// Se guardaron los cambios en un stash antes de cambiar
+    pub stashed: bool,
+    /// Los cambios se recuperaron sobre la nueva rama; si no (por ejemplo, por
+    /// conflictos que quedan marcados en los archivos), siguen en `stash@{0}`
+    pub restored: bool,
+}
+
+/// Cambia a `name`. Con una rama remota se crea (o se usa) la local que la sigue.
+pub fn switch_branch(path: &str, name: &str, auto_stash: bool) -> Result<BranchSwitch, String> {
+    let branches = backend().branches(path)?;
+    let branch = branches
+        .iter()
+        .find(|branch| branch.name == name)
+        .ok_or_else(|| format!("La rama '{}' no existe", name))?;
+
+    // `origin/feature/x` -> `feature/x`
+    let local = if branch.remote {
+        name.split_once('/').map_or(name, |(_, local)| local).to_string()
+    } else {
+        name.to_string()
+    };
+
+    if branches.iter().any(|branch| branch.current && branch.name == local) {
+        return Ok(BranchSwitch {
+            branch: local,
+            stashed: false,
+            restored: false,
+        });
+    }
+
+    let dirty = backend()
+        .status(path)?
+        .entries
+        .iter()
+        .any(|entry| entry.index != FileState::Untracked);
+    if dirty && !auto_stash {
+        return Err(
+            "Hay cambios sin commitear: haz commit, guárdalos en un stash o usa el auto-stash"
+                .to_string(),
+        );
+    }
+
+    if branch.remote && !branches.iter().any(|branch| !branch.remote && branch.name == local) {
+        backend().create_branch(path, &local, Some(name))?;
+    }
+
+    if dirty {
+        let message = format!("auto-stash al cambiar a {}", local);
+        backend().stash_save(path, Some(&message), false)?;
+        println!("📦 [GIT] Cambios guardados en stash antes de cambiar a '{}'", local);
+    }
+
+    if let Err(e) = backend().checkout_branch(path, &local) {
+        // Sin cambiar de rama: los cambios vuelven a donde estaban. El error que
+        // importa es el del checkout; si no se recuperan, se avisa de dónde siguen
+        if dirty {
+            if let Err(pop_error) = backend().stash_pop(path, 0) {
+                println!("⚠️ [GIT] No se pudo recuperar el auto-stash: {}", pop_error);
+                return Err(format!("{} (los cambios siguen guardados en stash@{{0}})", e));
+            }
+        }
+        return Err(e);
+    }
+
+    let restored = dirty
+        && match backend().stash_pop(path, 0) {
+            Ok(()) => true,
+            Err(e) => {
+                println!("⚠️ [GIT] Los cambios siguen en el stash: {}", e);
+                false
+            }
+        };
+
+    Ok(BranchSwitch {
+        branch: local,
+        stashed: dirty,
+        restored,
+    })
+}
@@ -0,0 +1,658 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+use super::diff::parse_unified;
+use super::{
+    ApplyTarget, FileDiff, FileState, GitBackend, GitBranch, GitCommit, GitStash, GitStatus,
+    GitStatusEntry,
+};
+
+/// Backend que lanza el ejecutable `git`
+pub struct CliBackend;
+
+/// Separador de campos en los formatos de `git log` (no aparece en nombres ni mensajes)
+const FIELD_SEPARATOR: char = '\u{1f}';
+
+/// Opciones comunes de los diffs: formato fijo aunque el usuario configure otro
+const DIFF_OPTIONS: [&str; 6] = [
+    "--no-color",
+    "--no-ext-diff",
+    "--no-textconv",
+    "-M",
+    "--src-prefix=a/",
+    "--dst-prefix=b/",
+];
+
+fn git(path: &str, args: &[&str]) -> Result<Output, String> {
+    Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to execute git command: {}", e))
+}
+
+/// Como `git_stdout`, pasando `input` por la entrada estándar
+fn git_stdin(path: &str, args: &[&str], input: &str, error: &str) -> Result<String, String> {
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to execute git command: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(input.as_bytes())
+            .map_err(|e| format!("Failed to write to git: {}", e))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to execute git command: {}", e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(format!("{}: {}", error, String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+/// Formato de `git for-each-ref` para las ramas: los campos de `GitBranch` y su último commit
+const BRANCH_FORMAT: &str = "%(refname)%1f%(HEAD)%1f%(symref)%1f%(upstream:short)%1f\
+%(upstream:track,nobracket)%1f%(objectname)%1f%(authorname)%1f%(committerdate:unix)%1f%(contents:subject)";
+
+/// Interpreta una línea de `BRANCH_FORMAT`; `None` para las referencias simbólicas (`origin/HEAD`)
+fn parse_branch(line: &str) -> Option<GitBranch> {
+    let mut fields = line.splitn(9, FIELD_SEPARATOR);
+    let refname = fields.next()?;
+    let current = fields.next()? == "*";
+    if !fields.next()?.is_empty() {
+        return None;
+    }
+    let upstream = Some(fields.next()?.to_string()).filter(|upstream| !upstream.is_empty());
+    // "ahead 1, behind 2", "gone" o vacío
+    let track = fields.next()?;
+    let count = |label: &str| {
+        track
+            .split(", ")
+            .find_map(|part| part.strip_prefix(label))
+            .and_then(|count| count.trim().parse().ok())
+            .unwrap_or(0)
+    };
+
+    let (name, remote) = match refname.strip_prefix("refs/heads/") {
+        Some(name) => (name, false),
+        None => (refname.strip_prefix("refs/remotes/")?, true),
+    };
+    let hash = fields.next()?.to_string();
+    let author = fields.next()?.to_string();
+    let timestamp = fields.next()?.parse().ok();
+    let message = fields.next().unwrap_or("").to_string();
+
+    Some(GitBranch {
+        name: name.to_string(),
+        remote,
+        current,
+        upstream,
+        upstream_gone: track == "gone",
+        ahead: count("ahead "),
+        behind: count("behind "),
+        last_commit: timestamp.map(|timestamp| GitCommit::new(hash, author, timestamp, message)),
+    })
+}
+
+/// Los nombres de rama no pueden empezar por `-`: git los tomaría por opciones
+fn check_branch_name(name: &str) -> Result<(), String> {
+    if name.starts_with('-') || name.trim().is_empty() {
+        Err(format!("Nombre de rama no válido: '{}'", name))
+    } else {
+        Ok(())
+    }
+}
+
+/// El punto de partida de una rama tampoco puede empezar por `-`
+fn check_start_point(start_point: &str) -> Result<(), String> {
+    if start_point.starts_with('-') || start_point.trim().is_empty() {
+        Err(format!("Punto de partida no válido: '{}'", start_point))
+    } else {
+        Ok(())
+    }
+}
+
+/// Rutas desde la raíz del repositorio, sin comodines, para usar tras `--`
+fn top_pathspecs(files: &[String]) -> Vec<String> {
+    files
+        .iter()
+        .map(|file| format!(":(top,literal){}", file))
+        .collect()
+}
+
+/// Salida estándar de un comando que debe terminar bien; si falla, `error` y el stderr
+fn git_stdout(path: &str, args: &[&str], error: &str) -> Result<String, String> {
+    let output = git(path, args)?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(format!("{}: {}", error, String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+/// Interpreta `git status --porcelain=v2 -z --branch --show-stash`:
+///
+/// ```text
+/// # branch.oid <hash> | (initial)      # branch.head <rama> | (detached)
+/// # branch.upstream <upstream>         # branch.ab +<ahead> -<behind>
+/// # stash <n>
+/// 1 XY sub mH mI mW hH hI ruta                     (cambio)
+/// 2 XY sub mH mI mW hH hI Xpuntuación ruta\0anterior (renombrado o copia)
+/// u XY sub m1 m2 m3 mW h1 h2 h3 ruta               (conflicto)
+/// ? ruta                                           (sin seguimiento)
+/// ```
+pub(super) fn parse_porcelain_v2(output: &str) -> GitStatus {
+    let mut status = GitStatus::default();
+    let mut records = output.split('\0').filter(|record| !record.is_empty());
+
+    while let Some(record) = records.next() {
+        let (kind, rest) = record.split_once(' ').unwrap_or((record, ""));
+
+        // Campos antes de la ruta (que puede contener espacios)
+        let fields_before_path = match kind {
+            "#" => {
+                parse_header(&mut status, rest);
+                continue;
+            }
+            "?" => {
+                status.entries.push(GitStatusEntry::new(
+                    rest.to_string(),
+                    FileState::Untracked,
+                    FileState::Untracked,
+                ));
+                continue;
+            }
+            "1" => 7,
+            "2" => 8,
+            "u" => 9,
+            _ => continue,
+        };
+
+        let fields: Vec<&str> = rest.splitn(fields_before_path + 1, ' ').collect();
+        let (Some(xy), Some(submodule), Some(path)) =
+            (fields.first(), fields.get(1), fields.get(fields_before_path))
+        else {
+            continue;
+        };
+        let mut states = xy.chars().map(FileState::from_char);
+
+        let mut entry = GitStatusEntry::new(
+            path.to_string(),
+            states.next().unwrap_or(FileState::Unmodified),
+            states.next().unwrap_or(FileState::Unmodified),
+        );
+        entry.submodule = submodule.starts_with('S');
+        entry.conflicted = kind == "u";
+        if kind == "2" {
+            entry.orig_path = records.next().map(str::to_string);
+        }
+
+        status.entries.push(entry);
+    }
+
+    status
+}
+
+fn parse_header(status: &mut GitStatus, header: &str) {
+    let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+
+    match key {
+        "branch.oid" => status.head = (value != "(initial)").then(|| value.to_string()),
+        "branch.head" => status.branch = (value != "(detached)").then(|| value.to_string()),
+        "branch.upstream" => status.upstream = Some(value.to_string()),
+        "branch.ab" => {
+            let mut counts = value
+                .split_whitespace()
+                .map(|count| count.trim_start_matches(['+', '-']).parse().unwrap_or(0));
+            status.ahead = counts.next().unwrap_or(0);
+            status.behind = counts.next().unwrap_or(0);
+        }
+        "stash" => status.stash_count = value.parse().unwrap_or(0),
+        _ => {}
+    }
+}
+
+impl GitBackend for CliBackend {
+    fn current_branch(&self, path: &str) -> Result<String, String> {
+        let output = git(path, &["rev-parse", "--abbrev-ref", "HEAD"])?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            Err("Not a git repository".to_string())
+        }
+    }
+
+    fn workdir(&self, path: &str) -> Result<PathBuf, String> {
+        let output = git(path, &["rev-parse", "--show-toplevel"])?;
+        let workdir = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if output.status.success() && !workdir.is_empty() {
+            Ok(PathBuf::from(workdir))
+        } else {
+            Err("Not a git repository".to_string())
+        }
+    }
+
+    fn status(&self, path: &str) -> Result<GitStatus, String> {
+        let output = git(
+            path,
+            &["status", "--porcelain=v2", "-z", "--branch", "--show-stash"],
+        )?;
+        if output.status.success() {
+            Ok(parse_porcelain_v2(&String::from_utf8_lossy(&output.stdout)))
+        } else {
+            Err("Not a git repository".to_string())
+        }
+    }
+
+    fn recent_commits(&self, path: &str, limit: usize) -> Result<Vec<GitCommit>, String> {
+        let output = git(
+            path,
+            &[
+                "log",
+                &format!("-{}", limit),
+                "--pretty=format:%H%x1f%an%x1f%ct%x1f%s",
+            ],
+        )?;
+        if !output.status.success() {
+            return Err("Not a git repository or no commits".to_string());
+        }
+
+        let commits = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                // El asunto va al final: puede contener cualquier carácter
+                let mut fields = line.splitn(4, FIELD_SEPARATOR);
+                let hash = fields.next()?.to_string();
+                let author = fields.next()?.to_string();
+                let timestamp = fields.next()?.parse().ok()?;
+                let message = fields.next().unwrap_or("").to_string();
+                Some(GitCommit::new(hash, author, timestamp, message))
+            })
+            .collect();
+
+        Ok(commits)
+    }
+
+    fn last_commit_time(&self, path: &str) -> Result<Option<i64>, String> {
+        let output = git(path, &["log", "-1", "--format=%ct"])?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().parse().ok())
+    }
+
+    fn remote_url(&self, path: &str, remote: &str) -> Result<Option<String>, String> {
+        let output = git(path, &["remote", "get-url", remote])?;
+        let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+        Ok((output.status.success() && !url.is_empty()).then_some(url))
+    }
+
+    fn diff(&self, path: &str, file: Option<&str>, staged: bool) -> Result<Vec<FileDiff>, String> {
+        let pathspec = file.map(|file| format!(":(top,literal){}", file));
+
+        let mut args = vec!["-c", "core.quotePath=false", "diff"];
+        args.extend(DIFF_OPTIONS);
+        if staged {
+            args.push("--cached");
+        }
+        args.push("--");
+        args.extend(pathspec.as_deref());
+
+        let stdout = git_stdout(path, &args, "Error getting diff")?;
+        Ok(parse_unified(&stdout))
+    }
+
+    fn commit_diff(&self, path: &str, hash: &str) -> Result<Vec<FileDiff>, String> {
+        let output = git(path, &["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", hash)])?;
+        if !output.status.success() {
+            return Err(format!("Error getting commit diff: commit '{}' no encontrado", hash));
+        }
+        let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let commit = commit.as_str();
+        let parent = format!("{}^", commit);
+        let has_parent = git(path, &["rev-parse", "--verify", "--quiet", &parent])?
+            .status
+            .success();
+
+        let mut args = vec!["-c", "core.quotePath=false"];
+        if has_parent {
+            args.push("diff");
+            args.extend(DIFF_OPTIONS);
+            args.extend([parent.as_str(), commit]);
+        } else {
+            // Primer commit: todo es nuevo
+            args.extend(["diff-tree", "-p", "-r", "--root", "--no-commit-id"]);
+            args.extend(DIFF_OPTIONS);
+            args.push(commit);
+        }
+
+        let stdout = git_stdout(path, &args, "Error getting commit diff")?;
+        Ok(parse_unified(&stdout))
+    }
+
+    fn stage(&self, path: &str, files: &[String]) -> Result<(), String> {
+        let pathspecs = top_pathspecs(files);
+        let mut args = vec!["add", "--"];
+        args.extend(pathspecs.iter().map(String::as_str));
+
+        git_stdout(path, &args, "Error staging files").map(|_| ())
+    }
+
+    fn unstage(&self, path: &str, files: &[String]) -> Result<(), String> {
+        let pathspecs = top_pathspecs(files);
+        let mut args = vec!["reset", "--quiet", "--"];
+        args.extend(pathspecs.iter().map(String::as_str));
+
+        git_stdout(path, &args, "Error unstaging files").map(|_| ())
+    }
+
+    fn discard(&self, path: &str, files: &[String]) -> Result<(), String> {
+        let pathspecs = top_pathspecs(files);
+        let mut args = vec!["checkout", "--quiet", "--"];
+        args.extend(pathspecs.iter().map(String::as_str));
+
+        git_stdout(path, &args, "Error discarding changes").map(|_| ())
+    }
+
+    fn apply(&self, path: &str, patch: &str, target: ApplyTarget) -> Result<(), String> {
+        // Las rutas del parche son desde la raíz: se aplica allí
+        let workdir = self.workdir(path)?;
+        let mut args = vec!["apply", "--whitespace=nowarn"];
+        if target == ApplyTarget::Index {
+            args.push("--cached");
+        }
+        args.push("-");
+
+        git_stdin(&workdir.to_string_lossy(), &args, patch, "Error applying patch").map(|_| ())
+    }
+
+    fn commit(&self, path: &str, message: &str) -> Result<String, String> {
+        let stdout = git_stdout(path, &["commit", "-m", message], "Error creating commit")?;
+        Ok(stdout.lines().next().unwrap_or("").to_string())
+    }
+
+    fn branches(&self, path: &str) -> Result<Vec<GitBranch>, String> {
+        let format = format!("--format={}", BRANCH_FORMAT);
+        let stdout = git_stdout(
+            path,
+            &["for-each-ref", &format, "refs/heads", "refs/remotes"],
+            "Error listing branches",
+        )?;
+
+        let mut branches: Vec<GitBranch> = stdout.lines().filter_map(parse_branch).collect();
+        branches.sort_by(|a, b| (a.remote, &a.name).cmp(&(b.remote, &b.name)));
+        Ok(branches)
+    }
+
+    fn create_branch(&self, path: &str, name: &str, start_point: Option<&str>) -> Result<(), String> {
+        check_branch_name(name)?;
+        if let Some(start) = start_point {
+            check_start_point(start)?;
+        }
+
+        let mut args = vec!["branch"];
+        // Desde una rama remota se sigue aunque `branch.autoSetupMerge` esté desactivado
+        let remote_ref = start_point.map(|start| format!("refs/remotes/{}", start));
+        if let Some(remote_ref) = &remote_ref {
+            if git(path, &["rev-parse", "--verify", "--quiet", remote_ref])?.status.success() {
+                args.push("--track");
+            }
+        }
+        args.push(name);
+        args.extend(start_point);
+
+        git_stdout(path, &args, "Error creating branch").map(|_| ())
+    }
+
+    fn checkout_branch(&self, path: &str, name: &str) -> Result<(), String> {
+        check_branch_name(name)?;
+        // `--` al final: `name` es siempre una rama, nunca un archivo
+        git_stdout(path, &["checkout", "--quiet", name, "--"], "Error switching branch").map(|_| ())
+    }
+
+    fn rename_branch(&self, path: &str, old_name: &str, new_name: &str) -> Result<(), String> {
+        check_branch_name(old_name)?;
+        check_branch_name(new_name)?;
+        git_stdout(path, &["branch", "-m", old_name, new_name], "Error renaming branch").map(|_| ())
+    }
+
+    fn delete_branch(&self, path: &str, name: &str, force: bool) -> Result<(), String> {
+        check_branch_name(name)?;
+        let flag = if force { "-D" } else { "-d" };
+        git_stdout(path, &["branch", flag, name], "Error deleting branch").map(|_| ())
+    }
+
+    fn stash_save(&self, path: &str, message: Option<&str>, include_untracked: bool) -> Result<(), String> {
+        let mut args = vec!["stash", "push", "--quiet"];
+        if include_untracked {
+            args.push("--include-untracked");
+        }
+        if let Some(message) = message {
+            args.extend(["-m", message]);
+        }
+
+        // Sin cambios `git stash` no falla: se detecta porque `refs/stash` no se mueve
+        let stash_ref = || git_stdout(path, &["rev-parse", "--verify", "--quiet", "refs/stash"], "");
+        let before = stash_ref().ok();
+        git_stdout(path, &args, "Error creating stash")?;
+        if stash_ref().ok() == before {
+            return Err("Error creating stash: no hay cambios que guardar".to_string());
+        }
+        Ok(())
+    }
+
+    fn stashes(&self, path: &str) -> Result<Vec<GitStash>, String> {
+        // %gd: `stash@{n}`; %gs: asunto del reflog (`On rama: mensaje`)
+        let stdout = git_stdout(
+            path,
+            &["stash", "list", "--format=%gd%x1f%H%x1f%ct%x1f%gs"],
+            "Error listing stashes",
+        )?;
+
+        let stashes = stdout
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(4, FIELD_SEPARATOR);
+                let index = fields
+                    .next()?
+                    .strip_prefix("stash@{")?
+                    .strip_suffix('}')?
+                    .parse()
+                    .ok()?;
+                let hash = fields.next()?.to_string();
+                let timestamp = fields.next()?.parse().ok()?;
+                Some(GitStash::new(index, hash, timestamp, fields.next().unwrap_or("")))
+            })
+            .collect();
+
+        Ok(stashes)
+    }
+
+    fn stash_pop(&self, path: &str, index: usize) -> Result<(), String> {
+        let stash = format!("stash@{{{}}}", index);
+        git_stdout(path, &["stash", "pop", "--quiet", &stash], "Error applying stash").map(|_| ())
+    }
+
+    fn stash_apply(&self, path: &str, index: usize) -> Result<(), String> {
+        let stash = format!("stash@{{{}}}", index);
+        git_stdout(path, &["stash", "apply", "--quiet", &stash], "Error applying stash").map(|_| ())
+    }
+
+    fn stash_drop(&self, path: &str, index: usize) -> Result<(), String> {
+        let stash = format!("stash@{{{}}}", index);
+        git_stdout(path, &["stash", "drop", "--quiet", &stash], "Error dropping stash").map(|_| ())
+    }
+
+    fn fetch(&self, path: &str) -> Result<(), String> {
+        git_stdout(path, &["fetch", "--quiet"], "Error fetching").map(|_| ())
+    }
+
+    fn push(&self, path: &str) -> Result<String, String> {
+        let output = git(path, &["push"])?;
+        if output.status.success() {
+            // git push informa del resultado por stderr
+            Ok(format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            ))
+        } else {
+            Err(format!("Error pushing: {}", String::from_utf8_lossy(&output.stderr)))
+        }
+    }
+
+    fn pull(&self, path: &str) -> Result<String, String> {
+        git_stdout(path, &["pull"], "Error pulling")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "0123456789abcdef0123456789abcdef01234567";
+
+    /// Une los registros como los separa `git status -z`
+    fn records(records: &[&str]) -> String {
+        records.iter().map(|record| format!("{}\0", record)).collect()
+    }
+
+    #[test]
+    fn branch_headers() {
+        let status = parse_porcelain_v2(&records(&[
+            &format!("# branch.oid {}", HASH),
+            "# branch.head feature/login",
+            "# branch.upstream origin/feature/login",
+            "# branch.ab +2 -5",
+            "# stash 3",
+        ]));
+
+        assert_eq!(status.head.as_deref(), Some(HASH));
+        assert_eq!(status.branch.as_deref(), Some("feature/login"));
+        assert_eq!(status.upstream.as_deref(), Some("origin/feature/login"));
+        assert_eq!((status.ahead, status.behind), (2, 5));
+        assert_eq!(status.stash_count, 3);
+        assert!(status.entries.is_empty());
+    }
+
+    #[test]
+    fn new_repository_and_detached_head() {
+        let status = parse_porcelain_v2(&records(&["# branch.oid (initial)", "# branch.head main"]));
+        assert_eq!(status.head, None);
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!(status.upstream, None);
+
+        let status = parse_porcelain_v2(&records(&[
+            &format!("# branch.oid {}", HASH),
+            "# branch.head (detached)",
+        ]));
+        assert_eq!(status.head.as_deref(), Some(HASH));
+        assert_eq!(status.branch, None);
+    }
+
+    #[test]
+    fn ordinary_changes_keep_spaces_in_paths() {
+        let status = parse_porcelain_v2(&records(&[
+            &format!("1 M. N... 100644 100644 100644 {} {} src/main.rs", HASH, HASH),
+            &format!("1 .D N... 100644 100644 000000 {} {} docs/notas de diseño.md", HASH, HASH),
+            &format!("1 AM N... 000000 100644 100644 {} {} nuevo.txt", HASH, HASH),
+            &format!("1 .M SC.. 160000 160000 160000 {} {} vendor/lib", HASH, HASH),
+        ]));
+
+        let entries = &status.entries;
+        assert_eq!(entries.len(), 4);
+
+        assert_eq!(entries[0].path, "src/main.rs");
+        assert_eq!((entries[0].index, entries[0].worktree), (FileState::Modified, FileState::Unmodified));
+
+        assert_eq!(entries[1].path, "docs/notas de diseño.md");
+        assert_eq!((entries[1].index, entries[1].worktree), (FileState::Unmodified, FileState::Deleted));
+
+        assert_eq!((entries[2].index, entries[2].worktree), (FileState::Added, FileState::Modified));
+        assert!(!entries[2].submodule);
+
+        assert_eq!(entries[3].path, "vendor/lib");
+        assert!(entries[3].submodule);
+        assert!(entries.iter().all(|entry| !entry.conflicted && entry.orig_path.is_none()));
+    }
+
+    #[test]
+    fn renames_take_the_next_record_as_original_path() {
+        let status = parse_porcelain_v2(&records(&[
+            &format!("2 R. N... 100644 100644 100644 {} {} R100 src/nuevo nombre.rs", HASH, HASH),
+            "src/viejo.rs",
+            &format!("2 C. N... 100644 100644 100644 {} {} C75 copia.rs", HASH, HASH),
+            "original.rs",
+            "? suelto.txt",
+        ]));
+
+        let entries = &status.entries;
+        assert_eq!(entries.len(), 3);
+
+        assert_eq!(entries[0].path, "src/nuevo nombre.rs");
+        assert_eq!(entries[0].orig_path.as_deref(), Some("src/viejo.rs"));
+        assert_eq!(entries[0].index, FileState::Renamed);
+        assert_eq!(entries[0].porcelain(), "R  src/viejo.rs -> src/nuevo nombre.rs");
+
+        assert_eq!(entries[1].path, "copia.rs");
+        assert_eq!(entries[1].orig_path.as_deref(), Some("original.rs"));
+        assert_eq!(entries[1].index, FileState::Copied);
+
+        // La ruta anterior no se confunde con un registro propio
+        assert_eq!(entries[2].path, "suelto.txt");
+    }
+
+    #[test]
+    fn conflicts_and_untracked_files() {
+        let status = parse_porcelain_v2(&records(&[
+            &format!("u UU N... 100644 100644 100644 100644 {} {} {} en conflicto.rs", HASH, HASH, HASH),
+            &format!("u DU N... 100644 000000 100644 100644 {} {} {} borrado.rs", HASH, HASH, HASH),
+            "? nuevo archivo.txt",
+            "? dir/",
+        ]));
+
+        let entries = &status.entries;
+        assert_eq!(entries.len(), 4);
+
+        assert_eq!(entries[0].path, "en conflicto.rs");
+        assert!(entries[0].conflicted);
+        assert_eq!((entries[0].index, entries[0].worktree), (FileState::Conflicted, FileState::Conflicted));
+
+        assert_eq!(entries[1].path, "borrado.rs");
+        assert!(entries[1].conflicted);
+        assert_eq!((entries[1].index, entries[1].worktree), (FileState::Deleted, FileState::Conflicted));
+
+        for (entry, path) in entries[2..].iter().zip(["nuevo archivo.txt", "dir/"]) {
+            assert_eq!(entry.path, path);
+            assert_eq!((entry.index, entry.worktree), (FileState::Untracked, FileState::Untracked));
+            assert!(!entry.conflicted);
+        }
+    }
+
+    #[test]
+    fn unknown_and_truncated_records_are_skipped() {
+        let status = parse_porcelain_v2(&records(&[
+            "! ignorado.log",
+            "1 M. N...",
+            "# branch.desconocida x",
+            &format!("1 .M N... 100644 100644 100644 {} {} bien.rs", HASH, HASH),
+        ]));
+
+        assert_eq!(status.entries.len(), 1);
+        assert_eq!(status.entries[0].path, "bien.rs");
+        assert_eq!(status.branch, None);
+    }
+}
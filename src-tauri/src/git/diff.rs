@@ -0,0 +1,557 @@
+//! Diffs estructurados: archivos, hunks y líneas con su número en cada versión.
+//!
+//! Los dos backends producen los mismos tipos; la CLI a partir de la salida de
+//! `git diff` (`parse_unified`) y libgit2 directamente. Los cambios a nivel de
+//! palabra se calculan aquí para ambos (`highlight_words`).
+
+use serde::Serialize;
+
+use super::FileState;
+
+/// Tokens máximos por línea para comparar palabras; más allá se marca la línea entera
+const MAX_WORD_TOKENS: usize = 400;
+
+/// Cambios de un archivo
+#[derive(Debug, Clone, Serialize)]
+pub struct FileDiff {
+    /// Ruta nueva (la anterior si el archivo se ha borrado)
+    pub path: String,
+    /// Ruta anterior de un renombrado o copia
+    pub old_path: Option<String>,
+    /// `Added`, `Deleted`, `Modified`, `Renamed`, `Copied` o `TypeChanged`
+    pub status: FileState,
+    /// Los binarios no tienen hunks
+    pub binary: bool,
+    pub additions: usize,
+    pub deletions: usize,
+    pub hunks: Vec<DiffHunk>,
+}
+
+impl FileDiff {
+    fn new(path: String) -> Self {
+        FileDiff {
+            path,
+            old_path: None,
+            status: FileState::Modified,
+            binary: false,
+            additions: 0,
+            deletions: 0,
+            hunks: Vec::new(),
+        }
+    }
+}
+
+/// Bloque `@@ -old_start,old_lines +new_start,new_lines @@ header`
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffHunk {
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    /// Contexto que git añade tras `@@` (normalmente la función)
+    pub header: String,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffLineKind {
+    Context,
+    Addition,
+    Deletion,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    /// Contenido sin el salto de línea final
+    pub content: String,
+    /// Número de línea en la versión anterior (no en las añadidas)
+    pub old_lineno: Option<u32>,
+    /// Número de línea en la versión nueva (no en las borradas)
+    pub new_lineno: Option<u32>,
+    /// La línea no termina en salto de línea (`\ No newline at end of file`)
+    pub missing_newline: bool,
+    /// Trozos de la línea marcando los que cambian respecto a su pareja del otro
+    /// lado; vacío en el contexto y en las líneas sin pareja
+    pub words: Vec<WordSegment>,
+}
+
+impl DiffLine {
+    pub(super) fn new(
+        kind: DiffLineKind,
+        content: String,
+        old_lineno: Option<u32>,
+        new_lineno: Option<u32>,
+    ) -> Self {
+        DiffLine {
+            kind,
+            content,
+            old_lineno,
+            new_lineno,
+            missing_newline: false,
+            words: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WordSegment {
+    pub text: String,
+    pub changed: bool,
+}
+
+/// Interpreta la salida de `git diff` con prefijos `a/` y `b/` y sin colores
+pub(super) fn parse_unified(output: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    // Siguientes números de línea en cada versión dentro del hunk actual
+    let mut old_lineno = 0;
+    let mut new_lineno = 0;
+
+    for line in output.lines() {
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            files.push(FileDiff::new(path_from_header(paths)));
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+
+        // Dentro de un hunk
+        if let Some(hunk) = file.hunks.last_mut() {
+            let (kind, old, new) = match line.chars().next() {
+                Some(' ') => (DiffLineKind::Context, Some(old_lineno), Some(new_lineno)),
+                Some('+') => (DiffLineKind::Addition, None, Some(new_lineno)),
+                Some('-') => (DiffLineKind::Deletion, Some(old_lineno), None),
+                Some('\\') => {
+                    if let Some(last) = hunk.lines.last_mut() {
+                        last.missing_newline = true;
+                    }
+                    continue;
+                }
+                _ => (DiffLineKind::Context, None, None),
+            };
+
+            if old.is_some() || new.is_some() {
+                match kind {
+                    DiffLineKind::Addition => file.additions += 1,
+                    DiffLineKind::Deletion => file.deletions += 1,
+                    DiffLineKind::Context => {}
+                }
+                old_lineno += u32::from(old.is_some());
+                new_lineno += u32::from(new.is_some());
+                hunk.lines.push(DiffLine::new(kind, line[1..].to_string(), old, new));
+                continue;
+            }
+        }
+
+        if let Some(hunk) = parse_hunk_header(line) {
+            old_lineno = hunk.old_start;
+            new_lineno = hunk.new_start;
+            file.hunks.push(hunk);
+        } else if line.starts_with("new file mode") {
+            file.status = FileState::Added;
+        } else if line.starts_with("deleted file mode") {
+            file.status = FileState::Deleted;
+        } else if let Some(from) = line.strip_prefix("rename from ") {
+            file.status = FileState::Renamed;
+            file.old_path = Some(from.to_string());
+        } else if let Some(from) = line.strip_prefix("copy from ") {
+            file.status = FileState::Copied;
+            file.old_path = Some(from.to_string());
+        } else if let Some(to) = line.strip_prefix("rename to ").or(line.strip_prefix("copy to ")) {
+            file.path = to.to_string();
+        } else if let Some(path) = line.strip_prefix("+++ b/") {
+            // git añade un tabulador tras las rutas con espacios
+            file.path = path.trim_end_matches('\t').to_string();
+        } else if let Some(path) = line.strip_prefix("--- a/") {
+            // En los borrados `+++` es /dev/null
+            file.path = path.trim_end_matches('\t').to_string();
+        } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+            file.binary = true;
+        }
+    }
+
+    for file in &mut files {
+        highlight_words(&mut file.hunks);
+    }
+    files
+}
+
+/// Ruta de `diff --git a/ruta b/ruta`. Con espacios no se puede partir por ellos,
+/// pero sin renombrar las dos mitades son iguales; los renombrados y los
+/// archivos con texto la corrigen después con `rename to` o `+++`.
+fn path_from_header(paths: &str) -> String {
+    let half = paths.len() / 2;
+    if paths.len() % 2 == 1 && paths.is_char_boundary(half) {
+        let (old, new) = (&paths[..half], &paths[half + 1..]);
+        if let (Some(old), Some(new)) = (old.strip_prefix("a/"), new.strip_prefix("b/")) {
+            if old == new {
+                return new.to_string();
+            }
+        }
+    }
+
+    paths
+        .rsplit(" b/")
+        .next()
+        .unwrap_or(paths)
+        .to_string()
+}
+
+/// `@@ -1,3 +1,4 @@ contexto` (el número de líneas se omite cuando es 1)
+pub(super) fn parse_hunk_header(line: &str) -> Option<DiffHunk> {
+    let rest = line.strip_prefix("@@ -")?;
+    let (ranges, header) = rest.split_once(" @@")?;
+    let (old, new) = ranges.split_once(" +")?;
+
+    let range = |range: &str| -> Option<(u32, u32)> {
+        match range.split_once(',') {
+            Some((start, lines)) => Some((start.parse().ok()?, lines.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_lines) = range(old)?;
+    let (new_start, new_lines) = range(new)?;
+
+    Some(DiffHunk {
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        header: header.trim().to_string(),
+        lines: Vec::new(),
+    })
+}
+
+/// Empareja cada bloque de líneas borradas con las añadidas que le siguen
+/// (la primera con la primera...) y marca las palabras que difieren
+pub(super) fn highlight_words(hunks: &mut [DiffHunk]) {
+    for hunk in hunks {
+        let lines = &mut hunk.lines;
+        let mut i = 0;
+
+        while i < lines.len() {
+            if lines[i].kind != DiffLineKind::Deletion {
+                i += 1;
+                continue;
+            }
+
+            let deletions_start = i;
+            while i < lines.len() && lines[i].kind == DiffLineKind::Deletion {
+                i += 1;
+            }
+            let additions_start = i;
+            while i < lines.len() && lines[i].kind == DiffLineKind::Addition {
+                i += 1;
+            }
+
+            let pairs = (additions_start - deletions_start).min(i - additions_start);
+            for pair in 0..pairs {
+                let (old, new) =
+                    word_segments(&lines[deletions_start + pair].content, &lines[additions_start + pair].content);
+                lines[deletions_start + pair].words = old;
+                lines[additions_start + pair].words = new;
+            }
+        }
+    }
+}
+
+/// Palabras, espacios y signos sueltos
+fn tokenize(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((_, c)) = chars.next() {
+        let word = |c: char| c.is_alphanumeric() || c == '_';
+        let end = match chars.peek() {
+            Some(&(_, next)) if word(c) && word(next) => continue,
+            Some(&(_, next)) if c.is_whitespace() && next.is_whitespace() => continue,
+            Some(&(next_index, _)) => next_index,
+            None => text.len(),
+        };
+        tokens.push(&text[start..end]);
+        start = end;
+    }
+
+    tokens
+}
+
+/// Diferencia por palabras (subsecuencia común más larga) entre dos versiones de una línea
+fn word_segments(old: &str, new: &str) -> (Vec<WordSegment>, Vec<WordSegment>) {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+
+    if old_tokens.len() > MAX_WORD_TOKENS || new_tokens.len() > MAX_WORD_TOKENS {
+        return (
+            vec![WordSegment { text: old.to_string(), changed: true }],
+            vec![WordSegment { text: new.to_string(), changed: true }],
+        );
+    }
+
+    // lcs[i][j]: longitud de la subsecuencia común de old_tokens[i..] y new_tokens[j..]
+    let mut lcs = vec![vec![0u16; new_tokens.len() + 1]; old_tokens.len() + 1];
+    for i in (0..old_tokens.len()).rev() {
+        for j in (0..new_tokens.len()).rev() {
+            lcs[i][j] = if old_tokens[i] == new_tokens[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut old_segments = Vec::new();
+    let mut new_segments = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old_tokens.len() || j < new_tokens.len() {
+        if i < old_tokens.len() && j < new_tokens.len() && old_tokens[i] == new_tokens[j] {
+            push_segment(&mut old_segments, old_tokens[i], false);
+            push_segment(&mut new_segments, new_tokens[j], false);
+            i += 1;
+            j += 1;
+        } else if j < new_tokens.len() && (i == old_tokens.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            push_segment(&mut new_segments, new_tokens[j], true);
+            j += 1;
+        } else {
+            push_segment(&mut old_segments, old_tokens[i], true);
+            i += 1;
+        }
+    }
+
+    (old_segments, new_segments)
+}
+
+/// Añade un token uniéndolo al trozo anterior si tiene el mismo estado
+fn push_segment(segments: &mut Vec<WordSegment>, text: &str, changed: bool) {
+    match segments.last_mut() {
+        Some(last) if last.changed == changed => last.text.push_str(text),
+        _ => segments.push(WordSegment {
+            text: text.to_string(),
+            changed,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Trozos de una línea con los cambiados entre corchetes
+    fn marked(segments: &[WordSegment]) -> String {
+        segments
+            .iter()
+            .map(|segment| {
+                if segment.changed {
+                    format!("[{}]", segment.text)
+                } else {
+                    segment.text.clone()
+                }
+            })
+            .collect()
+    }
+
+    const DIFF: &str = "\
+diff --git a/src/main.rs b/src/main.rs
+index 1111111..2222222 100644
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,3 +1,3 @@ fn main() {
+ fn main() {
+-    let x = 1;
++    let y = 1;
+ }
+diff --git a/viejo.txt b/nuevo.txt
+similarity index 50%
+rename from viejo.txt
+rename to nuevo.txt
+index 3333333..4444444 100644
+--- a/viejo.txt
++++ b/nuevo.txt
+@@ -2 +2 @@
+-hola
+\\ No newline at end of file
++adiós
+\\ No newline at end of file
+diff --git a/docs/mis notas.md b/docs/mis notas.md
+new file mode 100644
+index 0000000..5555555
+--- /dev/null
++++ b/docs/mis notas.md\t
+@@ -0,0 +1,2 @@
++uno
++dos
+diff --git a/borrado.rs b/borrado.rs
+deleted file mode 100644
+index 6666666..0000000
+--- a/borrado.rs
++++ /dev/null
+@@ -1 +0,0 @@
+-adiós
+diff --git a/logo.png b/logo.png
+index 7777777..8888888 100644
+Binary files a/logo.png and b/logo.png differ
+";
+
+    #[test]
+    fn parse_unified_files_and_line_numbers() {
+        let files = parse_unified(DIFF);
+        assert_eq!(files.len(), 5);
+
+        let main = &files[0];
+        assert_eq!(main.path, "src/main.rs");
+        assert_eq!(main.status, FileState::Modified);
+        assert_eq!((main.additions, main.deletions), (1, 1));
+        assert_eq!(main.hunks.len(), 1);
+
+        let hunk = &main.hunks[0];
+        assert_eq!(hunk.header, "fn main() {");
+        let lines: Vec<_> = hunk
+            .lines
+            .iter()
+            .map(|line| (line.kind, line.content.as_str(), line.old_lineno, line.new_lineno))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (DiffLineKind::Context, "fn main() {", Some(1), Some(1)),
+                (DiffLineKind::Deletion, "    let x = 1;", Some(2), None),
+                (DiffLineKind::Addition, "    let y = 1;", None, Some(2)),
+                (DiffLineKind::Context, "}", Some(3), Some(3)),
+            ]
+        );
+        assert!(hunk.lines.iter().all(|line| !line.missing_newline));
+    }
+
+    #[test]
+    fn parse_unified_renames_and_missing_newline() {
+        let files = parse_unified(DIFF);
+        let renamed = &files[1];
+
+        assert_eq!(renamed.path, "nuevo.txt");
+        assert_eq!(renamed.old_path.as_deref(), Some("viejo.txt"));
+        assert_eq!(renamed.status, FileState::Renamed);
+
+        let lines = &renamed.hunks[0].lines;
+        assert_eq!(lines.len(), 2);
+        assert_eq!((lines[0].old_lineno, lines[1].new_lineno), (Some(2), Some(2)));
+        // La marca se aplica a la línea anterior y no cuenta como línea
+        assert!(lines.iter().all(|line| line.missing_newline));
+        assert_eq!((renamed.additions, renamed.deletions), (1, 1));
+    }
+
+    #[test]
+    fn parse_unified_added_deleted_and_binary_files() {
+        let files = parse_unified(DIFF);
+
+        let added = &files[2];
+        assert_eq!(added.path, "docs/mis notas.md");
+        assert_eq!(added.status, FileState::Added);
+        assert_eq!((added.additions, added.deletions), (2, 0));
+        assert_eq!(added.hunks[0].lines[1].new_lineno, Some(2));
+
+        let deleted = &files[3];
+        assert_eq!(deleted.path, "borrado.rs");
+        assert_eq!(deleted.status, FileState::Deleted);
+        assert_eq!((deleted.additions, deleted.deletions), (0, 1));
+
+        let binary = &files[4];
+        assert_eq!(binary.path, "logo.png");
+        assert!(binary.binary);
+        assert!(binary.hunks.is_empty());
+    }
+
+    #[test]
+    fn parse_unified_pure_rename_uses_rename_lines() {
+        let files = parse_unified(
+            "diff --git a/viejo dir/x.rs b/nuevo dir/x.rs\n\
+             similarity index 100%\n\
+             rename from viejo dir/x.rs\n\
+             rename to nuevo dir/x.rs\n",
+        );
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "nuevo dir/x.rs");
+        assert_eq!(files[0].old_path.as_deref(), Some("viejo dir/x.rs"));
+        assert!(files[0].hunks.is_empty());
+    }
+
+    #[test]
+    fn path_from_header_cases() {
+        assert_eq!(path_from_header("a/src/main.rs b/src/main.rs"), "src/main.rs");
+        assert_eq!(path_from_header("a/mis notas.md b/mis notas.md"), "mis notas.md");
+        // Iguales a ambos lados aunque la ruta contenga " b/"
+        assert_eq!(path_from_header("a/a b/c b/a b/c"), "a b/c");
+        // Renombrado: la ruta nueva
+        assert_eq!(path_from_header("a/viejo.rs b/nuevo.rs"), "nuevo.rs");
+    }
+
+    #[test]
+    fn parse_hunk_header_cases() {
+        let hunk = parse_hunk_header("@@ -10,7 +12,8 @@ impl Foo {").unwrap();
+        assert_eq!((hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines), (10, 7, 12, 8));
+        assert_eq!(hunk.header, "impl Foo {");
+        assert!(hunk.lines.is_empty());
+
+        // Sin número de líneas: es 1
+        let hunk = parse_hunk_header("@@ -3 +4 @@").unwrap();
+        assert_eq!((hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines), (3, 1, 4, 1));
+        assert_eq!(hunk.header, "");
+
+        let hunk = parse_hunk_header("@@ -0,0 +1,3 @@").unwrap();
+        assert_eq!((hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines), (0, 0, 1, 3));
+
+        assert!(parse_hunk_header("@@ -a,1 +1 @@").is_none());
+        assert!(parse_hunk_header("@@ -1,2 @@").is_none());
+        assert!(parse_hunk_header("@@@ -1,2 -1,2 +1,3 @@@").is_none());
+        assert!(parse_hunk_header(" fn main() {").is_none());
+    }
+
+    #[test]
+    fn tokenize_groups_words_and_spaces() {
+        assert_eq!(
+            tokenize("foo_bar(x,  año)"),
+            vec!["foo_bar", "(", "x", ",", "  ", "año", ")"]
+        );
+        assert!(tokenize("").is_empty());
+    }
+
+    #[test]
+    fn word_segments_mark_changed_words() {
+        let (old, new) = word_segments("let x = 1;", "let y = 1;");
+        assert_eq!(marked(&old), "let [x] = 1;");
+        assert_eq!(marked(&new), "let [y] = 1;");
+
+        let (old, new) = word_segments("adiós mundo", "hola mundo cruel");
+        assert_eq!(marked(&old), "[adiós] mundo");
+        assert_eq!(marked(&new), "[hola] mundo[ cruel]");
+
+        // Demasiados tokens: la línea entera cambia
+        let long = "a ".repeat(MAX_WORD_TOKENS);
+        let (old, new) = word_segments(&long, "b");
+        assert_eq!(old.len(), 1);
+        assert!(old[0].changed && new[0].changed);
+    }
+
+    #[test]
+    fn highlight_words_pairs_deletions_with_following_additions() {
+        let files = parse_unified(
+            "diff --git a/f b/f\n\
+             --- a/f\n\
+             +++ b/f\n\
+             @@ -1,3 +1,2 @@\n\
+             -uno dos\n\
+             -tres\n\
+             +uno 2\n \
+             fin\n",
+        );
+        let lines = &files[0].hunks[0].lines;
+
+        assert_eq!(marked(&lines[0].words), "uno [dos]");
+        assert_eq!(marked(&lines[2].words), "uno [2]");
+        // Sin pareja y contexto: sin trozos
+        assert!(lines[1].words.is_empty());
+        assert!(lines[3].words.is_empty());
+    }
+}
@@ -0,0 +1,975 @@
+use git2::build::CheckoutBuilder;
+use git2::{
+    ApplyLocation, Branch, BranchType, Commit, Delta, Diff, DiffFindOptions, DiffOptions,
+    ErrorCode, Patch, Repository, RepositoryState, Sort, StashFlags, Status, StatusOptions,
+};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::cli::CliBackend;
+use super::diff::{highlight_words, parse_hunk_header, DiffLine, DiffLineKind};
+use super::{
+    ApplyTarget, FileDiff, FileState, GitBackend, GitBranch, GitCommit, GitStash, GitStatus,
+    GitStatusEntry,
+};
+
+/// Backend en proceso con libgit2. Delega en `CliBackend` las operaciones de red
+/// y los repositorios que libgit2 no puede abrir.
+pub struct LibGit2Backend;
+
+/// Hooks que `git commit` ejecuta y libgit2 no
+const COMMIT_HOOKS: [&str; 3] = ["pre-commit", "prepare-commit-msg", "commit-msg"];
+
+fn git_error(e: git2::Error) -> String {
+    e.message().to_string()
+}
+
+/// Abre el repositorio que contiene `path` (como `git -C path`).
+/// `None` si existe pero libgit2 no lo soporta: hay que usar la CLI.
+fn open(path: &str) -> Result<Option<Repository>, String> {
+    match Repository::discover(path) {
+        Ok(repo) => Ok(Some(repo)),
+        Err(e) if e.code() == ErrorCode::NotFound => Err("Not a git repository".to_string()),
+        Err(e) => {
+            println!("⚠️ [GIT] libgit2 no puede abrir '{}', se usa git: {}", path, e.message());
+            Ok(None)
+        }
+    }
+}
+
+/// Commit de `HEAD`; `None` si la rama aún no tiene commits
+fn head_commit(repo: &Repository) -> Result<Option<Commit<'_>>, String> {
+    match repo.head() {
+        Ok(head) => head.peel_to_commit().map(Some).map_err(git_error),
+        Err(e) if matches!(e.code(), ErrorCode::UnbornBranch | ErrorCode::NotFound) => Ok(None),
+        Err(e) => Err(git_error(e)),
+    }
+}
+
+fn commit_info(commit: &Commit) -> GitCommit {
+    let author = commit.author();
+    GitCommit::new(
+        commit.id().to_string(),
+        String::from_utf8_lossy(author.name_bytes()).to_string(),
+        commit.time().seconds(),
+        String::from_utf8_lossy(commit.summary_bytes().unwrap_or_default()).to_string(),
+    )
+}
+
+fn check_branch_name(name: &str) -> Result<(), String> {
+    if Branch::name_is_valid(name).unwrap_or(false) {
+        Ok(())
+    } else {
+        Err(format!("Nombre de rama no válido: '{}'", name))
+    }
+}
+
+fn find_local_branch<'r>(repo: &'r Repository, name: &str) -> Result<Branch<'r>, String> {
+    repo.find_branch(name, BranchType::Local)
+        .map_err(|_| format!("La rama '{}' no existe", name))
+}
+
+/// Rama de la lista con su upstream (si es local) y su último commit
+fn branch_info(repo: &Repository, branch: &Branch, remote: bool) -> Option<GitBranch> {
+    let reference = branch.get();
+    // `origin/HEAD` apunta a otra rama: no es una rama en sí
+    if reference.symbolic_target().is_some() {
+        return None;
+    }
+
+    let mut info = GitBranch {
+        name: branch.name().ok()??.to_string(),
+        remote,
+        current: !remote && branch.is_head(),
+        upstream: None,
+        upstream_gone: false,
+        ahead: 0,
+        behind: 0,
+        last_commit: reference.peel_to_commit().ok().map(|commit| commit_info(&commit)),
+    };
+    if remote {
+        return Some(info);
+    }
+
+    // Upstream configurado, exista o no todavía la rama remota
+    let Some(upstream) = reference
+        .name()
+        .and_then(|refname| repo.branch_upstream_name(refname).ok())
+        .and_then(|upstream| upstream.as_str().map(str::to_string))
+    else {
+        return Some(info);
+    };
+    info.upstream = Some(
+        upstream
+            .strip_prefix("refs/remotes/")
+            .or_else(|| upstream.strip_prefix("refs/heads/"))
+            .unwrap_or(&upstream)
+            .to_string(),
+    );
+
+    match repo.refname_to_id(&upstream) {
+        Ok(remote_oid) => {
+            if let Some(local_oid) = reference.target() {
+                if let Ok((ahead, behind)) = repo.graph_ahead_behind(local_oid, remote_oid) {
+                    info.ahead = ahead as u32;
+                    info.behind = behind as u32;
+                }
+            }
+        }
+        Err(_) => info.upstream_gone = true,
+    }
+
+    Some(info)
+}
+
+/// Aplica `stash@{index}`. Con conflictos quedan marcados en los archivos y se
+/// devuelve error, como `git stash apply`, para que `pop` no borre el stash.
+fn apply_stash(repo: &mut Repository, index: usize) -> Result<(), String> {
+    repo.stash_apply(index, None)
+        .map_err(|e| format!("Error applying stash: {}", e.message()))?;
+
+    if repo.index().map_err(git_error)?.has_conflicts() {
+        return Err(format!(
+            "Error applying stash: hay conflictos, stash@{{{}}} se conserva",
+            index
+        ));
+    }
+    Ok(())
+}
+
+/// Estado del índice y del directorio de trabajo según los flags de libgit2.
+/// Un archivo sin seguimiento tiene `Untracked` en el directorio de trabajo.
+fn file_states(status: Status) -> (FileState, FileState) {
+    if status.is_conflicted() {
+        return (FileState::Conflicted, FileState::Conflicted);
+    }
+
+    let index = if status.is_index_new() {
+        FileState::Added
+    } else if status.is_index_modified() {
+        FileState::Modified
+    } else if status.is_index_deleted() {
+        FileState::Deleted
+    } else if status.is_index_renamed() {
+        FileState::Renamed
+    } else if status.is_index_typechange() {
+        FileState::TypeChanged
+    } else {
+        FileState::Unmodified
+    };
+
+    let worktree = if status.is_wt_new() {
+        FileState::Untracked
+    } else if status.is_wt_modified() {
+        FileState::Modified
+    } else if status.is_wt_deleted() {
+        FileState::Deleted
+    } else if status.is_wt_renamed() {
+        FileState::Renamed
+    } else if status.is_wt_typechange() {
+        FileState::TypeChanged
+    } else {
+        FileState::Unmodified
+    };
+
+    (index, worktree)
+}
+
+/// Rama, `HEAD` y upstream con los commits por delante y por detrás
+fn branch_status(repo: &Repository) -> Result<GitStatus, String> {
+    let mut status = GitStatus::default();
+
+    let head = match repo.head() {
+        Ok(head) => head,
+        // Sin commits: HEAD apunta a una rama que aún no existe
+        Err(e) if matches!(e.code(), ErrorCode::UnbornBranch | ErrorCode::NotFound) => {
+            let head = repo.find_reference("HEAD").map_err(git_error)?;
+            status.branch = head
+                .symbolic_target()
+                .map(|target| target.strip_prefix("refs/heads/").unwrap_or(target).to_string());
+            return Ok(status);
+        }
+        Err(e) => return Err(git_error(e)),
+    };
+
+    status.head = head.target().map(|oid| oid.to_string());
+    if !head.is_branch() {
+        return Ok(status);
+    }
+    let Some(name) = head.shorthand() else {
+        return Ok(status);
+    };
+    status.branch = Some(name.to_string());
+
+    // Sin upstream configurado no hay nada más que calcular
+    let Ok(upstream) = repo
+        .find_branch(name, BranchType::Local)
+        .and_then(|branch| branch.upstream())
+    else {
+        return Ok(status);
+    };
+    status.upstream = upstream.name().ok().flatten().map(str::to_string);
+
+    if let (Some(local), Some(remote)) = (head.target(), upstream.get().target()) {
+        let (ahead, behind) = repo.graph_ahead_behind(local, remote).map_err(git_error)?;
+        status.ahead = ahead as u32;
+        status.behind = behind as u32;
+    }
+
+    Ok(status)
+}
+
+/// Estados "nuestro" y "suyo" de cada conflicto del índice, como las letras de
+/// `git status` (`UU`, `AA`, `DU`...) según qué versiones existan
+fn conflict_states(repo: &Repository) -> Result<HashMap<String, (FileState, FileState)>, String> {
+    let index = repo.index().map_err(git_error)?;
+    let mut states = HashMap::new();
+
+    for conflict in index.conflicts().map_err(git_error)? {
+        let conflict = conflict.map_err(git_error)?;
+        let Some(path) = [&conflict.our, &conflict.their, &conflict.ancestor]
+            .into_iter()
+            .flatten()
+            .next()
+            .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+        else {
+            continue;
+        };
+
+        let state = match (
+            conflict.ancestor.is_some(),
+            conflict.our.is_some(),
+            conflict.their.is_some(),
+        ) {
+            (true, false, false) => (FileState::Deleted, FileState::Deleted),
+            (false, true, false) => (FileState::Added, FileState::Conflicted),
+            (true, true, false) => (FileState::Conflicted, FileState::Deleted),
+            (false, false, true) => (FileState::Conflicted, FileState::Added),
+            (true, false, true) => (FileState::Deleted, FileState::Conflicted),
+            (false, true, true) => (FileState::Added, FileState::Added),
+            _ => (FileState::Conflicted, FileState::Conflicted),
+        };
+        states.insert(path, state);
+    }
+
+    Ok(states)
+}
+
+/// Opciones de diff como las de `git diff`, limitadas a un archivo si se indica
+fn diff_options(file: Option<&str>) -> DiffOptions {
+    let mut options = DiffOptions::new();
+    if let Some(file) = file {
+        options.pathspec(file).disable_pathspec_match(true);
+    }
+    options
+}
+
+/// Convierte un diff de libgit2 a los tipos de la aplicación, detectando renombrados
+fn file_diffs(mut diff: Diff<'_>) -> Result<Vec<FileDiff>, String> {
+    diff.find_similar(Some(DiffFindOptions::new().renames(true)))
+        .map_err(git_error)?;
+
+    let path_string = |path: Option<&Path>| path.map(|path| path.to_string_lossy().to_string());
+    let mut files = Vec::with_capacity(diff.deltas().len());
+
+    for (idx, delta) in diff.deltas().enumerate() {
+        let old_path = path_string(delta.old_file().path());
+        let new_path = path_string(delta.new_file().path());
+        let status = match delta.status() {
+            Delta::Added | Delta::Untracked => FileState::Added,
+            Delta::Deleted => FileState::Deleted,
+            Delta::Renamed => FileState::Renamed,
+            Delta::Copied => FileState::Copied,
+            Delta::Typechange => FileState::TypeChanged,
+            _ => FileState::Modified,
+        };
+
+        let mut file = FileDiff {
+            path: new_path.clone().or(old_path.clone()).unwrap_or_default(),
+            old_path: old_path.filter(|old| {
+                matches!(status, FileState::Renamed | FileState::Copied) && Some(old) != new_path.as_ref()
+            }),
+            status,
+            binary: false,
+            additions: 0,
+            deletions: 0,
+            hunks: Vec::new(),
+        };
+
+        // Sin parche: binario (libgit2 lo decide al cargar el contenido, como git)
+        let patch = match Patch::from_diff(&diff, idx).map_err(git_error)? {
+            Some(patch) if !patch.delta().flags().is_binary() => patch,
+            _ => {
+                file.binary = true;
+                files.push(file);
+                continue;
+            }
+        };
+
+        for hunk_idx in 0..patch.num_hunks() {
+            let (hunk, line_count) = patch.hunk(hunk_idx).map_err(git_error)?;
+            let Some(mut diff_hunk) = parse_hunk_header(String::from_utf8_lossy(hunk.header()).trim_end())
+            else {
+                continue;
+            };
+
+            for line_idx in 0..line_count {
+                let line = patch.line_in_hunk(hunk_idx, line_idx).map_err(git_error)?;
+                let kind = match line.origin() {
+                    ' ' => DiffLineKind::Context,
+                    '+' => DiffLineKind::Addition,
+                    '-' => DiffLineKind::Deletion,
+                    // Marcas de "sin salto de línea al final": ya se ven en el contenido
+                    _ => continue,
+                };
+                match kind {
+                    DiffLineKind::Addition => file.additions += 1,
+                    DiffLineKind::Deletion => file.deletions += 1,
+                    DiffLineKind::Context => {}
+                }
+
+                let content = String::from_utf8_lossy(line.content());
+                let mut diff_line = DiffLine::new(
+                    kind,
+                    content.strip_suffix('\n').unwrap_or(&content).to_string(),
+                    line.old_lineno(),
+                    line.new_lineno(),
+                );
+                diff_line.missing_newline = !content.ends_with('\n');
+                diff_hunk.lines.push(diff_line);
+            }
+
+            file.hunks.push(diff_hunk);
+        }
+
+        highlight_words(&mut file.hunks);
+        files.push(file);
+    }
+
+    Ok(files)
+}
+
+/// `git commit` hace cosas que libgit2 no: ejecutar hooks, firmar y cerrar merges
+fn needs_cli_commit(repo: &Repository) -> bool {
+    if repo.state() != RepositoryState::Clean {
+        return true;
+    }
+
+    let config = repo.config().ok();
+    let config_bool = |name: &str| config.as_ref().and_then(|c| c.get_bool(name).ok()).unwrap_or(false);
+    if config_bool("commit.gpgsign") {
+        return true;
+    }
+
+    let hooks_dir = config
+        .as_ref()
+        .and_then(|c| c.get_path("core.hooksPath").ok())
+        .map(|hooks| match repo.workdir() {
+            Some(workdir) if hooks.is_relative() => workdir.join(hooks),
+            _ => hooks,
+        })
+        .unwrap_or_else(|| repo.path().join("hooks"));
+
+    COMMIT_HOOKS.iter().any(|hook| hooks_dir.join(hook).is_file())
+}
+
+impl GitBackend for LibGit2Backend {
+    fn current_branch(&self, path: &str) -> Result<String, String> {
+        let Some(repo) = open(path)? else {
+            return CliBackend.current_branch(path);
+        };
+
+        let branch = match repo.head() {
+            Ok(head) if head.is_branch() => Ok(head.shorthand().unwrap_or("HEAD").to_string()),
+            Ok(_) => Ok("HEAD".to_string()),
+            // Repositorio recién creado: HEAD apunta a una rama que aún no existe
+            Err(e) if e.code() == ErrorCode::UnbornBranch => {
+                let head = repo.find_reference("HEAD").map_err(git_error)?;
+                let target = head.symbolic_target().unwrap_or("HEAD");
+                Ok(target.strip_prefix("refs/heads/").unwrap_or(target).to_string())
+            }
+            Err(e) => Err(git_error(e)),
+        };
+        branch
+    }
+
+    fn workdir(&self, path: &str) -> Result<PathBuf, String> {
+        let Some(repo) = open(path)? else {
+            return CliBackend.workdir(path);
+        };
+
+        repo.workdir()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| "El repositorio no tiene directorio de trabajo".to_string())
+    }
+
+    fn status(&self, path: &str) -> Result<GitStatus, String> {
+        let Some(repo) = open(path)? else {
+            return CliBackend.status(path);
+        };
+
+        let mut status = branch_status(&repo)?;
+        // El reflog de `refs/stash` tiene una entrada por stash
+        status.stash_count = repo.reflog("refs/stash").map(|reflog| reflog.len()).unwrap_or(0);
+        let submodules: HashSet<String> = repo
+            .submodules()
+            .map(|submodules| {
+                submodules
+                    .iter()
+                    .map(|submodule| submodule.path().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Mismos criterios que `git status --porcelain`
+        let mut options = StatusOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(false)
+            .include_ignored(false)
+            .renames_head_to_index(true);
+
+        let conflicts = conflict_states(&repo)?;
+        let statuses = repo.statuses(Some(&mut options)).map_err(git_error)?;
+
+        for entry in statuses.iter().filter(|entry| !entry.status().is_ignored()) {
+            let renamed = entry.head_to_index().filter(|_| entry.status().is_index_renamed());
+            // En los renombrados, la ruta nueva
+            let path = renamed
+                .as_ref()
+                .and_then(|delta| delta.new_file().path().map(Path::to_path_buf))
+                .map(|path| path.to_string_lossy().to_string())
+                .unwrap_or_else(|| String::from_utf8_lossy(entry.path_bytes()).to_string());
+
+            if entry.status().is_conflicted() {
+                let (ours, theirs) = conflicts
+                    .get(&path)
+                    .copied()
+                    .unwrap_or((FileState::Conflicted, FileState::Conflicted));
+                let mut conflict = GitStatusEntry::new(path, ours, theirs);
+                conflict.conflicted = true;
+                status.entries.push(conflict);
+                continue;
+            }
+
+            let (index, worktree) = file_states(entry.status());
+            let submodule = submodules.contains(&path);
+
+            if worktree != FileState::Untracked {
+                let mut changed = GitStatusEntry::new(path, index, worktree);
+                changed.orig_path = renamed
+                    .and_then(|delta| delta.old_file().path().map(Path::to_path_buf))
+                    .map(|path| path.to_string_lossy().to_string());
+                changed.submodule = submodule;
+                status.entries.push(changed);
+                continue;
+            }
+
+            // Borrado del índice pero presente en disco: git lo muestra dos veces (`D ` y `??`)
+            if index != FileState::Unmodified {
+                status
+                    .entries
+                    .push(GitStatusEntry::new(path.clone(), index, FileState::Unmodified));
+            }
+            status
+                .entries
+                .push(GitStatusEntry::new(path, FileState::Untracked, FileState::Untracked));
+        }
+
+        Ok(status)
+    }
+
+    fn recent_commits(&self, path: &str, limit: usize) -> Result<Vec<GitCommit>, String> {
+        let Some(repo) = open(path)? else {
+            return CliBackend.recent_commits(path, limit);
+        };
+        if head_commit(&repo)?.is_none() {
+            return Err("Not a git repository or no commits".to_string());
+        }
+
+        let mut walk = repo.revwalk().map_err(git_error)?;
+        walk.push_head().map_err(git_error)?;
+        walk.set_sorting(Sort::TIME).map_err(git_error)?;
+
+        walk.take(limit)
+            .map(|oid| {
+                let commit = repo.find_commit(oid.map_err(git_error)?).map_err(git_error)?;
+                Ok(commit_info(&commit))
+            })
+            .collect()
+    }
+
+    fn last_commit_time(&self, path: &str) -> Result<Option<i64>, String> {
+        let Some(repo) = open(path)? else {
+            return CliBackend.last_commit_time(path);
+        };
+
+        let time = head_commit(&repo)?.map(|commit| commit.time().seconds());
+        Ok(time)
+    }
+
+    fn remote_url(&self, path: &str, remote: &str) -> Result<Option<String>, String> {
+        let Some(repo) = open(path)? else {
+            return CliBackend.remote_url(path, remote);
+        };
+
+        let url = match repo.find_remote(remote) {
+            Ok(remote) => remote.url().map(str::to_string),
+            Err(_) => None,
+        };
+        Ok(url.filter(|url| !url.is_empty()))
+    }
+
+    fn diff(&self, path: &str, file: Option<&str>, staged: bool) -> Result<Vec<FileDiff>, String> {
+        let Some(repo) = open(path)? else {
+            return CliBackend.diff(path, file, staged);
+        };
+
+        let mut options = diff_options(file);
+        let diff = if staged {
+            let tree = head_commit(&repo)?
+                .map(|commit| commit.tree())
+                .transpose()
+                .map_err(git_error)?;
+            repo.diff_tree_to_index(tree.as_ref(), None, Some(&mut options))
+        } else {
+            repo.diff_index_to_workdir(None, Some(&mut options))
+        }
+        .map_err(|e| format!("Error getting diff: {}", e.message()))?;
+
+        file_diffs(diff)
+    }
+
+    fn commit_diff(&self, path: &str, hash: &str) -> Result<Vec<FileDiff>, String> {
+        let Some(repo) = open(path)? else {
+            return CliBackend.commit_diff(path, hash);
+        };
+
+        let commit = repo
+            .revparse_single(hash)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|_| format!("Error getting commit diff: commit '{}' no encontrado", hash))?;
+        let tree = commit.tree().map_err(git_error)?;
+        // Primer commit: se compara con un árbol vacío
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree().map_err(git_error)?),
+            Err(_) => None,
+        };
+
+        let diff = repo
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut diff_options(None)))
+            .map_err(|e| format!("Error getting commit diff: {}", e.message()))?;
+        file_diffs(diff)
+    }
+
+    fn stage(&self, path: &str, files: &[String]) -> Result<(), String> {
+        let Some(repo) = open(path)? else {
+            return CliBackend.stage(path, files);
+        };
+
+        if files.is_empty() {
+            return Ok(());
+        }
+        let workdir = repo
+            .workdir()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| "Error staging files: el repositorio no tiene directorio de trabajo".to_string())?;
+
+        // Las rutas son literales (`a[1].txt` no es un patrón): el estado con coincidencia
+        // exacta da los archivos cambiados bajo ellas, también dentro de carpetas sin seguimiento
+        let mut options = StatusOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .disable_pathspec_match(true);
+        for file in files {
+            options.pathspec(file);
+        }
+        let statuses = repo
+            .statuses(Some(&mut options))
+            .map_err(|e| format!("Error staging files: {}", e.message()))?;
+
+        let mut index = repo.index().map_err(git_error)?;
+        for entry in statuses.iter() {
+            let Some(file) = entry.path() else { continue };
+            let file = Path::new(file);
+            // Lo que ya no está en disco se quita del índice
+            let result = if workdir.join(file).symlink_metadata().is_ok() {
+                index.add_path(file)
+            } else {
+                index.remove_path(file)
+            };
+            result.map_err(|e| format!("Error staging files: {}", e.message()))?;
+        }
+        index
+            .write()
+            .map_err(|e| format!("Error staging files: {}", e.message()))
+    }
+
+    fn unstage(&self, path: &str, files: &[String]) -> Result<(), String> {
+        let Some(repo) = open(path)? else {
+            return CliBackend.unstage(path, files);
+        };
+
+        // Sin commits, sacar del índice es quitar las entradas
+        let head = head_commit(&repo)?.map(|commit| commit.into_object());
+        repo.reset_default(head.as_ref(), files.iter())
+            .map_err(|e| format!("Error unstaging files: {}", e.message()))
+    }
+
+    fn discard(&self, path: &str, files: &[String]) -> Result<(), String> {
+        let Some(repo) = open(path)? else {
+            return CliBackend.discard(path, files);
+        };
+        // Sin rutas el checkout afectaría a todo el repositorio
+        if files.is_empty() {
+            return Ok(());
+        }
+
+        let mut checkout = CheckoutBuilder::new();
+        checkout.force().disable_pathspec_match(true);
+        for file in files {
+            checkout.path(file);
+        }
+
+        repo.checkout_index(None, Some(&mut checkout))
+            .map_err(|e| format!("Error discarding changes: {}", e.message()))
+    }
+
+    fn apply(&self, path: &str, patch: &str, target: ApplyTarget) -> Result<(), String> {
+        let Some(repo) = open(path)? else {
+            return CliBackend.apply(path, patch, target);
+        };
+
+        let location = match target {
+            ApplyTarget::Index => ApplyLocation::Index,
+            ApplyTarget::Worktree => ApplyLocation::WorkDir,
+        };
+        Diff::from_buffer(patch.as_bytes())
+            .and_then(|diff| repo.apply(&diff, location, None))
+            .map_err(|e| format!("Error applying patch: {}", e.message()))
+    }
+
+    fn commit(&self, path: &str, message: &str) -> Result<String, String> {
+        let Some(repo) = open(path)? else {
+            return CliBackend.commit(path, message);
+        };
+        if needs_cli_commit(&repo) {
+            return CliBackend.commit(path, message);
+        }
+
+        // Igual que `git commit`: sin comentarios ni espacios sobrantes
+        let message = git2::message_prettify(message, Some(b'#')).map_err(git_error)?;
+        if message.trim().is_empty() {
+            return Err("Error creating commit: el mensaje está vacío".to_string());
+        }
+
+        let signature = repo.signature().map_err(|_| {
+            "Error creating commit: configura user.name y user.email en git".to_string()
+        })?;
+
+        let mut index = repo.index().map_err(git_error)?;
+        let tree_id = index.write_tree().map_err(git_error)?;
+        let tree = repo.find_tree(tree_id).map_err(git_error)?;
+        let parent = head_commit(&repo)?;
+
+        let unchanged = match &parent {
+            Some(parent) => parent.tree_id() == tree_id,
+            None => index.is_empty(),
+        };
+        if unchanged {
+            return Err("Error creating commit: no hay cambios en el stage".to_string());
+        }
+
+        let parents: Vec<&Commit> = parent.iter().collect();
+        let oid = repo
+            .commit(Some("HEAD"), &signature, &signature, &message, &tree, &parents)
+            .map_err(|e| format!("Error creating commit: {}", e.message()))?;
+
+        let branch = self.current_branch(path).unwrap_or_else(|_| "HEAD".to_string());
+        let short_id: String = oid.to_string().chars().take(7).collect();
+        Ok(format!(
+            "[{} {}] {}",
+            branch,
+            short_id,
+            message.lines().next().unwrap_or("")
+        ))
+    }
+
+    fn branches(&self, path: &str) -> Result<Vec<GitBranch>, String> {
+        let Some(repo) = open(path)? else {
+            return CliBackend.branches(path);
+        };
+
+        let mut branches = Vec::new();
+        for item in repo.branches(None).map_err(git_error)? {
+            let (branch, kind) = item.map_err(git_error)?;
+            branches.extend(branch_info(&repo, &branch, kind == BranchType::Remote));
+        }
+
+        branches.sort_by(|a, b| (a.remote, &a.name).cmp(&(b.remote, &b.name)));
+        Ok(branches)
+    }
+
+    fn create_branch(&self, path: &str, name: &str, start_point: Option<&str>) -> Result<(), String> {
+        let Some(repo) = open(path)? else {
+            return CliBackend.create_branch(path, name, start_point);
+        };
+        check_branch_name(name)?;
+
+        let target = match start_point {
+            Some(start) => repo
+                .revparse_single(start)
+                .and_then(|object| object.peel_to_commit())
+                .map_err(|_| format!("'{}' no existe", start))?,
+            None => head_commit(&repo)?
+                .ok_or_else(|| "La rama actual aún no tiene commits".to_string())?,
+        };
+
+        let mut branch = repo
+            .branch(name, &target, false)
+            .map_err(|e| format!("Error creating branch: {}", e.message()))?;
+        // Como `git branch nueva origin/x`: la nueva rama sigue a la remota
+        if let Some(start) = start_point {
+            if repo.find_branch(start, BranchType::Remote).is_ok() {
+                branch.set_upstream(Some(start)).map_err(git_error)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn checkout_branch(&self, path: &str, name: &str) -> Result<(), String> {
+        let Some(repo) = open(path)? else {
+            return CliBackend.checkout_branch(path, name);
+        };
+
+        let branch = find_local_branch(&repo, name)?;
+        let refname = branch
+            .get()
+            .name()
+            .ok_or_else(|| format!("Nombre de rama no válido: '{}'", name))?
+            .to_string();
+        let tree = branch.get().peel_to_tree().map_err(git_error)?;
+
+        // `safe`: conserva los cambios locales salvo que choquen con la otra rama (y entonces falla)
+        repo.checkout_tree(tree.as_object(), Some(CheckoutBuilder::new().safe()))
+            .and_then(|_| repo.set_head(&refname))
+            .map_err(|e| format!("Error switching branch: {}", e.message()))
+    }
+
+    fn rename_branch(&self, path: &str, old_name: &str, new_name: &str) -> Result<(), String> {
+        let Some(repo) = open(path)? else {
+            return CliBackend.rename_branch(path, old_name, new_name);
+        };
+        check_branch_name(new_name)?;
+
+        let renamed = find_local_branch(&repo, old_name)?
+            .rename(new_name, false)
+            .map(|_| ())
+            .map_err(|e| format!("Error renaming branch: {}", e.message()));
+        renamed
+    }
+
+    fn delete_branch(&self, path: &str, name: &str, force: bool) -> Result<(), String> {
+        let Some(repo) = open(path)? else {
+            return CliBackend.delete_branch(path, name, force);
+        };
+
+        let mut branch = find_local_branch(&repo, name)?;
+        if branch.is_head() {
+            return Err(format!("Error deleting branch: '{}' es la rama actual", name));
+        }
+
+        if !force {
+            let tip = branch.get().target();
+            let base = match branch.upstream() {
+                Ok(upstream) => upstream.get().target(),
+                Err(_) => head_commit(&repo)?.map(|commit| commit.id()),
+            };
+            let merged = match (tip, base) {
+                (Some(tip), Some(base)) => {
+                    tip == base || repo.graph_descendant_of(base, tip).unwrap_or(false)
+                }
+                _ => false,
+            };
+            if !merged {
+                return Err(format!(
+                    "Error deleting branch: '{}' tiene commits sin fusionar; bórrala forzando para perderlos",
+                    name
+                ));
+            }
+        }
+
+        branch
+            .delete()
+            .map_err(|e| format!("Error deleting branch: {}", e.message()))
+    }
+
+    fn stash_save(&self, path: &str, message: Option<&str>, include_untracked: bool) -> Result<(), String> {
+        let Some(mut repo) = open(path)? else {
+            return CliBackend.stash_save(path, message, include_untracked);
+        };
+
+        let signature = repo.signature().map_err(|_| {
+            "Error creating stash: configura user.name y user.email en git".to_string()
+        })?;
+        let mut flags = StashFlags::DEFAULT;
+        if include_untracked {
+            flags |= StashFlags::INCLUDE_UNTRACKED;
+        }
+
+        match repo.stash_save2(&signature, message, Some(flags)) {
+            Ok(_) => Ok(()),
+            Err(e) if e.code() == ErrorCode::NotFound => {
+                Err("Error creating stash: no hay cambios que guardar".to_string())
+            }
+            Err(e) => Err(format!("Error creating stash: {}", e.message())),
+        }
+    }
+
+    fn stashes(&self, path: &str) -> Result<Vec<GitStash>, String> {
+        let Some(mut repo) = open(path)? else {
+            return CliBackend.stashes(path);
+        };
+
+        // `stash_foreach` toma el repositorio: las fechas se leen después
+        let mut entries = Vec::new();
+        repo.stash_foreach(|index, message, oid| {
+            entries.push((index, message.to_string(), *oid));
+            true
+        })
+        .map_err(git_error)?;
+
+        entries
+            .into_iter()
+            .map(|(index, message, oid)| {
+                let commit = repo.find_commit(oid).map_err(git_error)?;
+                Ok(GitStash::new(index, oid.to_string(), commit.time().seconds(), &message))
+            })
+            .collect()
+    }
+
+    fn stash_pop(&self, path: &str, index: usize) -> Result<(), String> {
+        let Some(mut repo) = open(path)? else {
+            return CliBackend.stash_pop(path, index);
+        };
+
+        apply_stash(&mut repo, index)?;
+        repo.stash_drop(index).map_err(git_error)
+    }
+
+    fn stash_apply(&self, path: &str, index: usize) -> Result<(), String> {
+        let Some(mut repo) = open(path)? else {
+            return CliBackend.stash_apply(path, index);
+        };
+
+        apply_stash(&mut repo, index)
+    }
+
+    fn stash_drop(&self, path: &str, index: usize) -> Result<(), String> {
+        let Some(mut repo) = open(path)? else {
+            return CliBackend.stash_drop(path, index);
+        };
+
+        repo.stash_drop(index)
+            .map_err(|e| format!("Error dropping stash: {}", e.message()))
+    }
+
+    fn fetch(&self, path: &str) -> Result<(), String> {
+        CliBackend.fetch(path)
+    }
+
+    fn push(&self, path: &str) -> Result<String, String> {
+        CliBackend.push(path)
+    }
+
+    fn pull(&self, path: &str) -> Result<String, String> {
+        CliBackend.pull(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Repositorio vacío en un directorio temporal que se borra al soltarlo
+    struct TempRepo {
+        dir: PathBuf,
+        repo: Repository,
+    }
+
+    impl TempRepo {
+        fn new(name: &str) -> Self {
+            let nanos = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos();
+            let dir = std::env::temp_dir().join(format!("gestor-libgit-{}-{}", name, nanos));
+            let repo = Repository::init(&dir).unwrap();
+            TempRepo { dir, repo }
+        }
+
+        fn path(&self) -> &str {
+            self.dir.to_str().unwrap()
+        }
+
+        fn write(&self, file: &str) {
+            let path = self.dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, file).unwrap();
+        }
+
+        /// Rutas del índice ordenadas
+        fn index_paths(&self) -> Vec<String> {
+            let mut index = self.repo.index().unwrap();
+            index.read(true).unwrap();
+            index
+                .iter()
+                .map(|entry| String::from_utf8(entry.path).unwrap())
+                .collect()
+        }
+    }
+
+    impl Drop for TempRepo {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    fn files(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|path| path.to_string()).collect()
+    }
+
+    #[test]
+    fn stage_takes_paths_literally() {
+        let repo = TempRepo::new("stage");
+        for file in ["a[1].txt", "a1.txt", "*.rs", "main.rs", "lib.rs"] {
+            repo.write(file);
+        }
+
+        LibGit2Backend.stage(repo.path(), &files(&["a[1].txt", "*.rs"])).unwrap();
+        assert_eq!(repo.index_paths(), vec!["*.rs", "a[1].txt"]);
+    }
+
+    #[test]
+    fn stage_adds_untracked_directories_and_removes_deleted_files() {
+        let repo = TempRepo::new("stage-dir");
+        for file in ["nuevo/uno.txt", "nuevo/dos.txt", "b[x].txt", "bx.txt"] {
+            repo.write(file);
+        }
+
+        LibGit2Backend
+            .stage(repo.path(), &files(&["nuevo/", "b[x].txt", "bx.txt"]))
+            .unwrap();
+        assert_eq!(
+            repo.index_paths(),
+            vec!["b[x].txt", "bx.txt", "nuevo/dos.txt", "nuevo/uno.txt"]
+        );
+
+        // Borrado del disco: se quita del índice sin tocar los que encajarían como patrón
+        std::fs::remove_file(repo.dir.join("b[x].txt")).unwrap();
+        LibGit2Backend.stage(repo.path(), &files(&["b[x].txt"])).unwrap();
+        assert_eq!(repo.index_paths(), vec!["bx.txt", "nuevo/dos.txt", "nuevo/uno.txt"]);
+    }
+}
@@ -0,0 +1,335 @@
+//! Acceso a repositorios git.
+//!
+//! Las operaciones locales se hacen en proceso con libgit2 (`LibGit2Backend`),
+//! sin lanzar `git` ni interpretar su salida de texto. La CLI (`CliBackend`)
+//! se mantiene para lo que libgit2 no cubre igual que `git`:
+//!
+//! | Operación                   | Backend                                                   |
+//! |-----------------------------|-----------------------------------------------------------|
+//! | Estado, log, diff, rama...  | libgit2                                                   |
+//! | `add`, `restore`, `apply`   | libgit2                                                   |
+//! | Ramas, `stash`              | libgit2                                                   |
+//! | `commit`                    | libgit2; CLI si hay hooks, firma GPG o un merge en curso  |
+//! | `fetch`, `push`, `pull`     | CLI (credenciales, agente SSH y helpers del usuario)      |
+//! | Repos que libgit2 no abre   | CLI para todo (extensiones nuevas, `sha256`...)           |
+//!
+//! Los comandos usan siempre `git::backend()`; las rutas son las de los
+//! proyectos (`local_path`) y pueden ser subcarpetas del repositorio.
+
+mod branches;
+mod cli;
+mod diff;
+mod libgit;
+mod staging;
+mod stash;
+
+pub use branches::{switch_branch, BranchSwitch, GitBranch};
+pub use cli::CliBackend;
+pub use diff::FileDiff;
+pub use libgit::LibGit2Backend;
+pub use staging::{discard, discard_preview, stage_hunks, unstage_hunks, DiscardPreview, HunkSelection};
+pub use stash::{stash_diff, GitStash};
+
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Operaciones de git que usa la aplicación. Los errores son mensajes listos para mostrar.
+pub trait GitBackend: Send + Sync {
+    /// Rama actual (`HEAD` si está desacoplado)
+    fn current_branch(&self, path: &str) -> Result<String, String>;
+
+    /// Raíz del directorio de trabajo del repositorio que contiene `path`
+    fn workdir(&self, path: &str) -> Result<PathBuf, String>;
+
+    /// Rama, upstream, stashes y archivos con cambios (sin los ignorados) en una sola lectura
+    fn status(&self, path: &str) -> Result<GitStatus, String>;
+
+    /// Últimos commits de `HEAD`, del más reciente al más antiguo
+    fn recent_commits(&self, path: &str, limit: usize) -> Result<Vec<GitCommit>, String>;
+
+    /// Fecha (timestamp Unix) del último commit de `HEAD`; `None` si aún no hay commits
+    fn last_commit_time(&self, path: &str) -> Result<Option<i64>, String>;
+
+    fn remote_url(&self, path: &str, remote: &str) -> Result<Option<String>, String>;
+
+    /// Cambios sin commitear de `file` (ruta desde la raíz del repositorio) o de todos
+    /// los archivos con seguimiento: del índice al directorio de trabajo o, con
+    /// `staged`, de `HEAD` al índice
+    fn diff(&self, path: &str, file: Option<&str>, staged: bool) -> Result<Vec<FileDiff>, String>;
+
+    /// Cambios de un commit respecto a su primer padre
+    fn commit_diff(&self, path: &str, hash: &str) -> Result<Vec<FileDiff>, String>;
+
+    /// Añade al índice archivos o pathspecs (ej: `.`), incluidos los borrados
+    fn stage(&self, path: &str, files: &[String]) -> Result<(), String>;
+
+    /// Devuelve archivos al estado de `HEAD` en el índice (`git restore --staged`)
+    /// sin tocar el directorio de trabajo. Rutas desde la raíz del repositorio.
+    fn unstage(&self, path: &str, files: &[String]) -> Result<(), String>;
+
+    /// Devuelve archivos con seguimiento al estado del índice (`git restore`),
+    /// perdiendo los cambios del directorio de trabajo. Rutas desde la raíz del repositorio.
+    fn discard(&self, path: &str, files: &[String]) -> Result<(), String>;
+
+    /// Aplica un parche con rutas desde la raíz del repositorio
+    fn apply(&self, path: &str, patch: &str, target: ApplyTarget) -> Result<(), String>;
+
+    /// Crea un commit con el índice actual y devuelve un resumen (`[rama hash] mensaje`)
+    fn commit(&self, path: &str, message: &str) -> Result<String, String>;
+
+    /// Ramas locales y remotas (sin `origin/HEAD`), primero las locales, por nombre
+    fn branches(&self, path: &str) -> Result<Vec<GitBranch>, String>;
+
+    /// Crea una rama en `start_point` (o en `HEAD`) sin cambiar a ella.
+    /// Si `start_point` es una rama remota, la nueva la sigue como upstream.
+    fn create_branch(&self, path: &str, name: &str, start_point: Option<&str>) -> Result<(), String>;
+
+    /// Cambia a una rama local. Falla si hay cambios que se perderían.
+    fn checkout_branch(&self, path: &str, name: &str) -> Result<(), String>;
+
+    fn rename_branch(&self, path: &str, old_name: &str, new_name: &str) -> Result<(), String>;
+
+    /// Borra una rama local. Sin `force` solo si ya está fusionada en su upstream
+    /// (o en `HEAD` si no tiene), como `git branch -d`.
+    fn delete_branch(&self, path: &str, name: &str, force: bool) -> Result<(), String>;
+
+    /// Guarda los cambios con seguimiento (y los sin seguimiento si se pide) en un
+    /// stash nuevo, dejando el directorio de trabajo como `HEAD`
+    fn stash_save(&self, path: &str, message: Option<&str>, include_untracked: bool) -> Result<(), String>;
+
+    /// Stashes guardados, del más reciente (`stash@{0}`) al más antiguo
+    fn stashes(&self, path: &str) -> Result<Vec<GitStash>, String>;
+
+    /// Aplica `stash@{index}` y lo borra si se ha aplicado sin conflictos
+    fn stash_pop(&self, path: &str, index: usize) -> Result<(), String>;
+
+    /// Aplica `stash@{index}` sin borrarlo
+    fn stash_apply(&self, path: &str, index: usize) -> Result<(), String>;
+
+    fn stash_drop(&self, path: &str, index: usize) -> Result<(), String>;
+
+    fn fetch(&self, path: &str) -> Result<(), String>;
+
+    /// Devuelve la salida de `git push`
+    fn push(&self, path: &str) -> Result<String, String>;
+
+    /// Devuelve la salida de `git pull`
+    fn pull(&self, path: &str) -> Result<String, String>;
+}
+
+/// Dónde se aplica un parche
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApplyTarget {
+    Index,
+    Worktree,
+}
+
+static LIBGIT2: LibGit2Backend = LibGit2Backend;
+
+/// Backend que usan los comandos
+pub fn backend() -> &'static dyn GitBackend {
+    &LIBGIT2
+}
+
+/// `true` si la ruta es un repositorio git con cambios sin commitear. Los archivos
+/// sin seguimiento no cuentan (como al cambiar de rama).
+pub fn has_uncommitted_changes(path: &str) -> bool {
+    backend()
+        .status(path)
+        .map(|status| {
+            status
+                .entries
+                .iter()
+                .any(|entry| entry.index != FileState::Untracked)
+        })
+        .unwrap_or(false)
+}
+
+/// Estado de un archivo en el índice o en el directorio de trabajo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileState {
+    Unmodified,
+    Modified,
+    Added,
+    Deleted,
+    Renamed,
+    Copied,
+    TypeChanged,
+    Untracked,
+    Conflicted,
+}
+
+impl FileState {
+    /// Letra de `git status --porcelain`
+    pub fn as_char(&self) -> char {
+        match self {
+            FileState::Unmodified => ' ',
+            FileState::Modified => 'M',
+            FileState::Added => 'A',
+            FileState::Deleted => 'D',
+            FileState::Renamed => 'R',
+            FileState::Copied => 'C',
+            FileState::TypeChanged => 'T',
+            FileState::Untracked => '?',
+            FileState::Conflicted => 'U',
+        }
+    }
+
+    pub fn from_char(c: char) -> Self {
+        match c {
+            'M' => FileState::Modified,
+            'A' => FileState::Added,
+            'D' => FileState::Deleted,
+            'R' => FileState::Renamed,
+            'C' => FileState::Copied,
+            'T' => FileState::TypeChanged,
+            '?' => FileState::Untracked,
+            'U' => FileState::Conflicted,
+            _ => FileState::Unmodified,
+        }
+    }
+}
+
+/// Archivo con cambios. Las rutas son relativas a la raíz del repositorio.
+///
+/// En los conflictos `index` y `worktree` indican el lado "nuestro" y el
+/// "suyo" como en `git status` (`UU`, `AA`, `DU`...), con `conflicted` a `true`.
+#[derive(Debug, Clone, Serialize)]
+pub struct GitStatusEntry {
+    /// En los renombrados y copias, la ruta nueva
+    pub path: String,
+    /// Ruta anterior de un renombrado o copia
+    pub orig_path: Option<String>,
+    pub index: FileState,
+    pub worktree: FileState,
+    pub conflicted: bool,
+    pub submodule: bool,
+}
+
+impl GitStatusEntry {
+    fn new(path: String, index: FileState, worktree: FileState) -> Self {
+        GitStatusEntry {
+            path,
+            orig_path: None,
+            index,
+            worktree,
+            conflicted: false,
+            submodule: false,
+        }
+    }
+
+    /// Línea equivalente de `git status --porcelain` (`XY ruta` o `XY anterior -> nueva`)
+    pub fn porcelain(&self) -> String {
+        match &self.orig_path {
+            Some(orig_path) => format!(
+                "{}{} {} -> {}",
+                self.index.as_char(),
+                self.worktree.as_char(),
+                orig_path,
+                self.path
+            ),
+            None => format!("{}{} {}", self.index.as_char(), self.worktree.as_char(), self.path),
+        }
+    }
+}
+
+/// Estado completo del repositorio
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GitStatus {
+    /// Rama actual (`None` con `HEAD` desacoplado)
+    pub branch: Option<String>,
+    /// Hash de `HEAD` (`None` si aún no hay commits)
+    pub head: Option<String>,
+    /// Rama remota que sigue la actual (ej: `origin/main`)
+    pub upstream: Option<String>,
+    /// Commits por delante y por detrás del upstream con la información ya descargada
+    pub ahead: u32,
+    pub behind: u32,
+    pub stash_count: usize,
+    pub entries: Vec<GitStatusEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GitCommit {
+    /// Hash abreviado (7 caracteres)
+    pub hash: String,
+    pub full_hash: String,
+    pub author: String,
+    /// Fecha relativa para mostrar (ej: "hace 3 días")
+    pub date: String,
+    pub timestamp: i64,
+    /// Primera línea del mensaje
+    pub message: String,
+}
+
+impl GitCommit {
+    fn new(full_hash: String, author: String, timestamp: i64, message: String) -> Self {
+        GitCommit {
+            hash: full_hash.chars().take(7).collect(),
+            full_hash,
+            author,
+            date: relative_time(timestamp),
+            timestamp,
+            message,
+        }
+    }
+}
+
+/// Conteo de archivos modificados, staged y untracked
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GitFileCount {
+    pub modified: usize,
+    pub staged: usize,
+    pub untracked: usize,
+}
+
+impl GitFileCount {
+    pub fn from_status(status: &GitStatus) -> Self {
+        let mut count = GitFileCount::default();
+
+        for entry in &status.entries {
+            // Un conflicto está pendiente de resolver en el directorio de trabajo
+            if entry.conflicted {
+                count.modified += 1;
+                continue;
+            }
+            if entry.index == FileState::Untracked {
+                count.untracked += 1;
+                continue;
+            }
+            if matches!(
+                entry.index,
+                FileState::Modified
+                    | FileState::Added
+                    | FileState::Deleted
+                    | FileState::Renamed
+                    | FileState::Copied
+            ) {
+                count.staged += 1;
+            }
+            if matches!(entry.worktree, FileState::Modified | FileState::Deleted) {
+                count.modified += 1;
+            }
+        }
+
+        count
+    }
+}
+
+/// Tiempo transcurrido desde `timestamp` en lenguaje natural (ej: "hace 2 horas")
+pub fn relative_time(timestamp: i64) -> String {
+    let seconds = (chrono::Utc::now().timestamp() - timestamp).max(0);
+
+    let (amount, singular, plural) = match seconds {
+        0..=59 => return "hace unos segundos".to_string(),
+        60..=3_599 => (seconds / 60, "minuto", "minutos"),
+        3_600..=86_399 => (seconds / 3_600, "hora", "horas"),
+        86_400..=1_209_599 => (seconds / 86_400, "día", "días"),
+        1_209_600..=5_183_999 => (seconds / 604_800, "semana", "semanas"),
+        5_184_000..=31_535_999 => (seconds / 2_592_000, "mes", "meses"),
+        _ => (seconds / 31_536_000, "año", "años"),
+    };
+
+    format!("hace {} {}", amount, if amount == 1 { singular } else { plural })
+}
@@ -0,0 +1,453 @@
+//! Staging parcial y descarte de cambios.
+//!
+//! Los hunks y líneas elegidos se convierten en un parche que el backend aplica
+//! al índice (stage/unstage) o al directorio de trabajo (descartar). Para
+//! deshacer cambios el parche se genera ya invertido, así ambos backends solo
+//! tienen que aplicarlo tal cual.
+//!
+//! Descartar no se puede deshacer: `discard_preview` devuelve lo que se perdería
+//! y un token que resume ese estado, y `discard` solo actúa si el token coincide
+//! (si algo ha cambiado entre medias hay que volver a confirmar).
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::diff::{DiffHunk, DiffLineKind};
+use super::{backend, ApplyTarget, FileDiff, FileState};
+
+/// Hunk elegido de un diff, identificado por sus posiciones como se mostró
+/// (si el diff ha cambiado y ya no existe, la operación falla)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HunkSelection {
+    /// Ruta desde la raíz del repositorio
+    pub file: String,
+    pub old_start: u32,
+    pub new_start: u32,
+    /// Índices en `DiffHunk::lines` de las líneas a incluir; `None` para el hunk entero
+    pub lines: Option<Vec<usize>>,
+}
+
+/// Lo que se perdería al descartar, con el token que lo confirma
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscardPreview {
+    pub token: String,
+    /// Cambios de los archivos con seguimiento afectados
+    pub diffs: Vec<FileDiff>,
+    /// Archivos y carpetas sin seguimiento que se borrarán
+    pub untracked: Vec<String>,
+}
+
+/// Añade al índice los hunks y líneas elegidos del directorio de trabajo
+pub fn stage_hunks(path: &str, selection: &[HunkSelection]) -> Result<(), String> {
+    apply_selection(path, selection, false, ApplyTarget::Index)
+}
+
+/// Saca del índice los hunks y líneas elegidos de lo que está en stage
+pub fn unstage_hunks(path: &str, selection: &[HunkSelection]) -> Result<(), String> {
+    apply_selection(path, selection, true, ApplyTarget::Index)
+}
+
+/// Genera y aplica el parche de la selección. `staged` elige el diff de origen:
+/// el del índice se deshace en el índice; el del directorio de trabajo se añade
+/// al índice o se deshace en el propio directorio de trabajo.
+fn apply_selection(
+    path: &str,
+    selection: &[HunkSelection],
+    staged: bool,
+    target: ApplyTarget,
+) -> Result<(), String> {
+    let reverse = staged || target == ApplyTarget::Worktree;
+    let mut patch = String::new();
+
+    for (file, hunks) in group_by_file(selection) {
+        let diff = backend()
+            .diff(path, Some(file), staged)?
+            .into_iter()
+            .find(|diff| diff.path == file)
+            .ok_or_else(|| format!("'{}' no tiene cambios: vuelve a cargar el diff", file))?;
+        if diff.binary {
+            return Err(format!("'{}' es binario: solo se puede tratar entero", file));
+        }
+
+        patch.push_str(&build_patch(&diff, &hunks, reverse)?);
+    }
+
+    if patch.is_empty() {
+        return Err("No hay cambios seleccionados".to_string());
+    }
+
+    backend().apply(path, &patch, target)
+}
+
+fn group_by_file(selection: &[HunkSelection]) -> BTreeMap<&str, Vec<&HunkSelection>> {
+    let mut files: BTreeMap<&str, Vec<&HunkSelection>> = BTreeMap::new();
+    for hunk in selection {
+        files.entry(hunk.file.as_str()).or_default().push(hunk);
+    }
+    files
+}
+
+/// Parche de un archivo con solo los hunks y líneas elegidos.
+///
+/// Las líneas no elegidas se quedan como están en la versión de partida: hacia
+/// delante una borrada pasa a contexto y una añadida desaparece; invertido, al
+/// revés. Así la versión de partida de cada hunk no cambia y sus posiciones
+/// siguen valiendo; solo se recalculan las de destino.
+fn build_patch(diff: &FileDiff, selection: &[&HunkSelection], reverse: bool) -> Result<String, String> {
+    let find_hunk = |selected: &HunkSelection| {
+        diff.hunks
+            .iter()
+            .find(|hunk| hunk.old_start == selected.old_start && hunk.new_start == selected.new_start)
+    };
+    if selection.iter().any(|selected| find_hunk(selected).is_none()) {
+        return Err(format!("El diff de '{}' ha cambiado: vuelve a cargarlo", diff.path));
+    }
+
+    let mut hunks = String::new();
+    // Diferencia acumulada de líneas entre partida y destino por los hunks anteriores
+    let mut offset: i64 = 0;
+
+    for hunk in &diff.hunks {
+        let Some(selected) = selection
+            .iter()
+            .find(|selected| selected.old_start == hunk.old_start && selected.new_start == hunk.new_start)
+        else {
+            continue;
+        };
+        let Some((body, base_lines, target_lines)) =
+            hunk_body(hunk, selected.lines.as_deref(), reverse)
+        else {
+            continue;
+        };
+
+        let base_start = if reverse { hunk.new_start } else { hunk.old_start };
+        // Sin líneas, la posición es la de la línea anterior (como en `@@ -0,0 +1 @@`)
+        let target_start = if target_lines == 0 {
+            i64::from(base_start) + offset - i64::from(base_lines > 0)
+        } else {
+            i64::from(base_start) + offset + i64::from(base_lines == 0)
+        };
+        offset += i64::from(target_lines) - i64::from(base_lines);
+
+        hunks.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n{}",
+            base_start,
+            base_lines,
+            target_start.max(0),
+            target_lines,
+            body
+        ));
+    }
+
+    if hunks.is_empty() {
+        return Ok(String::new());
+    }
+
+    Ok(format!(
+        "diff --git a/{path} b/{path}\n--- a/{path}\n+++ b/{path}\n{hunks}",
+        path = diff.path
+    ))
+}
+
+/// Líneas del hunk para el parche y número de líneas en la versión de partida y
+/// de destino; `None` si no queda ningún cambio elegido
+fn hunk_body(hunk: &DiffHunk, lines: Option<&[usize]>, reverse: bool) -> Option<(String, u32, u32)> {
+    let mut body = String::new();
+    let (mut base_lines, mut target_lines) = (0, 0);
+    let mut changed = false;
+
+    for (idx, line) in hunk.lines.iter().enumerate() {
+        let selected = lines.is_none_or(|lines| lines.contains(&idx));
+        // Añadida respecto a la versión de partida del parche
+        let kind = match (line.kind, reverse) {
+            (DiffLineKind::Addition, true) => DiffLineKind::Deletion,
+            (DiffLineKind::Deletion, true) => DiffLineKind::Addition,
+            (kind, _) => kind,
+        };
+
+        let prefix = match kind {
+            DiffLineKind::Context => ' ',
+            DiffLineKind::Addition if selected => '+',
+            DiffLineKind::Deletion if selected => '-',
+            // Sin elegir: lo que existe en la versión de partida se queda
+            DiffLineKind::Deletion => ' ',
+            DiffLineKind::Addition => continue,
+        };
+        changed |= prefix != ' ';
+        if prefix != '+' {
+            base_lines += 1;
+        }
+        if prefix != '-' {
+            target_lines += 1;
+        }
+
+        body.push(prefix);
+        body.push_str(&line.content);
+        body.push('\n');
+        if line.missing_newline {
+            body.push_str("\\ No newline at end of file\n");
+        }
+    }
+
+    changed.then_some((body, base_lines, target_lines))
+}
+
+/// Muestra lo que se perdería al descartar los `files` enteros y los `hunks`
+pub fn discard_preview(
+    path: &str,
+    files: &[String],
+    hunks: &[HunkSelection],
+) -> Result<DiscardPreview, String> {
+    let status = backend().status(path)?;
+    let workdir = backend().workdir(path)?;
+
+    let untracked: Vec<String> = status
+        .entries
+        .iter()
+        .filter(|entry| entry.worktree == FileState::Untracked && files.contains(&entry.path))
+        .map(|entry| entry.path.clone())
+        .collect();
+
+    let mut targets: Vec<&str> = files
+        .iter()
+        .filter(|file| !untracked.contains(file))
+        .map(String::as_str)
+        .chain(hunks.iter().map(|hunk| hunk.file.as_str()))
+        .collect();
+    targets.sort_unstable();
+    targets.dedup();
+
+    let mut diffs = Vec::with_capacity(targets.len());
+    for file in targets {
+        diffs.extend(backend().diff(path, Some(file), false)?);
+    }
+
+    // El token cubre la selección, los diffs y el tamaño y fecha de los archivos sin seguimiento
+    let untracked_state: Vec<(&String, u64, u64)> = untracked
+        .iter()
+        .map(|file| {
+            let metadata = std::fs::metadata(workdir.join(file)).ok();
+            let modified = metadata
+                .as_ref()
+                .and_then(|m| m.modified().ok())
+                .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                .map_or(0, |since| since.as_nanos() as u64);
+            (file, metadata.map_or(0, |m| m.len()), modified)
+        })
+        .collect();
+    let state = serde_json::to_vec(&(files, hunks, &diffs, untracked_state))
+        .map_err(|e| format!("Error calculando el token: {}", e))?;
+
+    Ok(DiscardPreview {
+        token: format!("{:x}", Sha256::digest(&state)),
+        diffs,
+        untracked,
+    })
+}
+
+/// Descarta los cambios del directorio de trabajo: los `files` vuelven al estado
+/// del índice (los sin seguimiento se borran) y los `hunks` se deshacen.
+/// `confirm_token` debe ser el de `discard_preview` con el mismo estado.
+pub fn discard(
+    path: &str,
+    files: &[String],
+    hunks: &[HunkSelection],
+    confirm_token: &str,
+) -> Result<(), String> {
+    let preview = discard_preview(path, files, hunks)?;
+    if preview.token != confirm_token {
+        return Err("Los cambios no coinciden con los confirmados: revísalos de nuevo".to_string());
+    }
+
+    // Los archivos que se descartan enteros no necesitan sus hunks
+    let hunks: Vec<HunkSelection> = hunks
+        .iter()
+        .filter(|hunk| !files.contains(&hunk.file))
+        .cloned()
+        .collect();
+    if !hunks.is_empty() {
+        apply_selection(path, &hunks, false, ApplyTarget::Worktree)?;
+    }
+
+    let tracked: Vec<String> = files
+        .iter()
+        .filter(|file| !preview.untracked.contains(file))
+        .cloned()
+        .collect();
+    if !tracked.is_empty() {
+        backend().discard(path, &tracked)?;
+    }
+
+    let workdir = backend().workdir(path)?;
+    for file in &preview.untracked {
+        remove_untracked(&workdir.join(file))
+            .map_err(|e| format!("Error borrando '{}': {}", file, e))?;
+    }
+
+    Ok(())
+}
+
+fn remove_untracked(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::diff::parse_unified;
+
+    const HEADER: &str = "diff --git a/f.txt b/f.txt\n--- a/f.txt\n+++ b/f.txt\n";
+
+    /// Antes: uno, dos, tres, cuatro. Después: uno, DOS, tres, nueva, cuatro
+    const HUNK: &str = "\
+@@ -1,4 +1,5 @@
+ uno
+-dos
++DOS
+ tres
++nueva
+ cuatro
+";
+
+    fn file_diff(hunks: &str) -> FileDiff {
+        parse_unified(&format!("{}{}", HEADER, hunks)).remove(0)
+    }
+
+    fn select(hunk: &DiffHunk, lines: Option<&[usize]>) -> HunkSelection {
+        HunkSelection {
+            file: "f.txt".to_string(),
+            old_start: hunk.old_start,
+            new_start: hunk.new_start,
+            lines: lines.map(<[usize]>::to_vec),
+        }
+    }
+
+    fn patch(diff: &FileDiff, selection: &[HunkSelection], reverse: bool) -> String {
+        let selection: Vec<&HunkSelection> = selection.iter().collect();
+        build_patch(diff, &selection, reverse).unwrap()
+    }
+
+    #[test]
+    fn whole_hunk_is_copied() {
+        let diff = file_diff(HUNK);
+        let selection = [select(&diff.hunks[0], None)];
+
+        assert_eq!(patch(&diff, &selection, false), format!("{}{}", HEADER, HUNK));
+    }
+
+    #[test]
+    fn unselected_lines_stay_as_in_the_base_version() {
+        let diff = file_diff(HUNK);
+        let hunk = &diff.hunks[0];
+
+        // Solo la añadida: la borrada sin elegir pasa a contexto y su pareja desaparece
+        assert_eq!(
+            hunk_body(hunk, Some(&[4]), false),
+            Some((" uno\n dos\n tres\n+nueva\n cuatro\n".to_string(), 4, 5))
+        );
+        // Solo el cambio de `dos`
+        assert_eq!(
+            hunk_body(hunk, Some(&[1, 2]), false),
+            Some((" uno\n-dos\n+DOS\n tres\n cuatro\n".to_string(), 4, 4))
+        );
+        // Solo contexto: no queda nada que aplicar
+        assert_eq!(hunk_body(hunk, Some(&[0, 3]), false), None);
+        assert_eq!(patch(&diff, &[select(hunk, Some(&[0]))], false), "");
+    }
+
+    #[test]
+    fn reverse_patches_start_from_the_new_version() {
+        let diff = file_diff(HUNK);
+        let hunk = &diff.hunks[0];
+
+        assert_eq!(
+            hunk_body(hunk, Some(&[4]), true),
+            Some((" uno\n DOS\n tres\n-nueva\n cuatro\n".to_string(), 5, 4))
+        );
+        assert_eq!(
+            patch(&diff, &[select(hunk, Some(&[4]))], true),
+            format!("{}@@ -1,5 +1,4 @@\n uno\n DOS\n tres\n-nueva\n cuatro\n", HEADER)
+        );
+    }
+
+    #[test]
+    fn later_hunks_move_by_the_selected_lines() {
+        let diff = file_diff(
+            "\
+@@ -1,2 +1,4 @@
+ a
++b
++c
+ d
+@@ -10,3 +12,2 @@ fn x
+ j
+-k
+ l
+",
+        );
+        let second = select(&diff.hunks[1], None);
+
+        let both = patch(&diff, &[select(&diff.hunks[0], None), second.clone()], false);
+        assert!(both.contains("@@ -1,2 +1,4 @@\n a\n+b\n+c\n d\n"));
+        assert!(both.contains("@@ -10,3 +12,2 @@\n j\n-k\n l\n"));
+
+        // Con una sola de las dos añadidas el segundo hunk se desplaza una línea
+        let partial = patch(&diff, &[select(&diff.hunks[0], Some(&[1])), second.clone()], false);
+        assert!(partial.contains("@@ -1,2 +1,3 @@\n a\n+b\n d\n"));
+        assert!(partial.contains("@@ -10,3 +11,2 @@\n j\n-k\n l\n"));
+
+        // Sin el primero, el segundo no se desplaza
+        let alone = patch(&diff, &[second], false);
+        assert_eq!(alone, format!("{}@@ -10,3 +10,2 @@\n j\n-k\n l\n", HEADER));
+    }
+
+    #[test]
+    fn hunks_without_lines_in_one_version() {
+        let diff = file_diff("@@ -0,0 +1,2 @@\n+uno\n+dos\n");
+        let selection = [select(&diff.hunks[0], None)];
+
+        assert_eq!(
+            patch(&diff, &selection, false),
+            format!("{}@@ -0,0 +1,2 @@\n+uno\n+dos\n", HEADER)
+        );
+        assert_eq!(
+            patch(&diff, &selection, true),
+            format!("{}@@ -1,2 +0,0 @@\n-uno\n-dos\n", HEADER)
+        );
+    }
+
+    #[test]
+    fn missing_newline_marker_is_kept() {
+        let hunk = "\
+@@ -1,2 +1,2 @@
+ a
+-b
+\\ No newline at end of file
++c
+\\ No newline at end of file
+";
+        let diff = file_diff(hunk);
+
+        assert_eq!(patch(&diff, &[select(&diff.hunks[0], None)], false), format!("{}{}", HEADER, hunk));
+    }
+
+    #[test]
+    fn changed_diffs_are_rejected() {
+        let diff = file_diff(HUNK);
+        let stale = HunkSelection {
+            file: "f.txt".to_string(),
+            old_start: 3,
+            new_start: 3,
+            lines: None,
+        };
+
+        let error = build_patch(&diff, &[&stale], false).unwrap_err();
+        assert!(error.contains("ha cambiado"), "{}", error);
+    }
+}
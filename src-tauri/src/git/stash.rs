@@ -0,0 +1,106 @@
+//! Stashes guardados.
+//!
+//! Un stash es un commit cuyo primer padre es el `HEAD` de cuando se creó; con
+//! archivos sin seguimiento, estos van en un tercer padre sin historia. La vista
+//! previa junta ambos: los cambios sobre el `HEAD` y los archivos nuevos.
+
+use serde::Serialize;
+
+use super::{backend, relative_time, FileDiff};
+
+/// Entrada de `git stash list`
+#[derive(Debug, Clone, Serialize)]
+pub struct GitStash {
+    /// Posición en la pila (`stash@{index}`); cambia al crear o borrar otros
+    pub index: usize,
+    pub hash: String,
+    /// Rama en la que se creó (`None` con `HEAD` desacoplado o sin ella en el reflog)
+    pub branch: Option<String>,
+    /// Mensaje indicado al crearlo o, sin él, el del commit de partida
+    pub message: String,
+    /// Fecha relativa para mostrar (ej: "hace 3 días")
+    pub date: String,
+    pub timestamp: i64,
+}
+
+impl GitStash {
+    /// `reflog_message` es el asunto del reflog: `On rama: mensaje` o
+    /// `WIP on rama: hash asunto` si no se indicó mensaje. Con `HEAD` desacoplado
+    /// la rama es `(no branch)`; `git stash store` puede guardar cualquier otro
+    pub(super) fn new(index: usize, hash: String, timestamp: i64, reflog_message: &str) -> Self {
+        let (branch, message) = reflog_message
+            .strip_prefix("WIP on ")
+            .or_else(|| reflog_message.strip_prefix("On "))
+            .and_then(|rest| rest.split_once(": "))
+            .map_or((None, reflog_message), |(branch, message)| {
+                ((branch != "(no branch)").then(|| branch.to_string()), message)
+            });
+
+        GitStash {
+            index,
+            hash,
+            branch,
+            message: message.to_string(),
+            date: relative_time(timestamp),
+            timestamp,
+        }
+    }
+}
+
+/// Cambios guardados en `stash@{index}`, incluidos los archivos sin seguimiento
+pub fn stash_diff(path: &str, index: usize) -> Result<Vec<FileDiff>, String> {
+    let stash = format!("stash@{{{}}}", index);
+    let mut diffs = backend().commit_diff(path, &stash)?;
+
+    // El tercer padre solo existe si se guardaron archivos sin seguimiento
+    if let Ok(untracked) = backend().commit_diff(path, &format!("{}^3", stash)) {
+        diffs.extend(untracked);
+    }
+
+    Ok(diffs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stash(reflog_message: &str) -> (Option<String>, String) {
+        let stash = GitStash::new(0, "abc".to_string(), 0, reflog_message);
+        (stash.branch, stash.message)
+    }
+
+    fn branch(name: &str) -> Option<String> {
+        Some(name.to_string())
+    }
+
+    #[test]
+    fn message_and_branch_from_reflog_subject() {
+        assert_eq!(stash("On main: guardar esto"), (branch("main"), "guardar esto".to_string()));
+        assert_eq!(
+            stash("WIP on feature/login: 037f764 primer commit"),
+            (branch("feature/login"), "037f764 primer commit".to_string())
+        );
+        // Solo el primer `: ` separa la rama
+        assert_eq!(stash("On main: fix: algo"), (branch("main"), "fix: algo".to_string()));
+    }
+
+    #[test]
+    fn subjects_without_a_branch() {
+        // `HEAD` desacoplado
+        assert_eq!(stash("On (no branch): con mensaje"), (None, "con mensaje".to_string()));
+        assert_eq!(stash("WIP on (no branch): 037f764 uno"), (None, "037f764 uno".to_string()));
+        // `git stash store -m` sin el formato habitual
+        assert_eq!(stash("guardado a mano"), (None, "guardado a mano".to_string()));
+        assert_eq!(stash("On main sin dos puntos"), (None, "On main sin dos puntos".to_string()));
+        assert_eq!(stash(""), (None, String::new()));
+    }
+
+    #[test]
+    fn keeps_position_hash_and_time() {
+        let stash = GitStash::new(2, "0123abc".to_string(), 1_700_000_000, "On main: x");
+        assert_eq!(stash.index, 2);
+        assert_eq!(stash.hash, "0123abc");
+        assert_eq!(stash.timestamp, 1_700_000_000);
+        assert!(!stash.date.is_empty());
+    }
+}
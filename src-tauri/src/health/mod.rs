@@ -0,0 +1,126 @@
+//! Puntuación de salud de los proyectos.
+//!
+//! Cada proyecto parte de 100 puntos y cada factor resta una penalización:
+//!
+//! | Factor                 | Penalización                                        |
+//! |------------------------|-----------------------------------------------------|
+//! | Sin abrir              | 5 (>30 días), 15 (>90 días), 25 (>180 días o nunca) |
+//! | Ruta local inexistente | 40 (y no se evalúa git)                             |
+//! | Cambios sin commitear  | 10 (20 con más de 20 archivos)                      |
+//! | Commits sin publicar   | 10                                                  |
+//! | Commits por traer      | 10                                                  |
+//! | TODOs vencidos         | 5 por TODO, hasta 25                                |
+//! | Backup                 | 15 si no hay ninguno, 5 (>30 días), 15 (>90 días)   |
+//!
+//! La puntuación nunca baja de 0. Los factores se devuelven de mayor a menor
+//! penalización para que la interfaz pueda explicar el resultado.
+
+use crate::models::project::{HealthFactor, HealthLevel, HealthSignals, Project, ProjectHealth};
+
+/// Estado de git del repositorio local (`None` si la ruta no es un repositorio)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GitHealth {
+    pub uncommitted_files: usize,
+    pub ahead: u32,
+    pub behind: u32,
+}
+
+const MAX_OVERDUE_TODOS_PENALTY: i64 = 25;
+
+pub fn compute(
+    project: &Project,
+    signals: &HealthSignals,
+    path_exists: bool,
+    git: Option<GitHealth>,
+) -> ProjectHealth {
+    let mut factors = Vec::new();
+    let mut add = |key: &str, description: String, penalty: i64| {
+        factors.push(HealthFactor {
+            key: key.to_string(),
+            description,
+            penalty,
+        });
+    };
+
+    if signals.never_opened {
+        add("never_opened", "Nunca se ha abierto".to_string(), 25);
+    } else {
+        let penalty = match signals.days_since_open {
+            d if d > 180 => 25,
+            d if d > 90 => 15,
+            d if d > 30 => 5,
+            _ => 0,
+        };
+        if penalty > 0 {
+            add(
+                "not_opened",
+                format!("Sin abrir hace {} días", signals.days_since_open),
+                penalty,
+            );
+        }
+    }
+
+    if !path_exists {
+        add(
+            "missing_local_path",
+            format!("La ruta local no existe: '{}'", project.local_path),
+            40,
+        );
+    }
+
+    if let Some(git) = git {
+        if git.uncommitted_files > 0 {
+            add(
+                "uncommitted_changes",
+                format!("{} archivos con cambios sin commitear", git.uncommitted_files),
+                if git.uncommitted_files > 20 { 20 } else { 10 },
+            );
+        }
+        if git.ahead > 0 {
+            add(
+                "unpushed_commits",
+                format!("{} commits sin publicar", git.ahead),
+                10,
+            );
+        }
+        if git.behind > 0 {
+            add(
+                "behind_remote",
+                format!("{} commits pendientes de traer del remoto", git.behind),
+                10,
+            );
+        }
+    }
+
+    if signals.overdue_todos > 0 {
+        add(
+            "overdue_todos",
+            format!("{} TODOs de hitos vencidos", signals.overdue_todos),
+            (signals.overdue_todos * 5).min(MAX_OVERDUE_TODOS_PENALTY),
+        );
+    }
+
+    match signals.days_since_backup {
+        None => add("no_backup", "No hay backups registrados".to_string(), 15),
+        Some(days) if days > 90 => add("old_backup", format!("Último backup hace {} días", days), 15),
+        Some(days) if days > 30 => add("old_backup", format!("Último backup hace {} días", days), 5),
+        Some(_) => {}
+    }
+
+    factors.sort_by_key(|f| std::cmp::Reverse(f.penalty));
+
+    let score = (100 - factors.iter().map(|f| f.penalty).sum::<i64>()).max(0);
+    let level = match score {
+        s if s >= 80 => HealthLevel::Good,
+        s if s >= 50 => HealthLevel::Warning,
+        _ => HealthLevel::Critical,
+    };
+
+    ProjectHealth {
+        project_id: project.id,
+        project_name: project.name.clone(),
+        score,
+        level,
+        factors,
+    }
+}
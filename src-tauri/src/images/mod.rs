@@ -0,0 +1,111 @@
+//! Procesado de las imágenes de los proyectos.
+//!
+//! El frontend envía la imagen como data URL en base64. Aquí se decodifica para
+//! comprobar que es realmente una imagen (PNG, JPEG, GIF o WebP) y se genera una
+//! miniatura pequeña para los listados. El original se conserva para la vista de
+//! detalle, con el tipo MIME corregido según el contenido real.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, ImageFormat, ImageReader, Limits};
+use std::io::Cursor;
+
+/// Lado máximo de la miniatura (las tarjetas la muestran a 64px; el doble para pantallas HiDPI)
+pub const THUMBNAIL_SIZE: u32 = 128;
+
+/// Tamaño máximo de la imagen original ya decodificada
+const MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+
+/// Dimensión máxima aceptada, para no reservar memoria desproporcionada al decodificar
+const MAX_DIMENSION: u32 = 8192;
+
+const THUMBNAIL_JPEG_QUALITY: u8 = 80;
+
+/// Imagen validada: original y miniatura como data URLs
+#[derive(Debug, Clone)]
+pub struct ProcessedImage {
+    pub original: String,
+    pub thumbnail: String,
+}
+
+/// Valida la imagen (data URL o base64 sin cabecera) y genera su miniatura
+pub fn process_image(data: &str) -> Result<ProcessedImage, String> {
+    let payload = base64_payload(data)?;
+    let bytes = STANDARD
+        .decode(payload)
+        .map_err(|_| "La imagen no está codificada en base64 válido".to_string())?;
+
+    if bytes.len() > MAX_IMAGE_BYTES {
+        return Err(format!(
+            "La imagen supera el tamaño máximo de {} MB",
+            MAX_IMAGE_BYTES / (1024 * 1024)
+        ));
+    }
+
+    let format = image::guess_format(&bytes)
+        .map_err(|_| "Los datos recibidos no son una imagen".to_string())?;
+    let mime = match format {
+        ImageFormat::Png => "image/png",
+        ImageFormat::Jpeg => "image/jpeg",
+        ImageFormat::Gif => "image/gif",
+        ImageFormat::WebP => "image/webp",
+        other => return Err(format!("Formato de imagen no soportado: {:?}", other)),
+    };
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+
+    let mut reader = ImageReader::with_format(Cursor::new(&bytes), format);
+    reader.limits(limits);
+    let image = reader
+        .decode()
+        .map_err(|e| format!("No se pudo leer la imagen: {}", e))?;
+
+    Ok(ProcessedImage {
+        original: format!("data:{};base64,{}", mime, payload),
+        thumbnail: encode_thumbnail(&image)?,
+    })
+}
+
+/// Extrae el base64 de una data URL (`data:<mime>;base64,<datos>`) o lo devuelve tal cual
+fn base64_payload(data: &str) -> Result<&str, String> {
+    let data = data.trim();
+    match data.strip_prefix("data:") {
+        Some(rest) => {
+            let (header, payload) = rest
+                .split_once(',')
+                .ok_or_else(|| "Data URL de imagen inválida".to_string())?;
+            if !header.ends_with(";base64") {
+                return Err("La imagen debe estar codificada en base64".to_string());
+            }
+            Ok(payload.trim())
+        }
+        None => Ok(data),
+    }
+}
+
+/// Reduce la imagen (sin ampliarla) y la codifica: PNG si tiene transparencia, JPEG si no
+fn encode_thumbnail(image: &DynamicImage) -> Result<String, String> {
+    let thumbnail = if image.width() > THUMBNAIL_SIZE || image.height() > THUMBNAIL_SIZE {
+        image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+    } else {
+        image.clone()
+    };
+
+    let mut buffer = Vec::new();
+    let mime = if thumbnail.color().has_alpha() {
+        DynamicImage::ImageRgba8(thumbnail.to_rgba8())
+            .write_to(&mut Cursor::new(&mut buffer), ImageFormat::Png)
+            .map_err(|e| format!("Error generando miniatura: {}", e))?;
+        "image/png"
+    } else {
+        DynamicImage::ImageRgb8(thumbnail.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut buffer, THUMBNAIL_JPEG_QUALITY))
+            .map_err(|e| format!("Error generando miniatura: {}", e))?;
+        "image/jpeg"
+    };
+
+    Ok(format!("data:{};base64,{}", mime, STANDARD.encode(&buffer)))
+}
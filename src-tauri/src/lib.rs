@@ -0,0 +1,194 @@
+//! Backend del gestor de proyectos.
+//!
+//! Los módulos se exponen como biblioteca para que los benchmarks (`benches/`)
+//! usen el mismo código que la aplicación.
+
+pub mod archive;
+pub mod attachments;
+pub mod commands;
+pub mod config;
+pub mod db;
+pub mod git;
+pub mod health;
+pub mod images;
+pub mod links;
+pub mod models;
+pub mod platform;
+pub mod protocol;
+pub mod scheduler;
+pub mod search;
+
+use db::Database;
+use config::ConfigManager;
+use tauri::Manager;
+
+/// Arranca la aplicación; `main.rs` solo llama a esta función
+pub fn run() {
+    // Crear directorio de datos de la app
+    let data_dir = dirs::data_local_dir()
+        .expect("No se pudo obtener el directorio de datos local")
+        .join("gestor-proyectos");
+
+    std::fs::create_dir_all(&data_dir).expect("No se pudo crear el directorio de datos");
+
+    let db_path = data_dir.join("projects.db");
+
+    let db = Database::new(db_path).expect("Error al inicializar la base de datos");
+    let config_manager = ConfigManager::new().expect("Error al inicializar la configuración");
+
+    if let Ok(config) = config_manager.get_config() {
+        db.set_analytics_enabled(config.advanced.enable_analytics);
+    }
+
+    tauri::Builder::default()
+        .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_notification::init())
+        .manage(db)
+        .manage(config_manager)
+        // gestor://attachment/{id} y gestor://project-image/{id}; se atiende fuera del hilo principal
+        .register_asynchronous_uri_scheme_protocol(protocol::SCHEME, |ctx, request, responder| {
+            let app = ctx.app_handle().clone();
+            std::thread::spawn(move || {
+                let response = protocol::handle(&app.state::<Database>(), &request);
+                responder.respond(response);
+            });
+        })
+        .setup(|app| {
+            // Recordatorios, retención de actividad y archivado: se revisan al arrancar y periódicamente
+            scheduler::start(app.handle().clone());
+            // Adjuntos cuyo texto quedó sin extraer (nuevos en esta versión o interrumpidos)
+            attachments::start_text_extraction(app.handle().clone());
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            commands::create_project,
+            commands::get_all_projects,
+            commands::get_project,
+            commands::update_project,
+            commands::delete_project,
+            commands::search_projects,
+            commands::parse_search_query,
+            commands::get_project_image,
+            commands::open_terminal,
+            commands::open_url,
+            commands::create_project_backup,
+            commands::write_file_to_path,
+            commands::sync_project_to_backup,
+            commands::sync_project,
+            commands::create_project_link,
+            commands::get_project_links,
+            commands::update_project_link,
+            commands::delete_project_link,
+            commands::check_links,
+            commands::get_broken_links,
+            commands::preview_bookmarks,
+            commands::import_bookmarks,
+            commands::track_project_open,
+            commands::add_project_time,
+            commands::get_project_stats,
+            commands::get_project_activities,
+            commands::get_activities,
+            commands::get_daily_activity,
+            commands::prune_activities,
+            commands::purge_analytics,
+            commands::add_attachment,
+            commands::add_attachment_from_path,
+            commands::add_attachments_from_paths,
+            commands::search_attachments,
+            commands::reindex_attachment,
+            commands::get_attachments,
+            commands::delete_attachment,
+            commands::add_attachment_version,
+            commands::get_attachment_versions,
+            commands::rollback_attachment,
+            commands::export_attachment,
+            commands::create_journal_entry,
+            commands::get_journal_entries,
+            commands::update_journal_entry,
+            commands::delete_journal_entry,
+            commands::create_todo,
+            commands::get_project_todos,
+            commands::update_todo,
+            commands::delete_todo,
+            commands::get_project_board,
+            commands::get_board_columns,
+            commands::create_board_column,
+            commands::update_board_column,
+            commands::delete_board_column,
+            commands::reorder_board_columns,
+            commands::move_todo,
+            commands::create_milestone,
+            commands::get_project_milestones,
+            commands::update_milestone,
+            commands::delete_milestone,
+            commands::set_todo_milestone,
+            commands::get_upcoming_milestones,
+            commands::create_reminder,
+            commands::get_reminders,
+            commands::snooze_reminder,
+            commands::dismiss_reminder,
+            commands::delete_reminder,
+            commands::update_project_status,
+            commands::toggle_pin_project,
+            commands::reorder_pinned_projects,
+            commands::create_smart_view,
+            commands::get_smart_views,
+            commands::update_smart_view,
+            commands::delete_smart_view,
+            commands::run_smart_view,
+            commands::preview_smart_view,
+            commands::create_project_relation,
+            commands::delete_project_relation,
+            commands::get_project_relations,
+            commands::get_project_dependents,
+            commands::get_project_graph,
+            commands::get_git_branch,
+            commands::get_git_status,
+            commands::get_recent_commits,
+            commands::get_git_branches,
+            commands::git_create_branch,
+            commands::git_switch_branch,
+            commands::git_rename_branch,
+            commands::git_delete_branch,
+            commands::get_git_stashes,
+            commands::git_stash_save,
+            commands::git_stash_apply,
+            commands::git_stash_pop,
+            commands::git_stash_drop,
+            commands::get_git_stash_diff,
+            commands::get_git_status_detailed,
+            commands::get_git_diff,
+            commands::get_commit_diff,
+            commands::get_git_file_count,
+            commands::get_git_modified_files,
+            commands::git_add,
+            commands::git_stage_hunks,
+            commands::git_unstage_hunks,
+            commands::git_unstage_files,
+            commands::git_discard_preview,
+            commands::git_discard_changes,
+            commands::git_commit,
+            commands::git_push,
+            commands::git_pull,
+            commands::get_git_remote_url,
+            commands::get_git_ahead_behind,
+            commands::get_project_health,
+            commands::get_projects_health,
+            commands::get_stale_projects,
+            commands::archive_stale_projects,
+            commands::get_config,
+            commands::update_config,
+            commands::reset_config,
+            commands::detect_programs,
+            commands::open_file_manager,
+            commands::open_text_editor,
+            commands::select_backup_folder,
+            commands::get_shortcuts_config,
+            commands::update_shortcuts_config,
+        ])
+        .run(tauri::generate_context!())
+        .expect("Error al ejecutar la aplicación Tauri");
+}
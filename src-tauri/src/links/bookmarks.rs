@@ -0,0 +1,439 @@
+//! Importación de marcadores del navegador.
+//!
+//! | Formato                       | Origen                                           |
+//! |-------------------------------|--------------------------------------------------|
+//! | HTML de Netscape              | "Exportar marcadores" de cualquier navegador     |
+//! | JSON de Chromium              | Archivo `Bookmarks` del perfil (Chrome, Edge...) |
+//! | JSON de Firefox               | Copia de seguridad de marcadores (`.json`)       |
+//!
+//! El archivo se lee entero y se devuelve como lista de carpetas; el usuario
+//! elige qué carpetas van a qué proyecto y solo entonces se crean los enlaces.
+
+use reqwest::Url;
+use serde_json::Value;
+use std::collections::HashSet;
+
+use super::metadata::{attribute, collapse_whitespace, decode_entities, infer_link_type, url_host};
+use crate::db::Database;
+use crate::models::project::{
+    Bookmark, BookmarkFolder, BookmarkImportSummary, BookmarkMapping, CreateLinkDTO,
+};
+
+/// Lee un archivo de marcadores en cualquiera de los formatos admitidos.
+/// Solo se devuelven las carpetas que contienen algún marcador.
+pub fn parse_bookmarks(content: &str) -> Result<Vec<BookmarkFolder>, String> {
+    let content = content.trim_start_matches('\u{feff}').trim_start();
+    let mut folders = Vec::new();
+
+    if content.starts_with('{') || content.starts_with('[') {
+        let json: Value = serde_json::from_str(content)
+            .map_err(|e| format!("JSON de marcadores inválido: {}", e))?;
+
+        match json.get("roots").and_then(Value::as_object) {
+            // Chromium: `roots` con `bookmark_bar`, `other` y `synced`
+            Some(roots) => {
+                for root in roots.values() {
+                    walk_json(root, &mut Vec::new(), &mut folders);
+                }
+            }
+            // Firefox: un único contenedor raíz sin título
+            None => walk_json(&json, &mut Vec::new(), &mut folders),
+        }
+    } else if content.to_ascii_lowercase().contains("<dl") {
+        parse_netscape(content, &mut folders);
+    } else {
+        return Err(
+            "Formato no reconocido: se espera un HTML exportado o un JSON de Firefox/Chromium"
+                .to_string(),
+        );
+    }
+
+    if folders.is_empty() {
+        return Err("El archivo no contiene marcadores".to_string());
+    }
+
+    Ok(folders)
+}
+
+fn add_bookmark(folders: &mut Vec<BookmarkFolder>, path: &[String], title: &str, url: &str) {
+    let url = url.trim();
+    if url.is_empty() {
+        return;
+    }
+
+    let bookmark = Bookmark {
+        title: collapse_whitespace(title),
+        url: url.to_string(),
+    };
+
+    match folders.iter_mut().find(|folder| folder.path == path) {
+        Some(folder) => folder.bookmarks.push(bookmark),
+        None => folders.push(BookmarkFolder {
+            path: path.to_vec(),
+            bookmarks: vec![bookmark],
+        }),
+    }
+}
+
+/// Recorre un nodo de Chromium (`name`/`url`/`children`) o de Firefox (`title`/`uri`/`children`)
+fn walk_json(node: &Value, path: &mut Vec<String>, folders: &mut Vec<BookmarkFolder>) {
+    let title = node
+        .get("name")
+        .or_else(|| node.get("title"))
+        .and_then(Value::as_str)
+        .unwrap_or("");
+
+    if let Some(url) = node.get("url").or_else(|| node.get("uri")).and_then(Value::as_str) {
+        add_bookmark(folders, path, title, url);
+        return;
+    }
+
+    let Some(children) = node.get("children").and_then(Value::as_array) else {
+        return;
+    };
+
+    // El contenedor raíz de Firefox no tiene título: sus hijos cuelgan de la raíz
+    let named = !title.trim().is_empty();
+    if named {
+        path.push(collapse_whitespace(title));
+    }
+    for child in children {
+        walk_json(child, path, folders);
+    }
+    if named {
+        path.pop();
+    }
+}
+
+/// Formato `NETSCAPE-Bookmark-file-1`: cada `<H3>` da nombre a la `<DL>` que le sigue
+/// y cada `<A HREF>` es un marcador de la `<DL>` abierta.
+fn parse_netscape(html: &str, folders: &mut Vec<BookmarkFolder>) {
+    // Misma longitud en bytes que el original: los índices sirven para ambos
+    let lower = html.to_ascii_lowercase();
+
+    let mut path: Vec<String> = Vec::new();
+    // Por cada `<DL>` abierta, si añadió un nombre a `path`
+    let mut open_lists: Vec<bool> = Vec::new();
+    let mut pending_folder: Option<String> = None;
+    let mut offset = 0;
+
+    while let Some(found) = lower[offset..].find('<') {
+        let start = offset + found;
+        let Some(end) = lower[start..].find('>').map(|end| start + end) else {
+            break;
+        };
+        offset = end + 1;
+
+        let tag = &lower[start + 1..end];
+        let name = tag
+            .split(|c: char| c.is_ascii_whitespace())
+            .next()
+            .unwrap_or("");
+
+        match name {
+            "h3" | "a" => {
+                let close = format!("</{}", name);
+                let Some(text_end) = lower[offset..].find(&close).map(|end| offset + end) else {
+                    continue;
+                };
+                let text = decode_entities(&html[offset..text_end]);
+                offset = text_end;
+
+                if name == "h3" {
+                    pending_folder = Some(collapse_whitespace(&text));
+                } else if let Some(href) = attribute(&html[start..end], "href") {
+                    add_bookmark(folders, &path, &text, &decode_entities(href));
+                }
+            }
+            "dl" => {
+                let folder = pending_folder.take().filter(|folder| !folder.is_empty());
+                open_lists.push(folder.is_some());
+                path.extend(folder);
+            }
+            "/dl" => {
+                let named = open_lists.pop().unwrap_or(false);
+                if named {
+                    path.pop();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Forma canónica de una URL para detectar duplicados: sin espacios ni `/` final,
+/// con el esquema y el dominio en minúsculas.
+fn normalize_url(url: &str) -> String {
+    let url = url.trim();
+    let normalized = Url::parse(url)
+        .map(|parsed| parsed.to_string())
+        .unwrap_or_else(|_| url.to_string());
+    normalized.trim_end_matches('/').to_string()
+}
+
+/// Solo se importan enlaces web y de archivo: los marcadores `javascript:`,
+/// `place:` (búsquedas de Firefox) o `chrome://` no tienen sentido como enlace.
+fn is_importable(url: &str) -> bool {
+    Url::parse(url).is_ok_and(|url| matches!(url.scheme(), "http" | "https" | "file"))
+}
+
+/// Crea los enlaces de cada carpeta elegida en su proyecto, sin repetir URLs
+/// que el proyecto ya tenga. El tipo se deduce de la URL y, sin título, se usa el dominio.
+pub fn import_bookmarks(
+    db: &Database,
+    folders: &[BookmarkFolder],
+    mappings: &[BookmarkMapping],
+) -> Result<BookmarkImportSummary, String> {
+    let mut summary = BookmarkImportSummary::default();
+
+    // Agrupar por proyecto: varias carpetas pueden ir al mismo
+    let mut project_ids: Vec<i64> = mappings.iter().map(|mapping| mapping.project_id).collect();
+    project_ids.sort_unstable();
+    project_ids.dedup();
+
+    for project_id in project_ids {
+        let mut seen: HashSet<String> = db
+            .get_project_links(project_id)
+            .map_err(|e| format!("Error leyendo enlaces del proyecto {}: {}", project_id, e))?
+            .iter()
+            .map(|link| normalize_url(&link.url))
+            .collect();
+
+        let bookmarks = mappings
+            .iter()
+            .filter(|mapping| mapping.project_id == project_id)
+            .flat_map(|mapping| {
+                folders.iter().filter(move |folder| {
+                    folder.path == mapping.folder
+                        || (mapping.include_subfolders && folder.path.starts_with(&mapping.folder))
+                })
+            })
+            .flat_map(|folder| &folder.bookmarks);
+
+        let mut links = Vec::new();
+        for bookmark in bookmarks {
+            if !is_importable(&bookmark.url) {
+                summary.skipped += 1;
+                continue;
+            }
+            if !seen.insert(normalize_url(&bookmark.url)) {
+                summary.duplicates += 1;
+                continue;
+            }
+
+            let title = match bookmark.title.trim() {
+                "" => url_host(&bookmark.url).unwrap_or_else(|| bookmark.url.clone()),
+                title => title.to_string(),
+            };
+            links.push(CreateLinkDTO {
+                project_id,
+                link_type: infer_link_type(&bookmark.url).unwrap_or("other").to_string(),
+                title,
+                url: bookmark.url.clone(),
+            });
+        }
+
+        summary.imported += db
+            .import_links(project_id, &links)
+            .map_err(|e| format!("Error importando enlaces del proyecto {}: {}", project_id, e))?;
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{create_project, memory_database};
+
+    const NETSCAPE: &str = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks</H1>
+<DL><p>
+    <DT><A HREF="https://example.com/suelto">Suelto</A>
+    <DT><H3 ADD_DATE="1">Barra de marcadores</H3>
+    <DL><p>
+        <DT><A HREF="https://github.com/acme/app" ADD_DATE="2">Repo   de
+            Acme</A>
+        <DT><H3>Clientes &amp; socios</H3>
+        <DL><p>
+            <DT><A HREF="https://acme.example.com/?a=1&amp;b=2">Acme</A>
+        </DL><p>
+        <DT><A HREF="https://docs.rs/serde">Serde</A>
+    </DL><p>
+</DL><p>
+"#;
+
+    const CHROMIUM: &str = r#"{
+        "checksum": "x",
+        "roots": {
+            "bookmark_bar": {
+                "name": "Barra de marcadores",
+                "type": "folder",
+                "children": [
+                    { "name": "Repo", "type": "url", "url": "https://github.com/acme/app" },
+                    { "name": "Clientes", "type": "folder", "children": [
+                        { "name": "Acme", "type": "url", "url": "https://acme.example.com/" }
+                    ] }
+                ]
+            },
+            "other": { "name": "Otros marcadores", "type": "folder", "children": [] }
+        },
+        "version": 1
+    }"#;
+
+    const FIREFOX: &str = r#"{
+        "title": "",
+        "type": "text/x-moz-place-container",
+        "children": [
+            { "title": "menu", "type": "text/x-moz-place-container", "children": [
+                { "title": "Docs", "type": "text/x-moz-place", "uri": "https://docs.rs/serde" },
+                { "title": "Búsqueda", "type": "text/x-moz-place", "uri": "place:sort=8" }
+            ] },
+            { "title": "toolbar", "type": "text/x-moz-place-container", "children": [] }
+        ]
+    }"#;
+
+    /// Ruta de la carpeta con el título y la URL de sus marcadores
+    type FolderSummary = (Vec<String>, Vec<(String, String)>);
+
+    fn summary(folders: &[BookmarkFolder]) -> Vec<FolderSummary> {
+        folders
+            .iter()
+            .map(|folder| {
+                let bookmarks = folder
+                    .bookmarks
+                    .iter()
+                    .map(|bookmark| (bookmark.title.clone(), bookmark.url.clone()))
+                    .collect();
+                (folder.path.clone(), bookmarks)
+            })
+            .collect()
+    }
+
+    fn path(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn bookmark(title: &str, url: &str) -> (String, String) {
+        (title.to_string(), url.to_string())
+    }
+
+    #[test]
+    fn netscape_html() {
+        let folders = parse_bookmarks(NETSCAPE).unwrap();
+        assert_eq!(
+            summary(&folders),
+            vec![
+                (path(&[]), vec![bookmark("Suelto", "https://example.com/suelto")]),
+                (
+                    path(&["Barra de marcadores"]),
+                    vec![
+                        bookmark("Repo de Acme", "https://github.com/acme/app"),
+                        bookmark("Serde", "https://docs.rs/serde"),
+                    ]
+                ),
+                (
+                    path(&["Barra de marcadores", "Clientes & socios"]),
+                    vec![bookmark("Acme", "https://acme.example.com/?a=1&b=2")]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn chromium_json() {
+        let folders = parse_bookmarks(&format!("\u{feff}{}", CHROMIUM)).unwrap();
+        assert_eq!(
+            summary(&folders),
+            vec![
+                (path(&["Barra de marcadores"]), vec![bookmark("Repo", "https://github.com/acme/app")]),
+                (
+                    path(&["Barra de marcadores", "Clientes"]),
+                    vec![bookmark("Acme", "https://acme.example.com/")]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn firefox_json() {
+        let folders = parse_bookmarks(FIREFOX).unwrap();
+        assert_eq!(
+            summary(&folders),
+            vec![(
+                path(&["menu"]),
+                vec![
+                    bookmark("Docs", "https://docs.rs/serde"),
+                    bookmark("Búsqueda", "place:sort=8"),
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn files_without_bookmarks_are_rejected() {
+        let empty = r#"{ "roots": { "bookmark_bar": { "name": "Barra", "children": [] } } }"#;
+        assert_eq!(
+            parse_bookmarks(empty).unwrap_err(),
+            "El archivo no contiene marcadores"
+        );
+        assert_eq!(
+            parse_bookmarks("<DL><p></DL>").unwrap_err(),
+            "El archivo no contiene marcadores"
+        );
+        assert!(parse_bookmarks("").unwrap_err().starts_with("Formato no reconocido"));
+        assert!(parse_bookmarks("{ roto").unwrap_err().starts_with("JSON de marcadores inválido"));
+    }
+
+    #[test]
+    fn import_skips_existing_and_repeated_urls() {
+        let db = memory_database();
+        let project = create_project(&db, "Proyecto", None);
+        db.create_link(CreateLinkDTO {
+            project_id: project.id,
+            link_type: "repository".to_string(),
+            title: "Repo".to_string(),
+            url: "HTTPS://GitHub.com/acme/app/".to_string(),
+        })
+        .unwrap();
+
+        let mut folders = parse_bookmarks(NETSCAPE).unwrap();
+        folders.extend(parse_bookmarks(FIREFOX).unwrap());
+        let mappings = [
+            BookmarkMapping {
+                folder: path(&["Barra de marcadores"]),
+                project_id: project.id,
+                include_subfolders: true,
+            },
+            BookmarkMapping {
+                folder: path(&["menu"]),
+                project_id: project.id,
+                include_subfolders: false,
+            },
+        ];
+
+        let summary = import_bookmarks(&db, &folders, &mappings).unwrap();
+        // Repo ya existía con otra forma y Serde viene en los dos archivos
+        assert_eq!(summary.imported, 2);
+        assert_eq!(summary.duplicates, 2);
+        assert_eq!(summary.skipped, 1);
+
+        let mut urls: Vec<String> = db
+            .get_project_links(project.id)
+            .unwrap()
+            .into_iter()
+            .map(|link| link.url)
+            .collect();
+        urls.sort();
+        assert_eq!(
+            urls,
+            vec![
+                "HTTPS://GitHub.com/acme/app/",
+                "https://acme.example.com/?a=1&b=2",
+                "https://docs.rs/serde",
+            ]
+        );
+    }
+}
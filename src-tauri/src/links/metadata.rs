@@ -0,0 +1,522 @@
+use reqwest::blocking::Client;
+use reqwest::Url;
+use std::io::Read;
+use std::time::Duration;
+
+use crate::db::Database;
+use crate::models::project::CreateLinkDTO;
+
+/// Tiempo máximo para descargar la página al crear un enlace
+pub const METADATA_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Tras un fallo (ej: sin conexión) no se vuelve a descargar la página hasta pasadas estas horas
+const RETRY_FAILED_HOURS: f64 = 24.0;
+
+/// Solo se lee el principio de la página: el `<head>` suele estar ahí
+const MAX_PAGE_BYTES: u64 = 512 * 1024;
+
+const MAX_FAVICON_BYTES: u64 = 100 * 1024;
+
+const MAX_TITLE_CHARS: usize = 200;
+
+/// Metadatos guardados de una URL
+#[derive(Debug, Clone)]
+pub struct CachedMetadata {
+    pub title: Option<String>,
+    pub error: Option<String>,
+    /// Horas desde la descarga
+    pub age_hours: f64,
+}
+
+/// Favicon ya descargado y validado
+#[derive(Debug, Clone)]
+pub struct Favicon {
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Datos extraídos del HTML de una página
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PageMetadata {
+    pub title: Option<String>,
+    pub favicon_url: Option<Url>,
+}
+
+/// URL tal cual o, si no tiene esquema (ej: `github.com/usuario/repo`), con `https://`
+fn parse_url(url: &str) -> Option<Url> {
+    Url::parse(url.trim())
+        .or_else(|_| Url::parse(&format!("https://{}", url.trim())))
+        .ok()
+}
+
+/// Tipo de enlace según patrones conocidos de la URL (`None` si no se reconoce)
+pub fn infer_link_type(url: &str) -> Option<&'static str> {
+    let parsed = parse_url(url)?;
+    let host = parsed.host_str()?.to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let path = parsed.path().to_lowercase();
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    let host_is = |domains: &[&str]| {
+        domains
+            .iter()
+            .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)))
+    };
+
+    if host_is(&["drive.google.com", "docs.google.com", "dropbox.com", "onedrive.live.com", "1drv.ms", "sharepoint.com", "box.com"]) {
+        return Some("drive");
+    }
+
+    if host_is(&["figma.com", "sketch.com", "miro.com", "zeplin.io", "invisionapp.com", "canva.com", "excalidraw.com", "penpot.app"]) {
+        return Some("design");
+    }
+
+    let is_forge = host_is(&["github.com", "gitlab.com", "bitbucket.org", "codeberg.org", "sr.ht", "gitea.com"])
+        || host.starts_with("gitlab.")
+        || host.starts_with("gitea.");
+
+    // `/browse/PROJ-123` solo es una incidencia en Jira
+    let is_jira = host_is(&["atlassian.net"]) || host.starts_with("jira.");
+
+    if host_is(&["linear.app", "trello.com", "youtrack.cloud", "clickup.com", "asana.com"])
+        || is_jira
+        || segments.contains(&"jira")
+        || (is_forge && segments.iter().any(|s| matches!(*s, "issues" | "pulls" | "merge_requests")))
+    {
+        return Some("issues");
+    }
+
+    if host_is(&["readthedocs.io", "readthedocs.org", "docs.rs", "gitbook.io", "notion.so", "notion.site"])
+        || host.starts_with("docs.")
+        || host.starts_with("wiki.")
+        || segments.iter().any(|s| matches!(*s, "docs" | "doc" | "wiki" | "documentation"))
+    {
+        return Some("documentation");
+    }
+
+    if (is_forge && segments.len() >= 2) || path.ends_with(".git") {
+        return Some("repository");
+    }
+
+    None
+}
+
+/// Título e icono declarados en el HTML. Sin `<link rel="icon">` se usa `/favicon.ico`.
+pub fn parse_page(html: &str, base: &Url) -> PageMetadata {
+    // Misma longitud en bytes que el original: los índices sirven para ambos
+    let lower = html.to_ascii_lowercase();
+
+    let title = lower.find("<title").and_then(|start| {
+        let content_start = start + lower[start..].find('>')? + 1;
+        let content_end = content_start + lower[content_start..].find("</title")?;
+        let title = collapse_whitespace(&decode_entities(&html[content_start..content_end]));
+        (!title.is_empty()).then(|| title.chars().take(MAX_TITLE_CHARS).collect())
+    });
+
+    let mut icon = None;
+    let mut touch_icon = None;
+    let mut offset = 0;
+    while let Some(start) = lower[offset..].find("<link") {
+        let start = offset + start;
+        let Some(end) = lower[start..].find('>').map(|end| start + end) else {
+            break;
+        };
+        offset = end;
+
+        let tag = &html[start..end];
+        let (Some(rel), Some(href)) = (attribute(tag, "rel"), attribute(tag, "href")) else {
+            continue;
+        };
+        let rel = rel.to_ascii_lowercase();
+        let rel: Vec<&str> = rel.split_whitespace().collect();
+        let Ok(url) = base.join(href.trim()) else {
+            continue;
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            continue;
+        }
+
+        if rel.contains(&"icon") && icon.is_none() {
+            icon = Some(url);
+        } else if rel.contains(&"apple-touch-icon") && touch_icon.is_none() {
+            touch_icon = Some(url);
+        }
+    }
+
+    PageMetadata {
+        title,
+        favicon_url: icon.or(touch_icon).or_else(|| base.join("/favicon.ico").ok()),
+    }
+}
+
+/// Valor de un atributo de una etiqueta HTML (con comillas simples, dobles o sin comillas)
+pub(super) fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let lower = tag.to_ascii_lowercase();
+    let mut offset = 0;
+
+    while let Some(found) = lower[offset..].find(name) {
+        let start = offset + found;
+        offset = start + name.len();
+
+        // Debe ser el nombre completo del atributo, no parte de otro (ej: `data-href`)
+        let preceded_by_space = lower[..start].ends_with(|c: char| c.is_ascii_whitespace());
+        let rest = lower[offset..].trim_start();
+        if !preceded_by_space || !rest.starts_with('=') {
+            continue;
+        }
+
+        let value_start = tag.len() - rest.len() + 1;
+        let value = tag[value_start..].trim_start();
+        return match value.chars().next()? {
+            quote @ ('"' | '\'') => value[1..].split(quote).next(),
+            // Sin comillas, el `/` final puede ser el cierre de la etiqueta (`<link ... href=x/>`)
+            _ => value
+                .split(|c: char| c.is_ascii_whitespace())
+                .next()
+                .map(|value| value.strip_suffix('/').unwrap_or(value)),
+        };
+    }
+
+    None
+}
+
+pub(super) fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| {
+                let name = &rest[1..end];
+                let c = match name {
+                    "amp" => Some('&'),
+                    "lt" => Some('<'),
+                    "gt" => Some('>'),
+                    "quot" => Some('"'),
+                    "apos" => Some('\''),
+                    "nbsp" => Some(' '),
+                    "ndash" => Some('–'),
+                    "mdash" => Some('—'),
+                    "middot" => Some('·'),
+                    "hellip" => Some('…'),
+                    "laquo" => Some('«'),
+                    "raquo" => Some('»'),
+                    "iexcl" => Some('¡'),
+                    "iquest" => Some('¿'),
+                    "copy" => Some('©'),
+                    "aacute" => Some('á'),
+                    "eacute" => Some('é'),
+                    "iacute" => Some('í'),
+                    "oacute" => Some('ó'),
+                    "uacute" => Some('ú'),
+                    "Aacute" => Some('Á'),
+                    "Eacute" => Some('É'),
+                    "Iacute" => Some('Í'),
+                    "Oacute" => Some('Ó'),
+                    "Uacute" => Some('Ú'),
+                    "ntilde" => Some('ñ'),
+                    "Ntilde" => Some('Ñ'),
+                    "uuml" => Some('ü'),
+                    "Uuml" => Some('Ü'),
+                    _ => name
+                        .strip_prefix("#x")
+                        .or_else(|| name.strip_prefix("#X"))
+                        .map(|hex| u32::from_str_radix(hex, 16))
+                        .or_else(|| name.strip_prefix('#').map(|dec| dec.parse()))
+                        .and_then(|code| code.ok())
+                        .and_then(char::from_u32),
+                };
+                c.map(|c| (c, end))
+            });
+
+        match entity {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+pub(super) fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Descarga la página y extrae su título y la URL de su icono
+pub fn fetch_page_metadata(client: &Client, url: &Url) -> Result<PageMetadata, String> {
+    let response = client
+        .get(url.clone())
+        .send()
+        .map_err(|e| format!("Error descargando la página: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("La página respondió {}", response.status().as_u16()));
+    }
+
+    let is_html = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_none_or(|value| value.contains("html"));
+    // Las redirecciones cambian la base de las rutas relativas
+    let base = response.url().clone();
+
+    if !is_html {
+        return Ok(PageMetadata {
+            title: None,
+            favicon_url: base.join("/favicon.ico").ok(),
+        });
+    }
+
+    let mut body = Vec::new();
+    response
+        .take(MAX_PAGE_BYTES)
+        .read_to_end(&mut body)
+        .map_err(|e| format!("Error leyendo la página: {}", e))?;
+
+    Ok(parse_page(&String::from_utf8_lossy(&body), &base))
+}
+
+/// Descarga un favicon. Solo se aceptan imágenes de hasta `MAX_FAVICON_BYTES`.
+pub fn fetch_favicon(client: &Client, url: &Url) -> Result<Favicon, String> {
+    let response = client
+        .get(url.clone())
+        .send()
+        .map_err(|e| format!("Error descargando favicon: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("El favicon respondió {}", response.status().as_u16()));
+    }
+
+    let mut data = Vec::new();
+    response
+        .take(MAX_FAVICON_BYTES + 1)
+        .read_to_end(&mut data)
+        .map_err(|e| format!("Error leyendo favicon: {}", e))?;
+    if data.len() as u64 > MAX_FAVICON_BYTES {
+        return Err("El favicon es demasiado grande".to_string());
+    }
+
+    let mime_type = match infer::get(&data) {
+        Some(kind) if kind.mime_type().starts_with("image/") => kind.mime_type().to_string(),
+        _ if is_svg(&data) => "image/svg+xml".to_string(),
+        _ => return Err("El favicon no es una imagen".to_string()),
+    };
+
+    Ok(Favicon { mime_type, data })
+}
+
+fn is_svg(data: &[u8]) -> bool {
+    let start = String::from_utf8_lossy(&data[..data.len().min(512)]).to_ascii_lowercase();
+    start.trim_start().starts_with("<svg") || (start.contains("<?xml") && start.contains("<svg"))
+}
+
+/// Dominio de una URL, usado como clave de los favicons
+pub fn url_host(url: &str) -> Option<String> {
+    parse_url(url).and_then(|url| url.host_str().map(|host| host.to_lowercase()))
+}
+
+/// Completa un enlace nuevo antes de guardarlo:
+/// - sin `link_type`, el tipo se deduce de la URL (`other` si no se reconoce);
+/// - sin título, se usa el `<title>` de la página o, en su defecto, el dominio.
+///
+/// La página solo hace falta sin título o si aún no hay favicon del dominio.
+/// Primero se consulta la caché; solo se descarga si `fetch` y no hay datos
+/// guardados (o el último intento falló hace más de `RETRY_FAILED_HOURS`).
+/// Sin conexión el enlace se crea igualmente.
+pub fn prepare_link(db: &Database, mut link: CreateLinkDTO, fetch: bool) -> CreateLinkDTO {
+    link.url = link.url.trim().to_string();
+    link.title = link.title.trim().to_string();
+
+    if link.link_type.trim().is_empty() {
+        link.link_type = infer_link_type(&link.url).unwrap_or("other").to_string();
+    }
+
+    if link.title.is_empty() {
+        link.title = page_title(db, &link.url, fetch)
+            .or_else(|| url_host(&link.url))
+            .unwrap_or_else(|| link.url.clone());
+    } else if !url_host(&link.url).is_some_and(|host| db.has_favicon(&host).unwrap_or(false)) {
+        // Con título propio la página solo se descarga por el favicon
+        page_title(db, &link.url, fetch);
+    }
+
+    link
+}
+
+/// Título de la página desde la caché o descargándola (guarda también el favicon)
+fn page_title(db: &Database, url: &str, fetch: bool) -> Option<String> {
+    let cached = match db.get_link_metadata(url) {
+        Ok(cached) => cached,
+        Err(e) => {
+            println!("🔗 [LINKS] Error leyendo metadatos de '{}': {}", url, e);
+            None
+        }
+    };
+
+    let retry = cached
+        .as_ref()
+        .is_none_or(|cached| cached.error.is_some() && cached.age_hours >= RETRY_FAILED_HOURS);
+    let parsed = parse_url(url).filter(|url| matches!(url.scheme(), "http" | "https"));
+
+    let (Some(parsed), true, true) = (parsed, fetch, retry) else {
+        return cached.and_then(|cached| cached.title);
+    };
+    let host = parsed.host_str().map(|host| host.to_lowercase());
+
+    let client = match super::http_client(METADATA_TIMEOUT) {
+        Ok(client) => client,
+        Err(e) => {
+            println!("🔗 [LINKS] {}", e);
+            return cached.and_then(|cached| cached.title);
+        }
+    };
+
+    let (title, error) = match fetch_page_metadata(&client, &parsed) {
+        Ok(page) => {
+            if let (Some(host), Some(favicon_url)) = (&host, &page.favicon_url) {
+                store_favicon(db, &client, host, favicon_url);
+            }
+            (page.title, None)
+        }
+        Err(e) => {
+            println!("🔗 [LINKS] Sin metadatos para '{}': {}", url, e);
+            (None, Some(e))
+        }
+    };
+
+    if let Err(e) = db.save_link_metadata(url, host.as_deref(), title.as_deref(), error.as_deref()) {
+        println!("🔗 [LINKS] Error guardando metadatos de '{}': {}", url, e);
+    }
+
+    title.or_else(|| cached.and_then(|cached| cached.title))
+}
+
+/// Descarga y guarda el favicon del dominio si aún no está guardado
+fn store_favicon(db: &Database, client: &Client, host: &str, favicon_url: &Url) {
+    if db.has_favicon(host).unwrap_or(false) {
+        return;
+    }
+
+    match fetch_favicon(client, favicon_url) {
+        Ok(favicon) => {
+            if let Err(e) = db.save_favicon(host, &favicon) {
+                println!("🔗 [LINKS] Error guardando favicon de '{}': {}", host, e);
+            }
+        }
+        Err(e) => println!("🔗 [LINKS] Sin favicon para '{}': {}", host, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Url {
+        Url::parse("https://example.com/docs/guia.html").unwrap()
+    }
+
+    #[test]
+    fn title_is_decoded_and_collapsed() {
+        let page = parse_page(
+            "<html><HEAD><Title lang=\"es\">\n  Gu&iacute;a &amp; API &#8212;\t&#x41;yuda </TITLE></head></html>",
+            &base(),
+        );
+        assert_eq!(page.title.as_deref(), Some("Guía & API — Ayuda"));
+
+        assert_eq!(parse_page("<title>   </title>", &base()).title, None);
+        assert_eq!(parse_page("<p>sin título</p>", &base()).title, None);
+    }
+
+    #[test]
+    fn icon_hrefs_are_resolved_against_the_page() {
+        let icon = |html: &str| parse_page(html, &base()).favicon_url.map(|url| url.to_string());
+
+        assert_eq!(
+            icon("<link rel=\"icon\" href=\"img/icono.png\">"),
+            Some("https://example.com/docs/img/icono.png".to_string())
+        );
+        assert_eq!(
+            icon("<link rel='shortcut icon' href='/favicon.svg'/>"),
+            Some("https://example.com/favicon.svg".to_string())
+        );
+        // Sin esquema: se usa el de la página
+        assert_eq!(
+            icon("<link rel=icon href=//cdn.example.net/i.ico>"),
+            Some("https://cdn.example.net/i.ico".to_string())
+        );
+        // `icon` gana a `apple-touch-icon` aunque vaya después
+        assert_eq!(
+            icon("<link rel=\"apple-touch-icon\" href=\"/touch.png\"><link rel=\"icon\" href=\"/i.png\">"),
+            Some("https://example.com/i.png".to_string())
+        );
+        // Sin icono declarado (o con uno no http)
+        assert_eq!(
+            icon("<link rel=\"icon\" href=\"data:image/png;base64,AAAA\">"),
+            Some("https://example.com/favicon.ico".to_string())
+        );
+    }
+
+    #[test]
+    fn attribute_matches_whole_names() {
+        let tag = "<link data-href=\"/otro.png\" rel=icon HREF = '/bueno.png'";
+        assert_eq!(attribute(tag, "href"), Some("/bueno.png"));
+        assert_eq!(attribute(tag, "rel"), Some("icon"));
+        assert_eq!(attribute("<link data-href=\"/otro.png\">", "href"), None);
+        // `parse_page` pasa la etiqueta sin el `>` final
+        assert_eq!(attribute("<link href=/a.png/", "href"), Some("/a.png"));
+        assert_eq!(attribute("<link href=\"\">", "href"), Some(""));
+    }
+
+    #[test]
+    fn unknown_entities_are_kept() {
+        assert_eq!(decode_entities("a &foo; b & c &#xZZ;"), "a &foo; b & c &#xZZ;");
+        assert_eq!(decode_entities("&lt;b&gt; &quot;x&quot; &ntilde;"), "<b> \"x\" ñ");
+        assert_eq!(decode_entities("&amp;amp;"), "&amp;");
+    }
+
+    #[test]
+    fn link_types_from_the_url() {
+        let cases = [
+            ("https://drive.google.com/file/d/abc", Some("drive")),
+            ("www.dropbox.com/s/abc", Some("drive")),
+            ("https://www.figma.com/file/abc", Some("design")),
+            ("https://github.com/usuario/repo/issues/3", Some("issues")),
+            ("https://gitlab.example.com/g/p/-/merge_requests/1", Some("issues")),
+            ("https://empresa.atlassian.net/browse/PROJ-1", Some("issues")),
+            ("https://jira.empresa.com/browse/PROJ-1", Some("issues")),
+            ("https://linear.app/equipo/issue/ABC-1", Some("issues")),
+            ("https://docs.rs/serde", Some("documentation")),
+            ("https://example.com/wiki/Inicio", Some("documentation")),
+            ("github.com/usuario/repo", Some("repository")),
+            ("https://servidor.local/repos/app.git", Some("repository")),
+            // `/browse` fuera de Jira no es una incidencia
+            ("https://example.com/browse/libros", None),
+            ("https://github.com/usuario", None),
+            ("mailto:alguien@example.com", None),
+        ];
+
+        for (url, expected) in cases {
+            assert_eq!(infer_link_type(url), expected, "{}", url);
+        }
+    }
+
+    #[test]
+    fn host_without_scheme() {
+        assert_eq!(url_host("WWW.Example.com/ruta"), Some("www.example.com".to_string()));
+        assert_eq!(
+            parse_url("example.com/a").map(|url| url.to_string()),
+            Some("https://example.com/a".to_string())
+        );
+    }
+}
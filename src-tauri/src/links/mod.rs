@@ -0,0 +1,415 @@
+//! Comprobación de enlaces rotos.
+//!
+//! Revisa en segundo plano los `project_links` y los campos de URL de los
+//! proyectos (`documentation_url`, `ai_documentation_url`, `drive_link`) y
+//! guarda por cada URL el último código HTTP, la hora de la comprobación y el
+//! destino final si hay redirecciones.
+//!
+//! | Tipo de enlace        | Comprobación                                          |
+//! |-----------------------|-------------------------------------------------------|
+//! | `http://`, `https://` | `HEAD` (y `GET` si falla), siguiendo redirecciones    |
+//! | `file://` o ruta      | Existencia del archivo o carpeta                      |
+//! | Otros (`mailto:`...)  | No se comprueban                                      |
+//!
+//! Se consideran rotos los errores de red, los 404/410 y los 5xx. Los 401/403
+//! y 429 no: suelen ser documentos privados o límites de peticiones.
+//!
+//! Al crear un enlace también se completan sus metadatos (`metadata`): el tipo
+//! según la URL y, si no tiene título, el `<title>` de la página (descargarla es
+//! opcional: sin permiso solo se usa lo ya guardado). El favicon de cada dominio
+//! se guarda en la base de datos y se sirve por `gestor://favicon/{id}`.
+//!
+//! Los enlaces también pueden importarse desde marcadores del navegador (`bookmarks`).
+
+mod bookmarks;
+mod metadata;
+
+pub use bookmarks::{import_bookmarks, parse_bookmarks};
+pub use metadata::{prepare_link, CachedMetadata, Favicon};
+
+use reqwest::blocking::Client;
+use reqwest::redirect::Policy;
+use reqwest::Url;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::db::Database;
+use crate::models::project::LinkCheckSummary;
+
+/// Evento con el resumen de cada comprobación en segundo plano
+pub const LINKS_CHECKED_EVENT: &str = "links-checked";
+
+/// Tiempo máximo por petición
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Horas tras las que una URL se vuelve a comprobar en segundo plano
+pub const RECHECK_INTERVAL_HOURS: i64 = 24;
+
+const MAX_REDIRECTS: usize = 10;
+
+/// Peticiones simultáneas
+const WORKERS: usize = 4;
+
+/// Resultado de comprobar una URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkCheckResult {
+    pub status_code: Option<u16>,
+    pub final_url: Option<String>,
+    pub error: Option<String>,
+    pub is_broken: bool,
+}
+
+impl LinkCheckResult {
+    fn broken(error: String) -> Self {
+        LinkCheckResult {
+            status_code: None,
+            final_url: None,
+            error: Some(error),
+            is_broken: true,
+        }
+    }
+}
+
+pub struct LinkChecker {
+    client: Client,
+}
+
+/// Cliente HTTP bloqueante que sigue hasta `MAX_REDIRECTS` redirecciones
+fn http_client(timeout: Duration) -> Result<Client, String> {
+    Client::builder()
+        .timeout(timeout)
+        .redirect(Policy::limited(MAX_REDIRECTS))
+        .user_agent(concat!("gestor-proyectos/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| format!("Error creando cliente HTTP: {}", e))
+}
+
+impl LinkChecker {
+    pub fn new(timeout: Duration) -> Result<Self, String> {
+        Ok(LinkChecker {
+            client: http_client(timeout)?,
+        })
+    }
+
+    /// Comprueba una URL. `None` si su esquema no se comprueba (`mailto:`, `ssh:`...).
+    pub fn check(&self, url: &str) -> Option<LinkCheckResult> {
+        let url = url.trim();
+
+        // Rutas locales guardadas sin `file://` (incluidas las de Windows, `C:\...`)
+        if Path::new(url).is_absolute() {
+            return Some(check_path(Path::new(url)));
+        }
+
+        // Enlaces guardados sin esquema, ej: `github.com/usuario/repo`
+        let parsed = match Url::parse(url).or_else(|_| Url::parse(&format!("https://{}", url))) {
+            Ok(parsed) => parsed,
+            Err(e) => return Some(LinkCheckResult::broken(format!("URL inválida: {}", e))),
+        };
+
+        match parsed.scheme() {
+            "http" | "https" => Some(self.check_http(parsed)),
+            "file" => Some(match parsed.to_file_path() {
+                Ok(path) => check_path(&path),
+                Err(()) => LinkCheckResult::broken("Ruta de archivo inválida".to_string()),
+            }),
+            _ => None,
+        }
+    }
+
+    fn check_http(&self, url: Url) -> LinkCheckResult {
+        // Algunos servidores no admiten HEAD o responden distinto: reintentar con GET
+        let response = match self.client.head(url.clone()).send() {
+            Ok(response) if response.status().is_success() || response.status().is_redirection() => {
+                Ok(response)
+            }
+            _ => self.client.get(url.clone()).send(),
+        };
+
+        match response {
+            Ok(response) => {
+                let status = response.status().as_u16();
+                let final_url = (response.url() != &url).then(|| response.url().to_string());
+                LinkCheckResult {
+                    status_code: Some(status),
+                    final_url,
+                    error: None,
+                    is_broken: is_broken_status(status),
+                }
+            }
+            Err(e) if e.is_timeout() => LinkCheckResult::broken("Tiempo de espera agotado".to_string()),
+            Err(e) if e.is_redirect() => LinkCheckResult::broken("Demasiadas redirecciones".to_string()),
+            Err(e) => LinkCheckResult::broken(format!("Error de conexión: {}", e)),
+        }
+    }
+}
+
+fn check_path(path: &Path) -> LinkCheckResult {
+    if path.exists() {
+        LinkCheckResult {
+            status_code: None,
+            final_url: None,
+            error: None,
+            is_broken: false,
+        }
+    } else {
+        LinkCheckResult::broken(format!("No existe: {}", path.display()))
+    }
+}
+
+fn is_broken_status(status: u16) -> bool {
+    status == 404 || status == 410 || status >= 500
+}
+
+/// Evita lanzar varias comprobaciones en segundo plano a la vez
+static CHECK_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Lanza en segundo plano la comprobación de las URLs nuevas o no comprobadas
+/// en las últimas `RECHECK_INTERVAL_HOURS` horas y emite `LINKS_CHECKED_EVENT`.
+pub fn start_link_check(app: AppHandle) {
+    if CHECK_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let spawned = thread::Builder::new()
+        .name("link-checker".to_string())
+        .spawn(move || {
+            let db = app.state::<Database>();
+            if let Err(e) = db.prune_link_checks() {
+                println!("🔗 [LINKS] Error limpiando comprobaciones: {}", e);
+            }
+
+            let result = check_links(&db, None, RECHECK_INTERVAL_HOURS);
+            CHECK_RUNNING.store(false, Ordering::SeqCst);
+
+            match result {
+                Ok(summary) if summary.checked > 0 => {
+                    println!(
+                        "🔗 [LINKS] {} enlaces comprobados, {} rotos",
+                        summary.checked, summary.broken
+                    );
+                    if let Err(e) = app.emit(LINKS_CHECKED_EVENT, summary) {
+                        println!("🔗 [LINKS] Error al emitir evento: {}", e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => println!("🔗 [LINKS] Error comprobando enlaces: {}", e),
+            }
+        });
+
+    if let Err(e) = spawned {
+        CHECK_RUNNING.store(false, Ordering::SeqCst);
+        println!("🔗 [LINKS] No se pudo iniciar la comprobación de enlaces: {}", e);
+    }
+}
+
+/// Comprueba las URLs de un proyecto (o de todos) no comprobadas en las últimas
+/// `older_than_hours` horas (0 para todas) y guarda los resultados.
+/// Bloquea hasta terminar: no llamar desde el hilo de la interfaz.
+pub fn check_links(
+    db: &Database,
+    project_id: Option<i64>,
+    older_than_hours: i64,
+) -> Result<LinkCheckSummary, String> {
+    let urls = db
+        .link_urls_to_check(project_id, older_than_hours)
+        .map_err(|e| format!("Error leyendo enlaces: {}", e))?;
+    if urls.is_empty() {
+        return Ok(LinkCheckSummary::default());
+    }
+
+    let checker = LinkChecker::new(REQUEST_TIMEOUT)?;
+    // Los hilos sacan las URLs del final: invertir para empezar por las más antiguas
+    let pending = Mutex::new(urls.into_iter().rev().collect::<Vec<_>>());
+    let summary = Mutex::new(LinkCheckSummary::default());
+
+    thread::scope(|scope| {
+        for _ in 0..WORKERS {
+            scope.spawn(|| loop {
+                let Some(url) = pending.lock().unwrap().pop() else {
+                    break;
+                };
+                let Some(result) = checker.check(&url) else {
+                    continue;
+                };
+
+                if let Err(e) = db.save_link_check(&url, &result) {
+                    println!("🔗 [LINKS] Error guardando comprobación de '{}': {}", url, e);
+                    continue;
+                }
+
+                let mut summary = summary.lock().unwrap();
+                summary.checked += 1;
+                if result.is_broken {
+                    summary.broken += 1;
+                }
+            });
+        }
+    });
+
+    Ok(summary.into_inner().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{create_project, memory_database};
+    use crate::models::project::CreateLinkDTO;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Servidor HTTP mínimo en 127.0.0.1 que responde según el método y la ruta.
+    /// Devuelve la URL base (`http://127.0.0.1:puerto`).
+    fn stub_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                thread::spawn(move || {
+                    let mut buf = [0u8; 2048];
+                    let read = stream.read(&mut buf).unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..read]).to_string();
+                    let mut parts = request.split_whitespace();
+                    let method = parts.next().unwrap_or_default();
+                    let path = parts.next().unwrap_or_default();
+
+                    let (status, headers) = match (method, path) {
+                        (_, "/ok") => ("200 OK", ""),
+                        (_, "/gone") => ("410 Gone", ""),
+                        (_, "/error") => ("503 Service Unavailable", ""),
+                        (_, "/private") => ("403 Forbidden", ""),
+                        (_, "/redirect") => ("301 Moved Permanently", "Location: /ok\r\n"),
+                        ("HEAD", "/no-head") => ("405 Method Not Allowed", ""),
+                        ("GET", "/no-head") => ("200 OK", ""),
+                        (_, "/slow") => {
+                            thread::sleep(Duration::from_secs(2));
+                            ("200 OK", "")
+                        }
+                        _ => ("404 Not Found", ""),
+                    };
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 {}\r\n{}Content-Length: 0\r\nConnection: close\r\n\r\n",
+                        status, headers
+                    );
+                });
+            }
+        });
+
+        base
+    }
+
+    fn checker() -> LinkChecker {
+        LinkChecker::new(Duration::from_millis(500)).unwrap()
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir().join(format!("gestor-links-{}-{}", name, nanos))
+    }
+
+    #[test]
+    fn http_status_codes() {
+        let base = stub_server();
+        let checker = checker();
+
+        let ok = checker.check(&format!("{}/ok", base)).unwrap();
+        assert_eq!(ok.status_code, Some(200));
+        assert_eq!(ok.final_url, None);
+        assert!(!ok.is_broken);
+
+        for (path, status) in [("/missing", 404), ("/gone", 410), ("/error", 503)] {
+            let result = checker.check(&format!("{}{}", base, path)).unwrap();
+            assert_eq!(result.status_code, Some(status), "{}", path);
+            assert!(result.is_broken, "{}", path);
+        }
+
+        // Privado: existe aunque no se pueda ver
+        let private = checker.check(&format!("{}/private", base)).unwrap();
+        assert_eq!(private.status_code, Some(403));
+        assert!(!private.is_broken);
+    }
+
+    #[test]
+    fn head_not_allowed_falls_back_to_get() {
+        let base = stub_server();
+        let result = checker().check(&format!("{}/no-head", base)).unwrap();
+        assert_eq!(result.status_code, Some(200));
+        assert!(!result.is_broken);
+    }
+
+    #[test]
+    fn redirects_keep_the_final_url() {
+        let base = stub_server();
+        let result = checker().check(&format!("{}/redirect", base)).unwrap();
+        assert_eq!(result.status_code, Some(200));
+        assert_eq!(result.final_url, Some(format!("{}/ok", base)));
+        assert!(!result.is_broken);
+    }
+
+    #[test]
+    fn timeout_is_broken() {
+        let base = stub_server();
+        let result = checker().check(&format!("{}/slow", base)).unwrap();
+        assert_eq!(result.status_code, None);
+        assert_eq!(result.error.as_deref(), Some("Tiempo de espera agotado"));
+        assert!(result.is_broken);
+    }
+
+    #[test]
+    fn file_links_check_the_path() {
+        let checker = checker();
+        let dir = temp_path("file");
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("notas.txt");
+        std::fs::write(&file, "x").unwrap();
+
+        let url = Url::from_file_path(&file).unwrap().to_string();
+        assert!(!checker.check(&url).unwrap().is_broken);
+        assert!(!checker.check(file.to_str().unwrap()).unwrap().is_broken);
+
+        let missing = Url::from_file_path(dir.join("no-existe.txt")).unwrap().to_string();
+        let result = checker.check(&missing).unwrap();
+        assert!(result.is_broken);
+        assert!(result.error.unwrap().starts_with("No existe"));
+
+        // Esquemas que no se comprueban
+        assert_eq!(checker.check("mailto:alguien@example.com"), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn check_links_saves_results() {
+        let base = stub_server();
+        let db = memory_database();
+        let project = create_project(&db, "Proyecto", Some(format!("{}/missing", base)));
+        db.create_link(CreateLinkDTO {
+            project_id: project.id,
+            link_type: "other".to_string(),
+            title: "Redirección".to_string(),
+            url: format!("{}/redirect", base),
+        })
+        .unwrap();
+
+        let summary = check_links(&db, Some(project.id), 0).unwrap();
+        assert_eq!(summary.checked, 2);
+        assert_eq!(summary.broken, 1);
+
+        let link = db.get_project_links(project.id).unwrap().remove(0);
+        let check = link.check.unwrap();
+        assert_eq!(check.status_code, Some(200));
+        assert_eq!(check.final_url, Some(format!("{}/ok", base)));
+
+        // Recién comprobadas: no vuelven a salir hasta pasado el intervalo
+        assert!(db.link_urls_to_check(Some(project.id), RECHECK_INTERVAL_HOURS).unwrap().is_empty());
+    }
+}
@@ -8,6 +8,24 @@ pub struct ProjectLink {
     pub title: String,
     pub url: String,
     pub created_at: String,
+    /// El dominio tiene favicon guardado (se sirve por `gestor://favicon/{id}`)
+    #[serde(default)]
+    pub has_favicon: bool,
+    /// Última comprobación de la URL (`None` si aún no se ha comprobado)
+    pub check: Option<LinkCheck>,
+}
+
+/// Resultado de la última comprobación de una URL. Se guarda por URL, así que
+/// los enlaces repetidos en varios proyectos comparten el resultado.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkCheck {
+    /// Código HTTP final (`None` en enlaces `file://` o si no hubo respuesta)
+    pub status_code: Option<u16>,
+    /// Destino final si la URL redirige a otra
+    pub final_url: Option<String>,
+    pub error: Option<String>,
+    pub is_broken: bool,
+    pub checked_at: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,7 +38,12 @@ pub struct Project {
     pub ai_documentation_url: Option<String>,
     pub drive_link: Option<String>,
     pub notes: Option<String>,
-    pub image_data: Option<String>,
+    pub image_data: Option<String>, // Solo se incluye al pedir un proyecto individual
+    #[serde(default)]
+    pub has_image: bool,
+    /// Miniatura de la imagen; se incluye también en los listados
+    #[serde(default)]
+    pub thumbnail_data: Option<String>,
     pub links: Option<Vec<ProjectLink>>,
     pub created_at: String,
     pub updated_at: String,
@@ -39,12 +62,164 @@ pub struct Project {
 pub struct ProjectActivity {
     pub id: i64,
     pub project_id: i64,
-    pub activity_type: String,
+    pub project_name: Option<String>, // None si el proyecto ya no existe
+    pub activity_type: String, // Ver `ActivityType`
     pub description: Option<String>,
     pub duration_seconds: Option<i64>,
+    pub details: Option<serde_json::Value>, // Datos estructurados según el tipo
     pub created_at: String,
 }
 
+/// Tipos de actividad que se registran en `project_activity`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityType {
+    Opened,
+    TimeTracked,
+    ProjectCreated,
+    ProjectUpdated,
+    ProjectDeleted,
+    StatusChanged,
+    Pinned,
+    Unpinned,
+    LinkAdded,
+    LinkUpdated,
+    LinkRemoved,
+    LinksImported,
+    AttachmentAdded,
+    AttachmentRemoved,
+    AttachmentRestored,
+    AttachmentExported,
+    JournalEntryAdded,
+    JournalEntryUpdated,
+    JournalEntryRemoved,
+    TodoCreated,
+    TodoUpdated,
+    TodoCompleted,
+    TodoReopened,
+    TodoMoved,
+    TodoDeleted,
+    BoardUpdated,
+    MilestoneCreated,
+    MilestoneUpdated,
+    MilestoneCompleted,
+    MilestoneDeleted,
+    ReminderCreated,
+    ReminderSnoozed,
+    ReminderDismissed,
+    ReminderDeleted,
+    RelationAdded,
+    RelationRemoved,
+    GitCommit,
+    GitPush,
+    GitPull,
+    GitStaged,
+    GitUnstaged,
+    GitDiscarded,
+    GitBranchCreated,
+    GitBranchSwitched,
+    GitBranchRenamed,
+    GitBranchDeleted,
+    GitStashSaved,
+    GitStashApplied,
+    GitStashPopped,
+    GitStashDropped,
+    BackupCreated,
+    BackupSynced,
+}
+
+impl ActivityType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActivityType::Opened => "opened",
+            ActivityType::TimeTracked => "time_tracked",
+            ActivityType::ProjectCreated => "project_created",
+            ActivityType::ProjectUpdated => "project_updated",
+            ActivityType::ProjectDeleted => "project_deleted",
+            ActivityType::StatusChanged => "status_changed",
+            ActivityType::Pinned => "pinned",
+            ActivityType::Unpinned => "unpinned",
+            ActivityType::LinkAdded => "link_added",
+            ActivityType::LinkUpdated => "link_updated",
+            ActivityType::LinkRemoved => "link_removed",
+            ActivityType::LinksImported => "links_imported",
+            ActivityType::AttachmentAdded => "attachment_added",
+            ActivityType::AttachmentRemoved => "attachment_removed",
+            ActivityType::AttachmentRestored => "attachment_restored",
+            ActivityType::AttachmentExported => "attachment_exported",
+            ActivityType::JournalEntryAdded => "journal_entry_added",
+            ActivityType::JournalEntryUpdated => "journal_entry_updated",
+            ActivityType::JournalEntryRemoved => "journal_entry_removed",
+            ActivityType::TodoCreated => "todo_created",
+            ActivityType::TodoUpdated => "todo_updated",
+            ActivityType::TodoCompleted => "todo_completed",
+            ActivityType::TodoReopened => "todo_reopened",
+            ActivityType::TodoMoved => "todo_moved",
+            ActivityType::TodoDeleted => "todo_deleted",
+            ActivityType::BoardUpdated => "board_updated",
+            ActivityType::MilestoneCreated => "milestone_created",
+            ActivityType::MilestoneUpdated => "milestone_updated",
+            ActivityType::MilestoneCompleted => "milestone_completed",
+            ActivityType::MilestoneDeleted => "milestone_deleted",
+            ActivityType::ReminderCreated => "reminder_created",
+            ActivityType::ReminderSnoozed => "reminder_snoozed",
+            ActivityType::ReminderDismissed => "reminder_dismissed",
+            ActivityType::ReminderDeleted => "reminder_deleted",
+            ActivityType::RelationAdded => "relation_added",
+            ActivityType::RelationRemoved => "relation_removed",
+            ActivityType::GitCommit => "git_commit",
+            ActivityType::GitPush => "git_push",
+            ActivityType::GitPull => "git_pull",
+            ActivityType::GitStaged => "git_staged",
+            ActivityType::GitUnstaged => "git_unstaged",
+            ActivityType::GitDiscarded => "git_discarded",
+            ActivityType::GitBranchCreated => "git_branch_created",
+            ActivityType::GitBranchSwitched => "git_branch_switched",
+            ActivityType::GitBranchRenamed => "git_branch_renamed",
+            ActivityType::GitBranchDeleted => "git_branch_deleted",
+            ActivityType::GitStashSaved => "git_stash_saved",
+            ActivityType::GitStashApplied => "git_stash_applied",
+            ActivityType::GitStashPopped => "git_stash_popped",
+            ActivityType::GitStashDropped => "git_stash_dropped",
+            ActivityType::BackupCreated => "backup_created",
+            ActivityType::BackupSynced => "backup_synced",
+        }
+    }
+}
+
+/// Filtro de `get_activities`. Todos los criterios son opcionales y se combinan con AND.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ActivityFilter {
+    pub project_id: Option<i64>,
+    /// Tipos aceptados (ej: ["todo_completed", "git_commit"])
+    pub activity_types: Vec<String>,
+    /// Fechas en formato SQLite ("YYYY-MM-DD" o "YYYY-MM-DD HH:MM:SS", UTC)
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Resultado de aplicar la política de retención
+#[derive(Debug, Serialize, Default)]
+pub struct ActivityPruneResult {
+    /// Filas detalladas eliminadas
+    pub deleted_rows: i64,
+    /// Días (por proyecto) agregados en los resúmenes
+    pub summarized_days: i64,
+}
+
+/// Actividad de un proyecto agregada por día y tipo
+#[derive(Debug, Serialize)]
+pub struct DailyActivitySummary {
+    pub project_id: i64,
+    pub day: String,
+    pub activity_type: String,
+    pub count: i64,
+    pub total_duration_seconds: i64,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CreateActivityDTO {
     pub project_id: i64,
@@ -77,7 +252,11 @@ pub struct CreateProjectDTO {
 #[derive(Debug, Deserialize)]
 pub struct CreateLinkDTO {
     pub project_id: i64,
+    /// Vacío para deducirlo de la URL
+    #[serde(default)]
     pub link_type: String,
+    /// Vacío para usar el título de la página
+    #[serde(default)]
     pub title: String,
     pub url: String,
 }
@@ -102,14 +281,91 @@ pub struct UpdateProjectDTO {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+/// Metadatos de un adjunto; el contenido se sirve por el protocolo `gestor://attachment/{id}`
 pub struct ProjectAttachment {
     pub id: i64,
     pub project_id: i64,
     pub filename: String,
-    pub file_data: String,
     pub file_size: i64,
     pub mime_type: String,
     pub created_at: String,
+    /// SHA-256 del contenido (vacío en adjuntos anteriores a la detección de duplicados)
+    pub content_hash: Option<String>,
+    /// Estado de la extracción de texto para la búsqueda
+    pub text_status: AttachmentTextStatus,
+    /// Motivo si la extracción falló o el formato no tiene texto
+    pub text_error: Option<String>,
+    /// Nombre estable del documento; todas sus versiones lo comparten
+    pub logical_name: String,
+    pub version: i64,
+    /// Número de versiones del documento
+    pub version_count: i64,
+}
+
+/// Estado de la extracción de texto de un adjunto (se procesa en segundo plano)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AttachmentTextStatus {
+    Pending,
+    Processing,
+    Indexed,
+    Unsupported,
+    Failed,
+}
+
+impl AttachmentTextStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttachmentTextStatus::Pending => "pending",
+            AttachmentTextStatus::Processing => "processing",
+            AttachmentTextStatus::Indexed => "indexed",
+            AttachmentTextStatus::Unsupported => "unsupported",
+            AttachmentTextStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(AttachmentTextStatus::Pending),
+            "processing" => Some(AttachmentTextStatus::Processing),
+            "indexed" => Some(AttachmentTextStatus::Indexed),
+            "unsupported" => Some(AttachmentTextStatus::Unsupported),
+            "failed" => Some(AttachmentTextStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// Adjunto cuyo texto coincide con una búsqueda
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttachmentSearchHit {
+    pub attachment: ProjectAttachment,
+    pub project_name: String,
+    /// Fragmento del texto con las coincidencias entre `<mark>` y `</mark>` (el resto escapado)
+    pub snippet: String,
+}
+
+/// Resultado de añadir varios archivos o carpetas como adjuntos
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AttachmentImportResult {
+    pub added: Vec<ProjectAttachment>,
+    pub skipped: Vec<SkippedAttachment>,
+}
+
+/// Archivo que no se pudo adjuntar y el motivo
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SkippedAttachment {
+    pub path: String,
+    pub reason: String,
+}
+
+/// Contenido de un adjunto en base64 (solo para el protocolo y la exportación, no viaja por IPC)
+#[derive(Debug, Clone)]
+pub struct AttachmentContent {
+    pub project_id: i64,
+    pub filename: String,
+    pub mime_type: String,
+    pub file_data: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -156,16 +412,477 @@ pub struct ProjectTodo {
     pub is_completed: bool,
     pub created_at: String,
     pub completed_at: Option<String>,
+    pub milestone_id: Option<i64>,
+    // Tablero: columna y posición dentro de ella
+    pub column_id: Option<i64>,
+    pub position: i64,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct CreateTodoDTO {
     pub project_id: i64,
     pub content: String,
+    #[serde(default)]
+    pub milestone_id: Option<i64>,
+    /// Columna inicial; por defecto la primera columna del tablero
+    #[serde(default)]
+    pub column_id: Option<i64>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateTodoDTO {
     pub content: Option<String>,
+    /// Completar mueve el TODO a la columna de terminados (y descompletar lo saca de ella)
     pub is_completed: Option<bool>,
+    /// Mover al final de otra columna
+    #[serde(default)]
+    pub column_id: Option<i64>,
+}
+
+// ==================== TODO BOARD ====================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardColumn {
+    pub id: i64,
+    pub project_id: i64,
+    pub name: String,
+    pub position: i64,
+    /// Los TODOs de esta columna se consideran completados
+    pub is_done_column: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateBoardColumnDTO {
+    pub project_id: i64,
+    pub name: String,
+    #[serde(default)]
+    pub is_done_column: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateBoardColumnDTO {
+    pub name: Option<String>,
+    pub is_done_column: Option<bool>,
+}
+
+/// Columnas del tablero y sus TODOs ordenados por columna y posición
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectBoard {
+    pub project_id: i64,
+    pub columns: Vec<BoardColumn>,
+    pub todos: Vec<ProjectTodo>,
+}
+
+// ==================== PROJECT MILESTONES ====================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectMilestone {
+    pub id: i64,
+    pub project_id: i64,
+    pub title: String,
+    pub description: Option<String>,
+    pub due_date: Option<String>, // YYYY-MM-DD
+    pub is_completed: bool,
+    pub completed_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    // Calculados a partir de los TODOs vinculados
+    pub total_todos: i64,
+    pub completed_todos: i64,
+    pub progress: f64, // 0.0 - 1.0
+    pub is_overdue: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateMilestoneDTO {
+    pub project_id: i64,
+    pub title: String,
+    pub description: Option<String>,
+    pub due_date: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateMilestoneDTO {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub due_date: Option<String>, // Cadena vacía para quitar la fecha
+    pub is_completed: Option<bool>,
+}
+
+/// Hito pendiente con el proyecto al que pertenece (vista de planificación)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpcomingMilestone {
+    #[serde(flatten)]
+    pub milestone: ProjectMilestone,
+    pub project_name: String,
+    /// Negativo si ya venció
+    pub days_until_due: i64,
+}
+
+// ==================== REMINDERS ====================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReminderStatus {
+    Pending,
+    Fired,
+    Dismissed,
+}
+
+impl ReminderStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReminderStatus::Pending => "pending",
+            ReminderStatus::Fired => "fired",
+            ReminderStatus::Dismissed => "dismissed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(ReminderStatus::Pending),
+            "fired" => Some(ReminderStatus::Fired),
+            "dismissed" => Some(ReminderStatus::Dismissed),
+            _ => None,
+        }
+    }
+}
+
+/// Recordatorio asociado a un proyecto y, opcionalmente, a uno de sus TODOs o hitos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reminder {
+    pub id: i64,
+    pub project_id: i64,
+    pub project_name: String,
+    pub todo_id: Option<i64>,
+    pub milestone_id: Option<i64>,
+    pub message: String,
+    pub remind_at: String, // UTC, "YYYY-MM-DD HH:MM:SS" (igual que CURRENT_TIMESTAMP)
+    pub status: ReminderStatus,
+    pub fired_at: Option<String>,
+    pub snooze_count: i64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateReminderDTO {
+    pub project_id: i64,
+    pub todo_id: Option<i64>,
+    pub milestone_id: Option<i64>,
+    pub message: String,
+    /// RFC 3339 ("2025-03-07T09:00:00+01:00") o fecha local "YYYY-MM-DD HH:MM"
+    pub remind_at: String,
+}
+
+/// Carga del evento `reminder-fired`
+#[derive(Debug, Clone, Serialize)]
+pub struct ReminderFiredEvent {
+    pub reminder: Reminder,
+    /// `true` si venció mientras la aplicación estaba cerrada
+    pub missed: bool,
+}
+
+// ==================== SMART VIEWS ====================
+
+/// Periodo relativo a la fecha actual
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RelativePeriod {
+    Today,
+    ThisWeek,
+    ThisMonth,
+    ThisYear,
+}
+
+/// Definición de la consulta de una vista guardada. Todos los criterios se combinan con AND.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SmartViewFilter {
+    /// Texto libre o consulta estructurada (mismo lenguaje que `search_projects`)
+    pub text: Option<String>,
+    /// Estados aceptados: activo, pausado, completado, archivado
+    pub statuses: Vec<String>,
+    pub pinned: Option<bool>,
+    /// Abierto en los últimos N días
+    pub opened_within_days: Option<i64>,
+    /// Nunca abierto o sin abrir en los últimos N días
+    pub not_opened_in_days: Option<i64>,
+    /// Estado cambiado dentro del periodo (ej: "completados este mes")
+    pub status_changed_in: Option<RelativePeriod>,
+    /// Con cambios sin commitear en `local_path` (se evalúa con git)
+    pub has_uncommitted_changes: Option<bool>,
+}
+
+/// Campo de ordenación de una vista
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SmartViewSortField {
+    Name,
+    CreatedAt,
+    UpdatedAt,
+    LastOpenedAt,
+    OpenedCount,
+    StatusChangedAt,
+    PinnedOrder,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmartView {
+    pub id: i64,
+    pub name: String,
+    pub filter: SmartViewFilter,
+    pub sort_by: SmartViewSortField,
+    pub sort_direction: SortDirection,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateSmartViewDTO {
+    pub name: String,
+    pub filter: SmartViewFilter,
+    pub sort_by: SmartViewSortField,
+    pub sort_direction: SortDirection,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSmartViewDTO {
+    pub name: Option<String>,
+    pub filter: Option<SmartViewFilter>,
+    pub sort_by: Option<SmartViewSortField>,
+    pub sort_direction: Option<SortDirection>,
+}
+
+// ==================== PROJECT RELATIONS ====================
+
+/// Tipo de relación entre dos proyectos. `depends-on` y `fork-of` son dirigidas
+/// (origen → destino); `related-to` es simétrica.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RelationType {
+    DependsOn,
+    RelatedTo,
+    ForkOf,
+}
+
+impl RelationType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelationType::DependsOn => "depends-on",
+            RelationType::RelatedTo => "related-to",
+            RelationType::ForkOf => "fork-of",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "depends-on" => Some(RelationType::DependsOn),
+            "related-to" => Some(RelationType::RelatedTo),
+            "fork-of" => Some(RelationType::ForkOf),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectRelation {
+    pub id: i64,
+    pub source_project_id: i64,
+    pub target_project_id: i64,
+    pub relation_type: RelationType,
+    pub created_at: String,
+}
+
+/// Sentido de la relación visto desde el proyecto consultado
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RelationDirection {
+    /// El proyecto consultado es el origen (ej: "depende de X")
+    Outgoing,
+    /// El proyecto consultado es el destino (ej: "X depende de este")
+    Incoming,
+}
+
+/// Relación junto con los datos básicos del otro proyecto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelatedProject {
+    pub relation: ProjectRelation,
+    pub direction: RelationDirection,
+    pub project_id: i64,
+    pub project_name: String,
+    pub project_status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRelationDTO {
+    pub source_project_id: i64,
+    pub target_project_id: i64,
+    pub relation_type: RelationType,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectGraphNode {
+    pub id: i64,
+    pub name: String,
+    pub status: Option<String>,
+    /// Distancia (en relaciones) al proyecto consultado
+    pub depth: i64,
+}
+
+/// Vecindario de un proyecto: nodos alcanzables hasta cierta profundidad y las relaciones entre ellos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectGraph {
+    pub root_id: i64,
+    pub nodes: Vec<ProjectGraphNode>,
+    pub edges: Vec<ProjectRelation>,
+}
+
+// ==================== PROJECT HEALTH ====================
+
+/// Datos de la base de datos que intervienen en la salud de un proyecto
+#[derive(Debug, Clone, Default)]
+pub struct HealthSignals {
+    /// Días desde la última apertura (o desde la creación si nunca se abrió)
+    pub days_since_open: i64,
+    pub never_opened: bool,
+    /// TODOs pendientes de hitos vencidos
+    pub overdue_todos: i64,
+    /// Días desde el último backup registrado en la actividad
+    pub days_since_backup: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthLevel {
+    Good,
+    Warning,
+    Critical,
+}
+
+/// Factor que resta puntos a la salud del proyecto
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthFactor {
+    /// Identificador estable (ej: "uncommitted_changes")
+    pub key: String,
+    pub description: String,
+    pub penalty: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectHealth {
+    pub project_id: i64,
+    pub project_name: String,
+    /// 0-100, mayor es mejor
+    pub score: i64,
+    pub level: HealthLevel,
+    pub factors: Vec<HealthFactor>,
+}
+
+// ==================== STALE PROJECTS ====================
+
+/// Origen de un enlace comprobado: un `project_links` o un campo del proyecto
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkSource {
+    Link,
+    Documentation,
+    AiDocumentation,
+    Drive,
+}
+
+impl LinkSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LinkSource::Link => "link",
+            LinkSource::Documentation => "documentation",
+            LinkSource::AiDocumentation => "ai_documentation",
+            LinkSource::Drive => "drive",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "link" => Some(LinkSource::Link),
+            "documentation" => Some(LinkSource::Documentation),
+            "ai_documentation" => Some(LinkSource::AiDocumentation),
+            "drive" => Some(LinkSource::Drive),
+            _ => None,
+        }
+    }
+}
+
+/// Enlace roto de un proyecto, para el informe de todos los proyectos
+#[derive(Debug, Clone, Serialize)]
+pub struct BrokenLink {
+    pub project_id: i64,
+    pub project_name: String,
+    pub source: LinkSource,
+    /// Id en `project_links` (solo si `source` es `link`)
+    pub link_id: Option<i64>,
+    pub title: String,
+    pub url: String,
+    pub check: LinkCheck,
+}
+
+/// Resumen de una pasada del comprobador de enlaces
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LinkCheckSummary {
+    pub checked: usize,
+    pub broken: usize,
+}
+
+/// Marcador leído de una exportación del navegador
+#[derive(Debug, Clone, Serialize)]
+pub struct Bookmark {
+    pub title: String,
+    pub url: String,
+}
+
+/// Carpeta de marcadores con sus marcadores directos (sin los de subcarpetas)
+#[derive(Debug, Clone, Serialize)]
+pub struct BookmarkFolder {
+    /// Nombres desde la raíz, ej: ["Barra de marcadores", "Clientes", "Acme"]. Vacío para la raíz.
+    pub path: Vec<String>,
+    pub bookmarks: Vec<Bookmark>,
+}
+
+/// Carpeta elegida para importar en un proyecto
+#[derive(Debug, Clone, Deserialize)]
+pub struct BookmarkMapping {
+    pub folder: Vec<String>,
+    pub project_id: i64,
+    /// Importar también los marcadores de sus subcarpetas
+    #[serde(default)]
+    pub include_subfolders: bool,
+}
+
+/// Resultado de importar marcadores
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BookmarkImportSummary {
+    pub imported: usize,
+    /// Ya existían en el proyecto (o estaban repetidos en la importación)
+    pub duplicates: usize,
+    /// URLs que no se importan (`javascript:`, `place:`...)
+    pub skipped: usize,
+}
+
+/// Proyecto 'activo' que cumple los criterios de la política de archivado
+#[derive(Debug, Clone, Serialize)]
+pub struct StaleProject {
+    pub project_id: i64,
+    pub project_name: String,
+    pub local_path: String,
+    /// Días desde la última apertura (o desde la creación si nunca se abrió)
+    pub days_since_open: i64,
+    pub never_opened: bool,
+    /// Días desde el último commit (`None` si no es un repositorio git o no tiene commits)
+    pub days_since_commit: Option<i64>,
 }
@@ -0,0 +1,461 @@
+//! Protocolo `gestor://` para servir adjuntos e imágenes directamente al webview.
+//!
+//! Evita pasar los archivos en base64 por IPC: el frontend usa las URLs en
+//! `<img>`, `<embed>` o `fetch` y el backend responde con los bytes ya decodificados.
+//!
+//! | URL                              | Contenido                                  |
+//! |----------------------------------|--------------------------------------------|
+//! | `gestor://attachment/{id}`       | Archivo adjunto de `project_attachments`   |
+//! | `gestor://project-image/{id}`    | Imagen completa del proyecto               |
+//! | `gestor://favicon/{id}`          | Favicon del dominio de un `project_links`  |
+//!
+//! Tauri expone el esquema como `gestor://localhost/...` en Linux/macOS y como
+//! `http://gestor.localhost/...` en Windows; se aceptan todas las formas.
+//! Las respuestas admiten peticiones `Range` (visores de PDF, audio y vídeo)
+//! y revalidación con `ETag` / `If-None-Match`. El contenido decodificado de los
+//! últimos recursos se guarda en memoria: un visor pide el mismo archivo en
+//! muchos trozos y cada uno solo consulta en la base de datos si ha cambiado.
+//!
+//! Los adjuntos y favicons vienen de fuera (un SVG puede llevar scripts): todas
+//! las respuestas prohíben ejecutar nada y solo los orígenes de la app pueden
+//! leerlas con `fetch`.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use tauri::http::{header, HeaderValue, Method, Request, Response, StatusCode};
+
+use crate::db::Database;
+
+/// Nombre del esquema registrado en Tauri
+pub const SCHEME: &str = "gestor";
+
+/// Orígenes de la app que pueden leer las respuestas con `fetch`
+/// (`tauri://localhost` en Linux/macOS, `http(s)://tauri.localhost` en Windows)
+const ALLOWED_ORIGINS: [&str; 3] = ["tauri://localhost", "http://tauri.localhost", "https://tauri.localhost"];
+
+/// Servidor de desarrollo (`devUrl` de `tauri.conf.json`), solo en compilaciones de depuración
+const DEV_ORIGIN: &str = "http://localhost:1420";
+
+/// Sin scripts, objetos ni recursos externos; los SVG conservan sus estilos
+const CONTENT_SECURITY_POLICY: &str = "default-src 'none'; style-src 'unsafe-inline'";
+
+/// Tamaño máximo del contenido decodificado en memoria
+const CACHE_MAX_BYTES: usize = 64 * 1024 * 1024;
+
+/// Recurso servido por el protocolo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Resource {
+    Attachment(i64),
+    ProjectImage(i64),
+    Favicon(i64),
+}
+
+/// Contenido ya decodificado de un recurso
+struct Content {
+    bytes: Vec<u8>,
+    mime_type: String,
+    filename: Option<String>,
+}
+
+/// Contenido guardado junto a la marca de versión con la que se leyó
+struct CachedContent {
+    resource: Resource,
+    stamp: String,
+    content: Arc<Content>,
+}
+
+/// Últimos recursos servidos, del más antiguo al más reciente
+static CACHE: Mutex<Vec<CachedContent>> = Mutex::new(Vec::new());
+
+/// Atiende una petición del protocolo. Nunca falla: los errores se devuelven como respuestas HTTP.
+pub fn handle(db: &Database, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let mut response = respond(db, request);
+    secure(&mut response, request);
+    response
+}
+
+fn respond(db: &Database, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let method = request.method();
+    if method != Method::GET && method != Method::HEAD {
+        return error_response(StatusCode::METHOD_NOT_ALLOWED, "Método no soportado");
+    }
+
+    let Some(resource) = parse_resource(request.uri()) else {
+        return error_response(StatusCode::BAD_REQUEST, "URL de recurso inválida");
+    };
+
+    let stamp = match stamp(db, resource) {
+        Ok(Some(stamp)) => stamp,
+        Ok(None) => return error_response(StatusCode::NOT_FOUND, "Recurso no encontrado"),
+        Err(e) => {
+            println!("🌐 [PROTOCOL] Error sirviendo {:?}: {}", resource, e);
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, &e);
+        }
+    };
+
+    let etag = etag(resource, &stamp);
+    let not_modified = request
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*"));
+
+    let mut builder = Response::builder()
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::ETAG, &etag)
+        // Los recursos pueden cambiar con el mismo id (ej: nueva imagen): revalidar siempre
+        .header(header::CACHE_CONTROL, "no-cache")
+        .header(header::ACCESS_CONTROL_EXPOSE_HEADERS, "Content-Range, Content-Length, ETag");
+
+    // La revalidación no necesita leer el contenido
+    if not_modified {
+        return builder
+            .status(StatusCode::NOT_MODIFIED)
+            .body(Vec::new())
+            .unwrap_or_else(|_| error_response(StatusCode::INTERNAL_SERVER_ERROR, "Respuesta inválida"));
+    }
+
+    let content = match cached_content(db, resource, stamp) {
+        Ok(Some(content)) => content,
+        Ok(None) => return error_response(StatusCode::NOT_FOUND, "Recurso no encontrado"),
+        Err(e) => {
+            println!("🌐 [PROTOCOL] Error sirviendo {:?}: {}", resource, e);
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, &e);
+        }
+    };
+
+    builder = builder.header(header::CONTENT_TYPE, &content.mime_type);
+    if let Some(filename) = &content.filename {
+        builder = builder.header(header::CONTENT_DISPOSITION, content_disposition(filename));
+    }
+
+    let total = content.bytes.len();
+    let range_header = request.headers().get(header::RANGE).and_then(|value| value.to_str().ok());
+
+    let (status, body) = match range_header.map(|value| parse_range(value, total)) {
+        None | Some(Ok(None)) => (StatusCode::OK, &content.bytes[..]),
+        Some(Ok(Some((start, end)))) => {
+            builder = builder.header(
+                header::CONTENT_RANGE,
+                format!("bytes {}-{}/{}", start, end, total),
+            );
+            (StatusCode::PARTIAL_CONTENT, &content.bytes[start..=end])
+        }
+        Some(Err(())) => {
+            return builder
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header(header::CONTENT_RANGE, format!("bytes */{}", total))
+                .body(Vec::new())
+                .unwrap_or_else(|_| {
+                    error_response(StatusCode::INTERNAL_SERVER_ERROR, "Respuesta inválida")
+                });
+        }
+    };
+
+    let body = if method == Method::HEAD { Vec::new() } else { body.to_vec() };
+    builder
+        .status(status)
+        .header(header::CONTENT_LENGTH, body.len())
+        .body(body)
+        .unwrap_or_else(|_| error_response(StatusCode::INTERNAL_SERVER_ERROR, "Respuesta inválida"))
+}
+
+/// Cabeceras de seguridad de todas las respuestas, incluidas las de error
+fn secure(response: &mut Response<Vec<u8>>, request: &Request<Vec<u8>>) {
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_SECURITY_POLICY,
+        HeaderValue::from_static(CONTENT_SECURITY_POLICY),
+    );
+    headers.insert(header::X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+    headers.insert(header::VARY, HeaderValue::from_static("Origin"));
+
+    let origin = request.headers().get(header::ORIGIN);
+    if let Some(origin) = origin.filter(|origin| is_allowed_origin(origin.to_str().unwrap_or(""))) {
+        headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin.clone());
+    }
+}
+
+fn is_allowed_origin(origin: &str) -> bool {
+    ALLOWED_ORIGINS.contains(&origin) || (cfg!(debug_assertions) && origin == DEV_ORIGIN)
+}
+
+/// Marca que cambia cuando cambia el contenido, sin leerlo; `None` si el recurso no existe
+fn stamp(db: &Database, resource: Resource) -> Result<Option<String>, String> {
+    match resource {
+        Resource::Attachment(id) => db.get_attachment_stamp(id),
+        Resource::ProjectImage(id) => db.get_project_image_stamp(id),
+        Resource::Favicon(link_id) => db.get_link_favicon_stamp(link_id),
+    }
+    .map_err(|e| format!("Error leyendo {:?}: {}", resource, e))
+}
+
+/// Contenido de la caché si sigue en la misma versión; si no, se lee y se decodifica
+fn cached_content(db: &Database, resource: Resource, stamp: String) -> Result<Option<Arc<Content>>, String> {
+    {
+        let mut cache = CACHE.lock().unwrap();
+        if let Some(index) = cache
+            .iter()
+            .position(|entry| entry.resource == resource && entry.stamp == stamp)
+        {
+            // Pasa a ser el más reciente
+            let entry = cache.remove(index);
+            let content = Arc::clone(&entry.content);
+            cache.push(entry);
+            return Ok(Some(content));
+        }
+    }
+
+    let Some(content) = load(db, resource)? else {
+        return Ok(None);
+    };
+    let content = Arc::new(content);
+
+    if content.bytes.len() <= CACHE_MAX_BYTES {
+        let mut cache = CACHE.lock().unwrap();
+        cache.retain(|entry| entry.resource != resource);
+        cache.push(CachedContent {
+            resource,
+            stamp,
+            content: Arc::clone(&content),
+        });
+
+        let mut total: usize = cache.iter().map(|entry| entry.content.bytes.len()).sum();
+        while total > CACHE_MAX_BYTES {
+            total -= cache.remove(0).content.bytes.len();
+        }
+    }
+
+    Ok(Some(content))
+}
+
+/// Interpreta `gestor://{tipo}/{id}`, `gestor://localhost/{tipo}/{id}` o
+/// `http://gestor.localhost/{tipo}/{id}` (la ruta puede venir codificada, ej: `attachment%2F3`)
+fn parse_resource(uri: &tauri::http::Uri) -> Option<Resource> {
+    let path = percent_decode(uri.path());
+    let mut segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    match uri.host() {
+        Some(host) if host != "localhost" && host != "gestor.localhost" => segments.insert(0, host),
+        _ => {}
+    }
+
+    match segments.as_slice() {
+        [kind, id] => {
+            let id = id.parse().ok()?;
+            match *kind {
+                "attachment" => Some(Resource::Attachment(id)),
+                "project-image" => Some(Resource::ProjectImage(id)),
+                "favicon" => Some(Resource::Favicon(id)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn load(db: &Database, resource: Resource) -> Result<Option<Content>, String> {
+    match resource {
+        Resource::Attachment(id) => {
+            let Some(attachment) = db
+                .get_attachment_content(id)
+                .map_err(|e| format!("Error leyendo adjunto: {}", e))?
+            else {
+                return Ok(None);
+            };
+
+            let bytes = STANDARD
+                .decode(attachment.file_data.trim())
+                .map_err(|_| "El adjunto no está codificado en base64 válido".to_string())?;
+
+            Ok(Some(Content {
+                bytes,
+                mime_type: attachment.mime_type,
+                filename: Some(attachment.filename),
+            }))
+        }
+        Resource::ProjectImage(id) => {
+            let Some(image_data) = db
+                .get_project_image(id)
+                .map_err(|e| format!("Error leyendo imagen: {}", e))?
+            else {
+                return Ok(None);
+            };
+
+            let (mime_type, bytes) = decode_data_url(&image_data)?;
+            Ok(Some(Content {
+                bytes,
+                mime_type,
+                filename: None,
+            }))
+        }
+        Resource::Favicon(link_id) => {
+            let favicon = db
+                .get_link_favicon(link_id)
+                .map_err(|e| format!("Error leyendo favicon: {}", e))?;
+
+            Ok(favicon.map(|favicon| Content {
+                bytes: favicon.data,
+                mime_type: favicon.mime_type,
+                filename: None,
+            }))
+        }
+    }
+}
+
+/// Decodifica una data URL en base64 (`data:<mime>;base64,<datos>`)
+fn decode_data_url(data: &str) -> Result<(String, Vec<u8>), String> {
+    let (header, payload) = data
+        .trim()
+        .strip_prefix("data:")
+        .and_then(|rest| rest.split_once(','))
+        .ok_or_else(|| "Data URL inválida".to_string())?;
+
+    let mime_type = header
+        .strip_suffix(";base64")
+        .ok_or_else(|| "La data URL no está en base64".to_string())?;
+    let bytes = STANDARD
+        .decode(payload.trim())
+        .map_err(|_| "La data URL no contiene base64 válido".to_string())?;
+
+    let mime_type = if mime_type.is_empty() {
+        "application/octet-stream"
+    } else {
+        mime_type
+    };
+    Ok((mime_type.to_string(), bytes))
+}
+
+/// Rango pedido en la cabecera `Range` (solo se admite un rango de bytes).
+/// `Ok(None)` si la cabecera no es de bytes o trae varios rangos: se sirve el recurso completo.
+/// `Err(())` si el rango queda fuera del contenido.
+fn parse_range(value: &str, total: usize) -> Result<Option<(usize, usize)>, ()> {
+    let Some(spec) = value.trim().strip_prefix("bytes=") else {
+        return Ok(None);
+    };
+    if spec.contains(',') {
+        return Ok(None);
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return Ok(None);
+    };
+
+    let (start, end) = match (start.trim(), end.trim()) {
+        // Sufijo: los últimos N bytes
+        ("", suffix) => {
+            let suffix: usize = suffix.parse().map_err(|_| ())?;
+            if suffix == 0 || total == 0 {
+                return Err(());
+            }
+            (total.saturating_sub(suffix), total - 1)
+        }
+        (start, "") => (start.parse().map_err(|_| ())?, total.saturating_sub(1)),
+        (start, end) => {
+            let start: usize = start.parse().map_err(|_| ())?;
+            let end: usize = end.parse().map_err(|_| ())?;
+            (start, end.min(total.saturating_sub(1)))
+        }
+    };
+
+    if start >= total || start > end {
+        return Err(());
+    }
+    Ok(Some((start, end)))
+}
+
+/// ETag débil derivado de la marca de versión (suficiente para revalidar la caché del webview)
+fn etag(resource: Resource, stamp: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    (resource, stamp).hash(&mut hasher);
+    format!("W/\"{:x}\"", hasher.finish())
+}
+
+/// `inline` para que el webview muestre PDFs e imágenes; el nombre va codificado según RFC 5987
+fn content_disposition(filename: &str) -> String {
+    let encoded: String = filename
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+    format!("inline; filename*=UTF-8''{}", encoded)
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(byte) = hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Vec<u8>> {
+    let mut response = Response::new(message.as_bytes().to_vec());
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/plain; charset=utf-8"),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closed_ranges_are_clamped_to_the_content() {
+        assert_eq!(parse_range("bytes=0-99", 1000), Ok(Some((0, 99))));
+        assert_eq!(parse_range(" bytes= 10 - 20 ", 1000), Ok(Some((10, 20))));
+        assert_eq!(parse_range("bytes=900-5000", 1000), Ok(Some((900, 999))));
+        assert_eq!(parse_range("bytes=999-999", 1000), Ok(Some((999, 999))));
+    }
+
+    #[test]
+    fn open_ended_ranges_reach_the_end() {
+        assert_eq!(parse_range("bytes=0-", 1000), Ok(Some((0, 999))));
+        assert_eq!(parse_range("bytes=500-", 1000), Ok(Some((500, 999))));
+    }
+
+    #[test]
+    fn suffix_ranges_take_the_last_bytes() {
+        assert_eq!(parse_range("bytes=-100", 1000), Ok(Some((900, 999))));
+        // Un sufijo mayor que el contenido lo devuelve entero
+        assert_eq!(parse_range("bytes=-5000", 1000), Ok(Some((0, 999))));
+        assert_eq!(parse_range("bytes=-0", 1000), Err(()));
+        assert_eq!(parse_range("bytes=-10", 0), Err(()));
+    }
+
+    #[test]
+    fn unsatisfiable_ranges() {
+        assert_eq!(parse_range("bytes=1000-", 1000), Err(()));
+        assert_eq!(parse_range("bytes=1000-1200", 1000), Err(()));
+        assert_eq!(parse_range("bytes=0-", 0), Err(()));
+        assert_eq!(parse_range("bytes=50-10", 1000), Err(()));
+        assert_eq!(parse_range("bytes=x-10", 1000), Err(()));
+    }
+
+    #[test]
+    fn other_headers_serve_the_whole_content() {
+        assert_eq!(parse_range("items=0-10", 1000), Ok(None));
+        assert_eq!(parse_range("bytes=0-10,20-30", 1000), Ok(None));
+        assert_eq!(parse_range("bytes=10", 1000), Ok(None));
+    }
+}
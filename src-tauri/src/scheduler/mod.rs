@@ -0,0 +1,150 @@
+//! Planificador en segundo plano.
+//!
+//! Revisa periódicamente los recordatorios vencidos mientras la aplicación está
+//! abierta. Cada recordatorio se emite como evento `reminder-fired` para el
+//! frontend y como notificación nativa del sistema. La primera revisión se hace
+//! al arrancar, así que los recordatorios vencidos con la app cerrada se disparan
+//! en ese momento (marcados como `missed`).
+//!
+//! También aplica una vez al día la política de retención de la actividad y,
+//! en modo `auto`, la de archivado de proyectos inactivos (evento `projects-archived`),
+//! y lanza la comprobación de enlaces rotos (evento `links-checked`).
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::archive;
+use crate::links;
+use crate::config::{ArchivePolicyMode, ConfigManager};
+use crate::db::Database;
+use crate::models::project::{Reminder, ReminderFiredEvent};
+
+/// Evento que recibe el frontend por cada recordatorio disparado
+pub const REMINDER_FIRED_EVENT: &str = "reminder-fired";
+
+/// Evento con los proyectos archivados automáticamente por inactividad
+pub const PROJECTS_ARCHIVED_EVENT: &str = "projects-archived";
+
+/// Intervalo entre revisiones
+const TICK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Intervalo entre tareas de mantenimiento (retención y archivado)
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Arranca el planificador en un hilo propio (vive mientras viva la aplicación)
+pub fn start(app: AppHandle) {
+    // Misma representación que `remind_at` para poder comparar como texto
+    let started_at = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+    thread::Builder::new()
+        .name("scheduler".to_string())
+        .spawn(move || {
+            let mut last_maintenance: Option<Instant> = None;
+            loop {
+                fire_due_reminders(&app, &started_at);
+
+                if last_maintenance.is_none_or(|at| at.elapsed() >= MAINTENANCE_INTERVAL) {
+                    prune_activities(&app);
+                    archive_stale_projects(&app);
+                    links::start_link_check(app.clone());
+                    last_maintenance = Some(Instant::now());
+                }
+
+                thread::sleep(TICK_INTERVAL);
+            }
+        })
+        .expect("No se pudo iniciar el planificador");
+}
+
+fn fire_due_reminders(app: &AppHandle, started_at: &str) {
+    let db = app.state::<Database>();
+
+    let reminders = match db.take_due_reminders() {
+        Ok(reminders) => reminders,
+        Err(e) => {
+            println!("⏰ [SCHEDULER] Error al leer recordatorios: {}", e);
+            return;
+        }
+    };
+
+    for reminder in reminders {
+        let missed = reminder.remind_at.as_str() < started_at;
+        println!(
+            "⏰ [SCHEDULER] Recordatorio {} para '{}'{}",
+            reminder.id,
+            reminder.project_name,
+            if missed { " (atrasado)" } else { "" }
+        );
+
+        notify(app, &reminder, missed);
+
+        if let Err(e) = app.emit(REMINDER_FIRED_EVENT, ReminderFiredEvent { reminder, missed }) {
+            println!("⏰ [SCHEDULER] Error al emitir evento: {}", e);
+        }
+    }
+}
+
+fn notify(app: &AppHandle, reminder: &Reminder, missed: bool) {
+    let title = if missed {
+        format!("⏰ {} (pendiente)", reminder.project_name)
+    } else {
+        format!("⏰ {}", reminder.project_name)
+    };
+
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(title)
+        .body(&reminder.message)
+        .show()
+    {
+        println!("⏰ [SCHEDULER] Error al mostrar notificación: {}", e);
+    }
+}
+
+fn prune_activities(app: &AppHandle) {
+    let retention_days = match app.state::<ConfigManager>().get_config() {
+        Ok(config) => config.advanced.analytics_retention_days,
+        Err(e) => {
+            println!("🧹 [SCHEDULER] Error al leer configuración: {}", e);
+            return;
+        }
+    };
+
+    match app.state::<Database>().prune_activities(retention_days) {
+        Ok(result) if result.deleted_rows > 0 => println!(
+            "🧹 [SCHEDULER] Actividad resumida: {} filas en {} días",
+            result.deleted_rows, result.summarized_days
+        ),
+        Ok(_) => {}
+        Err(e) => println!("🧹 [SCHEDULER] Error aplicando retención: {}", e),
+    }
+}
+
+fn archive_stale_projects(app: &AppHandle) {
+    let policy = match app.state::<ConfigManager>().get_config() {
+        Ok(config) => config.archive_policy,
+        Err(e) => {
+            println!("🗄️ [SCHEDULER] Error al leer configuración: {}", e);
+            return;
+        }
+    };
+    if policy.mode != ArchivePolicyMode::Auto {
+        return;
+    }
+
+    match archive::archive_stale_projects(&app.state::<Database>(), &policy, None) {
+        Ok(archived) if !archived.is_empty() => {
+            println!("🗄️ [SCHEDULER] {} proyectos archivados por inactividad", archived.len());
+            if let Err(e) = app.emit(PROJECTS_ARCHIVED_EVENT, archived) {
+                println!("🗄️ [SCHEDULER] Error al emitir evento: {}", e);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => println!("🗄️ [SCHEDULER] Error archivando proyectos: {}", e),
+    }
+}
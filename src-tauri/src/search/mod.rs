@@ -0,0 +1,609 @@
+//! Lenguaje de consulta estructurado para la búsqueda de proyectos.
+//!
+//! Ejemplo: `status:activo tag:client pinned:yes opened:<30d path:~/work "frase exacta"`
+//!
+//! | Término              | Significado                                               |
+//! |----------------------|-----------------------------------------------------------|
+//! | `palabra`            | Coincide en nombre, descripción, ruta o notas             |
+//! | `"frase exacta"`     | Igual que una palabra, pero con espacios                  |
+//! | `status:a,b`         | Estado es alguno de los indicados                         |
+//! | `tag:x`              | Tiene entradas de diario con la etiqueta `x`              |
+//! | `pinned:yes\|no`     | Marcado (o no) como favorito                              |
+//! | `opened:<30d`        | Abierto en los últimos 30 días (`d`, `w`, `m`, `y`)       |
+//! | `opened:>2w`         | Sin abrir en las últimas 2 semanas (incluye nunca)        |
+//! | `opened:never`       | Nunca abierto                                             |
+//! | `created:` `updated:`| Igual que `opened:` sobre las fechas de creación/edición  |
+//! | `path:~/work`        | Ruta local bajo `~/work` (o que contiene el texto)        |
+//! | `name:texto`         | Nombre contiene el texto                                  |
+//! | `-término`           | Niega cualquier término                                   |
+//!
+//! Una consulta formada solo por palabras sueltas conserva el comportamiento
+//! anterior: se busca el texto completo tal cual. Un `clave:valor` con una clave
+//! que no es un campo (`foo:bar`, URLs) también cuenta como palabra.
+//!
+//! Cambio respecto a la búsqueda anterior: un `-` al inicio de un término lo
+//! niega, así que `-foo` ya no busca el texto literal. Para buscarlo, entre
+//! comillas: `"-foo"`.
+
+use serde::Serialize;
+use std::fmt;
+
+pub const VALID_STATUSES: [&str; 4] = ["activo", "pausado", "completado", "archivado"];
+
+/// Cantidad máxima en las fechas relativas (`<9999y`): más no tiene sentido y
+/// evita desbordes al pasar semanas a días
+pub const MAX_AGE_AMOUNT: i64 = 9_999;
+
+/// Rango de caracteres (no bytes) dentro de la consulta original, fin exclusivo
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AgeUnit {
+    Days,
+    Weeks,
+    Months,
+    Years,
+}
+
+/// Antigüedad relativa a ahora, ej: `30d`
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct RelativeAge {
+    pub amount: i64,
+    pub unit: AgeUnit,
+}
+
+impl RelativeAge {
+    /// Modificador de `datetime('now', ?)` en SQLite
+    pub fn sqlite_modifier(&self) -> String {
+        match self.unit {
+            AgeUnit::Days => format!("-{} days", self.amount),
+            AgeUnit::Weeks => format!("-{} days", self.amount.saturating_mul(7)),
+            AgeUnit::Months => format!("-{} months", self.amount),
+            AgeUnit::Years => format!("-{} years", self.amount),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "kind", content = "age", rename_all = "snake_case")]
+pub enum DateFilter {
+    /// `<30d`: dentro de los últimos N
+    Within(RelativeAge),
+    /// `>30d`: hace más de N
+    OlderThan(RelativeAge),
+    /// `never`
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DateField {
+    Opened,
+    Created,
+    Updated,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum Term {
+    Word(String),
+    Phrase(String),
+    Status(Vec<String>),
+    Tag(String),
+    Pinned(bool),
+    Date(DateField, DateFilter),
+    Path(String),
+    Name(String),
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Clause {
+    pub term: Term,
+    pub negated: bool,
+    pub span: Span,
+}
+
+/// Consulta analizada: todas las cláusulas se combinan con AND
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SearchQuery {
+    pub raw: String,
+    pub clauses: Vec<Clause>,
+}
+
+impl SearchQuery {
+    /// `true` si solo hay palabras sueltas: se mantiene la búsqueda clásica por texto completo
+    pub fn is_plain_text(&self) -> bool {
+        self.clauses
+            .iter()
+            .all(|c| !c.negated && matches!(c.term, Term::Word(_)))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct QueryParseError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl fmt::Display for QueryParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (posición {}-{})",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for QueryParseError {}
+
+/// Error devuelto al frontend por los comandos de búsqueda.
+/// `span` solo está presente en los errores de sintaxis.
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchError {
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+}
+
+impl From<QueryParseError> for SearchError {
+    fn from(e: QueryParseError) -> Self {
+        SearchError {
+            message: e.message,
+            span: Some(e.span),
+        }
+    }
+}
+
+impl From<String> for SearchError {
+    fn from(message: String) -> Self {
+        SearchError { message, span: None }
+    }
+}
+
+fn error(message: impl Into<String>, start: usize, end: usize) -> QueryParseError {
+    QueryParseError {
+        message: message.into(),
+        span: Span { start, end },
+    }
+}
+
+/// Token crudo: texto sin comillas y posición del valor
+struct Token {
+    negated: bool,
+    start: usize,
+    end: usize,
+    /// Clave antes de `:` si la hay
+    key: Option<String>,
+    /// Valor (o el token completo si no hay clave) y su posición de inicio
+    value: String,
+    value_start: usize,
+    quoted: bool,
+}
+
+/// Lee un valor entre comillas a partir de `i` (que apunta a la comilla de apertura)
+fn read_quoted(chars: &[char], i: usize) -> Result<(String, usize), QueryParseError> {
+    let mut j = i + 1;
+    let mut text = String::new();
+    while j < chars.len() && chars[j] != '"' {
+        text.push(chars[j]);
+        j += 1;
+    }
+    if j >= chars.len() {
+        return Err(error("Comillas sin cerrar", i, chars.len()));
+    }
+    Ok((text, j + 1))
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, QueryParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i].is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        let negated = chars[i] == '-' && i + 1 < chars.len() && !chars[i + 1].is_whitespace();
+        if negated {
+            i += 1;
+        }
+
+        // Frase entre comillas
+        if chars[i] == '"' {
+            let (text, next) = read_quoted(&chars, i)?;
+            tokens.push(Token {
+                negated,
+                start,
+                end: next,
+                key: None,
+                value: text,
+                value_start: i + 1,
+                quoted: true,
+            });
+            i = next;
+            continue;
+        }
+
+        // Palabra o `clave:valor` (el valor puede ir entre comillas)
+        let word_start = i;
+        let mut word = String::new();
+        let mut key = None;
+        let mut value_start = word_start;
+        let mut quoted = false;
+
+        while i < chars.len() && !chars[i].is_whitespace() {
+            if chars[i] == ':' && key.is_none() {
+                key = Some(word.clone());
+                word.clear();
+                i += 1;
+                value_start = i;
+                if i < chars.len() && chars[i] == '"' {
+                    let (text, next) = read_quoted(&chars, i)?;
+                    word.push_str(&text);
+                    value_start = i + 1;
+                    quoted = true;
+                    i = next;
+                }
+                continue;
+            }
+            word.push(chars[i]);
+            i += 1;
+        }
+
+        tokens.push(Token {
+            negated,
+            start,
+            end: i,
+            key,
+            value: word,
+            value_start,
+            quoted,
+        });
+    }
+
+    Ok(tokens)
+}
+
+fn parse_status(value: &str, value_start: usize) -> Result<Vec<String>, QueryParseError> {
+    let mut statuses = Vec::new();
+    let mut offset = value_start;
+    for part in value.split(',') {
+        let len = part.chars().count();
+        let status = part.trim().to_lowercase();
+        if !VALID_STATUSES.contains(&status.as_str()) {
+            return Err(error(
+                format!(
+                    "Estado desconocido '{}'. Valores válidos: {}",
+                    part,
+                    VALID_STATUSES.join(", ")
+                ),
+                offset,
+                offset + len,
+            ));
+        }
+        statuses.push(status);
+        offset += len + 1;
+    }
+    Ok(statuses)
+}
+
+fn parse_bool(value: &str, value_start: usize) -> Result<bool, QueryParseError> {
+    match value.to_lowercase().as_str() {
+        "yes" | "true" | "si" | "sí" | "1" => Ok(true),
+        "no" | "false" | "0" => Ok(false),
+        _ => Err(error(
+            format!("Valor booleano inválido '{}'. Usa yes o no", value),
+            value_start,
+            value_start + value.chars().count(),
+        )),
+    }
+}
+
+fn parse_date_filter(
+    field: DateField,
+    value: &str,
+    value_start: usize,
+) -> Result<DateFilter, QueryParseError> {
+    let end = value_start + value.chars().count();
+    let lower = value.to_lowercase();
+
+    if lower == "never" || lower == "nunca" {
+        if field != DateField::Opened {
+            return Err(error("'never' solo se puede usar con opened:", value_start, end));
+        }
+        return Ok(DateFilter::Never);
+    }
+
+    let invalid = || {
+        error(
+            format!(
+                "Fecha relativa inválida '{}'. Usa por ejemplo <30d, >2w, <6m o >1y",
+                value
+            ),
+            value_start,
+            end,
+        )
+    };
+
+    let (older, rest) = if let Some(rest) = lower.strip_prefix('>') {
+        (true, rest)
+    } else if let Some(rest) = lower.strip_prefix('<') {
+        (false, rest)
+    } else {
+        (false, lower.as_str())
+    };
+
+    let unit = match rest.chars().last() {
+        Some('d') => AgeUnit::Days,
+        Some('w') => AgeUnit::Weeks,
+        Some('m') => AgeUnit::Months,
+        Some('y') => AgeUnit::Years,
+        _ => return Err(invalid()),
+    };
+    let digits = &rest[..rest.len() - 1];
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let amount = digits
+        .parse::<i64>()
+        .ok()
+        .filter(|amount| *amount <= MAX_AGE_AMOUNT)
+        .ok_or_else(|| {
+            error(
+                format!("Fecha relativa demasiado grande '{}': máximo {}", value, MAX_AGE_AMOUNT),
+                value_start,
+                end,
+            )
+        })?;
+
+    let age = RelativeAge { amount, unit };
+    Ok(if older {
+        DateFilter::OlderThan(age)
+    } else {
+        DateFilter::Within(age)
+    })
+}
+
+/// Analiza una consulta. Los errores incluyen la posición del término inválido.
+pub fn parse(input: &str) -> Result<SearchQuery, QueryParseError> {
+    let mut clauses = Vec::new();
+
+    for token in tokenize(input)? {
+        let span = Span {
+            start: token.start,
+            end: token.end,
+        };
+
+        let term = match &token.key {
+            None if token.quoted => Term::Phrase(token.value.clone()),
+            None => Term::Word(token.value.clone()),
+            Some(key) => {
+                let field = key.to_lowercase();
+                let known = matches!(
+                    field.as_str(),
+                    "status" | "tag" | "pinned" | "opened" | "created" | "updated" | "path" | "name"
+                );
+
+                if !known {
+                    // URLs y textos con ':' se tratan como palabras
+                    clauses.push(Clause {
+                        term: Term::Word(format!("{}:{}", key, token.value)),
+                        negated: token.negated,
+                        span,
+                    });
+                    continue;
+                }
+
+                if token.value.trim().is_empty() {
+                    return Err(error(
+                        format!("Falta el valor para '{}:'", key),
+                        token.start,
+                        token.end,
+                    ));
+                }
+
+                let value = token.value.trim();
+                match field.as_str() {
+                    "status" => Term::Status(parse_status(value, token.value_start)?),
+                    "tag" => Term::Tag(value.to_string()),
+                    "pinned" => Term::Pinned(parse_bool(value, token.value_start)?),
+                    "opened" => Term::Date(
+                        DateField::Opened,
+                        parse_date_filter(DateField::Opened, value, token.value_start)?,
+                    ),
+                    "created" => Term::Date(
+                        DateField::Created,
+                        parse_date_filter(DateField::Created, value, token.value_start)?,
+                    ),
+                    "updated" => Term::Date(
+                        DateField::Updated,
+                        parse_date_filter(DateField::Updated, value, token.value_start)?,
+                    ),
+                    "path" => Term::Path(value.to_string()),
+                    _ => Term::Name(value.to_string()),
+                }
+            }
+        };
+
+        clauses.push(Clause {
+            term,
+            negated: token.negated,
+            span,
+        });
+    }
+
+    Ok(SearchQuery {
+        raw: input.to_string(),
+        clauses,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_err(input: &str) -> QueryParseError {
+        parse(input).expect_err("la consulta debería ser inválida")
+    }
+
+    fn terms(input: &str) -> Vec<(Term, bool)> {
+        parse(input)
+            .unwrap()
+            .clauses
+            .into_iter()
+            .map(|c| (c.term, c.negated))
+            .collect()
+    }
+
+    #[test]
+    fn errors_point_at_the_invalid_value() {
+        let e = parse_err("tag:x status:activo,pausdo");
+        assert_eq!(e.span, Span { start: 20, end: 26 });
+        assert!(e.message.contains("pausdo"));
+        assert_eq!(
+            e.to_string(),
+            format!("{} (posición 20-26)", e.message)
+        );
+    }
+
+    #[test]
+    fn spans_count_characters_not_bytes() {
+        let query = parse("ñandú \"café con leche\"").unwrap();
+        assert_eq!(query.clauses[0].span, Span { start: 0, end: 5 });
+        assert_eq!(query.clauses[1].span, Span { start: 6, end: 22 });
+
+        assert_eq!(parse_err("ñandú pinned:quizá").span, Span { start: 13, end: 18 });
+    }
+
+    #[test]
+    fn dash_negates_any_term() {
+        let query = parse("-status:archivado -borrador").unwrap();
+        assert_eq!(query.clauses[0].span, Span { start: 0, end: 17 });
+        assert_eq!(
+            terms("-status:archivado -borrador"),
+            vec![
+                (Term::Status(vec!["archivado".to_string()]), true),
+                (Term::Word("borrador".to_string()), true),
+            ]
+        );
+        assert!(!query.is_plain_text());
+
+        // Un guion suelto no niega nada
+        assert_eq!(
+            terms("a - b"),
+            vec![
+                (Term::Word("a".to_string()), false),
+                (Term::Word("-".to_string()), false),
+                (Term::Word("b".to_string()), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn quoted_values() {
+        assert_eq!(
+            terms("\"frase exacta\" name:\"mi proyecto\" -\"-foo\""),
+            vec![
+                (Term::Phrase("frase exacta".to_string()), false),
+                (Term::Name("mi proyecto".to_string()), false),
+                (Term::Phrase("-foo".to_string()), true),
+            ]
+        );
+
+        let e = parse_err("path:~/work \"sin cerrar");
+        assert_eq!(e.span, Span { start: 12, end: 23 });
+        assert_eq!(parse_err("name:\"abierta").span, Span { start: 5, end: 13 });
+    }
+
+    #[test]
+    fn invalid_status_and_boolean_values() {
+        assert_eq!(parse_err("status:cerrado").span, Span { start: 7, end: 14 });
+        assert_eq!(parse_err("pinned:quizá").span, Span { start: 7, end: 12 });
+        assert_eq!(parse_err("tag:").span, Span { start: 0, end: 4 });
+        assert_eq!(
+            terms("STATUS:Activo,pausado pinned:no"),
+            vec![
+                (Term::Status(vec!["activo".to_string(), "pausado".to_string()]), false),
+                (Term::Pinned(false), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn date_values() {
+        assert_eq!(
+            terms("opened:<30d created:>2w updated:6m opened:never"),
+            vec![
+                (
+                    Term::Date(
+                        DateField::Opened,
+                        DateFilter::Within(RelativeAge { amount: 30, unit: AgeUnit::Days })
+                    ),
+                    false
+                ),
+                (
+                    Term::Date(
+                        DateField::Created,
+                        DateFilter::OlderThan(RelativeAge { amount: 2, unit: AgeUnit::Weeks })
+                    ),
+                    false
+                ),
+                (
+                    Term::Date(
+                        DateField::Updated,
+                        DateFilter::Within(RelativeAge { amount: 6, unit: AgeUnit::Months })
+                    ),
+                    false
+                ),
+                (Term::Date(DateField::Opened, DateFilter::Never), false),
+            ]
+        );
+
+        assert_eq!(parse_err("opened:<30x").span, Span { start: 7, end: 11 });
+        assert_eq!(parse_err("opened:<d").span, Span { start: 7, end: 9 });
+        assert_eq!(parse_err("created:never").span, Span { start: 8, end: 13 });
+    }
+
+    #[test]
+    fn date_amounts_are_bounded() {
+        let e = parse_err("updated:<99999999999999999w");
+        assert_eq!(e.span, Span { start: 8, end: 27 });
+        assert!(e.message.contains("demasiado grande"));
+        assert!(parse_err("opened:<10000d").message.contains("demasiado grande"));
+        assert!(parse_err("opened:<-3d").message.contains("inválida"));
+
+        let Term::Date(_, DateFilter::Within(age)) = parse("updated:<9999w").unwrap().clauses[0].term.clone() else {
+            panic!("se esperaba una fecha relativa");
+        };
+        assert_eq!(age.sqlite_modifier(), "-69993 days");
+    }
+
+    #[test]
+    fn plain_words_keep_the_full_text_search() {
+        assert!(parse("hola mundo").unwrap().is_plain_text());
+        assert!(parse("").unwrap().is_plain_text());
+
+        // Claves que no son campos: se buscan tal cual
+        let query = parse("foo:bar https://example.com/x").unwrap();
+        assert!(query.is_plain_text());
+        assert_eq!(
+            terms("foo:bar https://example.com/x"),
+            vec![
+                (Term::Word("foo:bar".to_string()), false),
+                (Term::Word("https://example.com/x".to_string()), false),
+            ]
+        );
+
+        assert!(!parse("hola tag:x").unwrap().is_plain_text());
+        assert!(!parse("\"hola mundo\"").unwrap().is_plain_text());
+    }
+}
@@ -1,7 +1,9 @@
-import { Component, createSignal, onMount, Show } from 'solid-js';
-import { Toaster } from 'solid-toast';
+import { Component, createSignal, onCleanup, onMount, Show } from 'solid-js';
+import toast, { Toaster } from 'solid-toast';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import { createProjectStore } from './stores/projectStore';
 import SearchBar from './components/SearchBar';
+import AttachmentSearchResults from './components/AttachmentSearchResults';
 import ProjectList from './components/ProjectList';
 import ProjectFormTabs from './components/ProjectFormTabs';
 import { type ProjectFormData } from './components/ProjectForm';
@@ -11,9 +13,14 @@ import Settings from './components/Settings';
 import WelcomeScreen from './components/WelcomeScreen';
 import { ThemeProvider } from './contexts/ThemeContext';
 import { ShortcutsProvider, useShortcuts } from './contexts/ShortcutsContext';
-import type { Project } from './types/project';
+import type {
+  LinkCheckSummary,
+  Project,
+  ReminderFiredEvent,
+  StaleProject,
+} from './types/project';
 import { confirm } from '@tauri-apps/plugin-dialog';
-import { getConfig } from './services/api';
+import { getConfig, getProjectDependents } from './services/api';
 
 // Componente interno que usa shortcuts
 const AppContent: Component = () => {
@@ -28,9 +35,47 @@ const AppContent: Component = () => {
     null
   );
 
+  let unlistenReminders: UnlistenFn | undefined;
+  let unlistenArchived: UnlistenFn | undefined;
+  let unlistenLinks: UnlistenFn | undefined;
+  onCleanup(() => {
+    unlistenReminders?.();
+    unlistenArchived?.();
+    unlistenLinks?.();
+  });
+
   onMount(async () => {
     store.loadProjects();
 
+    // Recordatorios disparados por el planificador del backend
+    unlistenReminders = await listen<ReminderFiredEvent>(
+      'reminder-fired',
+      (event) => {
+        const { reminder, missed } = event.payload;
+        toast(
+          `⏰ ${reminder.project_name}: ${reminder.message}${missed ? ' (pendiente)' : ''}`,
+          { duration: 10000 }
+        );
+      }
+    );
+
+    // Proyectos archivados automáticamente por inactividad
+    unlistenArchived = await listen<StaleProject[]>(
+      'projects-archived',
+      (event) => {
+        const names = event.payload.map((p) => p.project_name).join(', ');
+        toast(`🗄️ Archivados por inactividad: ${names}`, { duration: 10000 });
+        store.loadProjects();
+      }
+    );
+
+    // Comprobación de enlaces en segundo plano (detalle en Configuración)
+    unlistenLinks = await listen<LinkCheckSummary>('links-checked', (event) => {
+      if (event.payload.broken > 0) {
+        toast(`🔗 ${event.payload.broken} enlaces rotos`, { duration: 6000 });
+      }
+    });
+
     // Verificar si mostrar welcome screen
     try {
       const config = await getConfig();
@@ -115,14 +160,26 @@ const AppContent: Component = () => {
   };
 
   const handleDelete = async (project: Project) => {
-    const confirmed = await confirm(`¿Eliminar proyecto "${project.name}"?`, {
-      title: 'Confirmar eliminación',
-      kind: 'warning',
-    });
+    const dependents = await getProjectDependents(project.id).catch(() => []);
+    const warning =
+      dependents.length > 0
+        ? `\n\n⚠️ Estos proyectos dependen de él: ${dependents
+            .map((d) => d.project_name)
+            .join(', ')}`
+        : '';
+
+    const confirmed = await confirm(
+      `¿Eliminar proyecto "${project.name}"?${warning}`,
+      {
+        title: 'Confirmar eliminación',
+        kind: 'warning',
+      }
+    );
 
     if (confirmed) {
       try {
-        await store.deleteProject(project.id);
+        // El usuario ya ha visto y aceptado el aviso de dependencias
+        await store.deleteProject(project.id, dependents.length > 0);
       } catch {
         alert('Error al eliminar el proyecto');
       }
@@ -218,6 +275,9 @@ const AppContent: Component = () => {
             <SearchBar onSearch={handleSearch} value={searchQuery()} />
           </div>
 
+          {/* Coincidencias en el texto de los adjuntos */}
+          <AttachmentSearchResults query={searchQuery()} />
+
           {/* Error Message */}
           <Show when={store.error()}>
             <div class="mb-4 rounded-lg bg-red-50 p-4 text-red-800 dark:bg-red-900/20 dark:text-red-200">
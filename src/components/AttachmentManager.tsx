@@ -1,11 +1,33 @@
 /* eslint-disable no-undef */
 import { Component, createSignal, For, Show, onMount } from 'solid-js';
+import { open } from '@tauri-apps/plugin-dialog';
+import toast from 'solid-toast';
 import {
   addAttachment,
+  addAttachmentsFromPaths,
+  addAttachmentVersion,
   getAttachments,
+  getAttachmentVersions,
+  rollbackAttachment,
+  exportAttachment,
   deleteAttachment,
+  reindexAttachment,
+  attachmentUrl,
 } from '../services/api';
-import type { ProjectAttachment, CreateAttachmentDTO } from '../types/project';
+import type {
+  ProjectAttachment,
+  CreateAttachmentDTO,
+  AttachmentTextStatus,
+} from '../types/project';
+
+// Estado de la extracción de texto para la búsqueda
+const TEXT_STATUS_LABELS: Record<AttachmentTextStatus, string> = {
+  pending: 'Indexando…',
+  processing: 'Indexando…',
+  indexed: 'Indexado',
+  unsupported: 'Sin texto',
+  failed: 'Error al indexar',
+};
 
 interface AttachmentManagerProps {
   projectId: number;
@@ -18,6 +40,9 @@ const AttachmentManager: Component<AttachmentManagerProps> = (props) => {
   const [isDragging, setIsDragging] = createSignal(false);
   const [previewImage, setPreviewImage] =
     createSignal<ProjectAttachment | null>(null);
+  // Documento cuyo historial de versiones está desplegado
+  const [historyFor, setHistoryFor] = createSignal<number | null>(null);
+  const [versions, setVersions] = createSignal<ProjectAttachment[]>([]);
 
   // Cargar adjuntos al montar el componente
   onMount(async () => {
@@ -34,15 +59,44 @@ const AttachmentManager: Component<AttachmentManagerProps> = (props) => {
     }
   };
 
-  const handleFileInput = async (e: Event) => {
-    const input = e.target as HTMLInputElement;
-    if (!input.files || input.files.length === 0) return;
+  // El backend lee los archivos directamente desde su ruta (sin base64)
+  const addFromDisk = async (directory: boolean) => {
+    const selected = await open({
+      directory,
+      multiple: true,
+      title: directory
+        ? 'Selecciona carpetas para adjuntar'
+        : 'Selecciona archivos para adjuntar',
+    });
+    if (!selected) return;
+    const paths = Array.isArray(selected) ? selected : [selected];
+    if (paths.length === 0) return;
 
-    const file = input.files[0];
-    await uploadFile(file);
+    setIsUploading(true);
+    setError(null);
 
-    // Limpiar input
-    input.value = '';
+    try {
+      const result = await addAttachmentsFromPaths(props.projectId, paths);
+      await loadAttachments();
+
+      if (result.skipped.length > 0) {
+        console.warn('Archivos omitidos:', result.skipped);
+        setError(
+          `${result.added.length} agregados, ${result.skipped.length} omitidos: ` +
+            result.skipped
+              .slice(0, 3)
+              .map((s) => `${s.path.split(/[\\/]/).pop()} (${s.reason})`)
+              .join(', ')
+        );
+        setTimeout(() => setError(null), 6000);
+      }
+    } catch (err) {
+      console.error('Error adding attachments from disk:', err);
+      setError('Error al agregar los archivos');
+      setTimeout(() => setError(null), 3000);
+    } finally {
+      setIsUploading(false);
+    }
   };
 
   const uploadFile = async (file: File) => {
@@ -80,8 +134,97 @@ const AttachmentManager: Component<AttachmentManagerProps> = (props) => {
     }
   };
 
+  const handleReindex = async (id: number) => {
+    try {
+      await reindexAttachment(id);
+      await loadAttachments();
+    } catch (err) {
+      console.error('Error reindexing attachment:', err);
+      setError('Error al reintentar la indexación');
+      setTimeout(() => setError(null), 3000);
+    }
+  };
+
+  const loadVersions = async (attachmentId: number) => {
+    try {
+      setVersions(await getAttachmentVersions(attachmentId));
+      setHistoryFor(attachmentId);
+    } catch (err) {
+      console.error('Error loading attachment versions:', err);
+      setError('Error al cargar el historial de versiones');
+      setTimeout(() => setError(null), 3000);
+    }
+  };
+
+  const toggleHistory = async (attachment: ProjectAttachment) => {
+    if (historyFor() === attachment.id) {
+      setHistoryFor(null);
+      return;
+    }
+    await loadVersions(attachment.id);
+  };
+
+  const uploadVersion = async (attachment: ProjectAttachment) => {
+    const selected = await open({
+      multiple: false,
+      title: `Nueva versión de ${attachment.logical_name}`,
+    });
+    if (!selected || Array.isArray(selected)) return;
+
+    setIsUploading(true);
+    try {
+      const added = await addAttachmentVersion(attachment.id, selected);
+      await loadAttachments();
+      if (historyFor() === attachment.id) await loadVersions(added.id);
+      toast.success(`Versión ${added.version} de ${added.logical_name}`);
+    } catch (err) {
+      console.error('Error adding attachment version:', err);
+      setError('Error al subir la nueva versión');
+      setTimeout(() => setError(null), 3000);
+    } finally {
+      setIsUploading(false);
+    }
+  };
+
+  const handleRollback = async (version: ProjectAttachment) => {
+    if (
+      !confirm(
+        `¿Restaurar la versión ${version.version} de "${version.logical_name}"?`
+      )
+    )
+      return;
+
+    try {
+      const restored = await rollbackAttachment(version.id);
+      await loadAttachments();
+      await loadVersions(restored.id);
+      toast.success(`Versión ${version.version} restaurada`);
+    } catch (err) {
+      console.error('Error restoring attachment version:', err);
+      setError('Error al restaurar la versión');
+      setTimeout(() => setError(null), 3000);
+    }
+  };
+
+  // Escribe el archivo en la carpeta local del proyecto
+  const handleExport = async (attachment: ProjectAttachment) => {
+    try {
+      const path = await exportAttachment(attachment.id).catch(async (err) => {
+        if (!String(err).includes('Ya existe')) throw err;
+        if (!confirm(`${err}\n¿Sobrescribirlo?`)) return null;
+        return await exportAttachment(attachment.id, undefined, true);
+      });
+      if (path) toast.success(`Exportado a ${path}`);
+    } catch (err) {
+      console.error('Error exporting attachment:', err);
+      setError(`Error al exportar: ${err}`);
+      setTimeout(() => setError(null), 4000);
+    }
+  };
+
   const handleDelete = async (id: number, filename: string) => {
-    if (!confirm(`¿Eliminar el archivo "${filename}"?`)) return;
+    if (!confirm(`¿Eliminar el archivo "${filename}" y todas sus versiones?`))
+      return;
 
     try {
       await deleteAttachment(id);
@@ -93,16 +236,12 @@ const AttachmentManager: Component<AttachmentManagerProps> = (props) => {
     }
   };
 
-  const downloadAttachment = (attachment: ProjectAttachment) => {
+  const downloadAttachment = async (attachment: ProjectAttachment) => {
     try {
-      // Convertir base64 a blob
-      const byteCharacters = atob(attachment.file_data);
-      const byteNumbers = new Array(byteCharacters.length);
-      for (let i = 0; i < byteCharacters.length; i++) {
-        byteNumbers[i] = byteCharacters.charCodeAt(i);
-      }
-      const byteArray = new Uint8Array(byteNumbers);
-      const blob = new Blob([byteArray], { type: attachment.mime_type });
+      // El contenido lo sirve el backend por gestor://, sin base64 por IPC
+      const response = await fetch(attachmentUrl(attachment.id));
+      if (!response.ok) throw new Error(`HTTP ${response.status}`);
+      const blob = await response.blob();
 
       // Crear link de descarga
       const url = URL.createObjectURL(blob);
@@ -178,10 +317,9 @@ const AttachmentManager: Component<AttachmentManagerProps> = (props) => {
     await uploadFile(files[0]);
   };
 
-  // Obtener URL de imagen para preview
-  const getImageDataUrl = (attachment: ProjectAttachment): string => {
-    return `data:${attachment.mime_type};base64,${attachment.file_data}`;
-  };
+  // Imágenes y PDFs se pueden mostrar directamente en el webview
+  const isPreviewable = (mimeType: string): boolean =>
+    mimeType.startsWith('image/') || mimeType === 'application/pdf';
 
   return (
     <div class="space-y-4">
@@ -189,18 +327,22 @@ const AttachmentManager: Component<AttachmentManagerProps> = (props) => {
         <h3 class="text-lg font-medium text-gray-900 dark:text-gray-100">
           Archivos Adjuntos
         </h3>
-        <label
-          class="inline-flex cursor-pointer items-center rounded-lg bg-blue-600 px-4 py-2 text-white transition-colors hover:bg-blue-700 disabled:opacity-50"
-          classList={{ 'opacity-50 cursor-not-allowed': isUploading() }}
-        >
-          <span>{isUploading() ? 'Subiendo...' : 'Subir Archivo'}</span>
-          <input
-            type="file"
-            class="hidden"
-            onChange={handleFileInput}
+        <div class="flex items-center gap-2">
+          <button
+            onClick={() => addFromDisk(false)}
+            disabled={isUploading()}
+            class="inline-flex items-center rounded-lg bg-blue-600 px-4 py-2 text-white transition-colors hover:bg-blue-700 disabled:cursor-not-allowed disabled:opacity-50"
+          >
+            {isUploading() ? 'Subiendo...' : 'Subir Archivos'}
+          </button>
+          <button
+            onClick={() => addFromDisk(true)}
             disabled={isUploading()}
-          />
-        </label>
+            class="inline-flex items-center rounded-lg border border-blue-600 px-4 py-2 text-blue-600 transition-colors hover:bg-blue-50 disabled:cursor-not-allowed disabled:opacity-50 dark:text-blue-400 dark:hover:bg-blue-900/20"
+          >
+            Subir Carpeta
+          </button>
+        </div>
       </div>
 
       <Show when={error()}>
@@ -227,7 +369,7 @@ const AttachmentManager: Component<AttachmentManagerProps> = (props) => {
               <>
                 <div class="mb-2 text-4xl">📎</div>
                 <div class="font-medium">Arrastra archivos aquí</div>
-                <div class="mt-1 text-sm">o usa el botón "Subir Archivo"</div>
+                <div class="mt-1 text-sm">o usa los botones "Subir Archivos" / "Subir Carpeta"</div>
                 <div class="mt-2 text-xs">Tamaño máximo: 5MB</div>
               </>
             }
@@ -258,20 +400,104 @@ const AttachmentManager: Component<AttachmentManagerProps> = (props) => {
                   </span>
                   <div class="min-w-0 flex-1">
                     <div class="truncate font-medium text-gray-900 dark:text-gray-100">
-                      {attachment.filename}
+                      {attachment.logical_name}
+                      <Show when={attachment.version_count > 1}>
+                        <span class="ml-2 rounded bg-gray-200 px-1.5 text-xs text-gray-600 dark:bg-gray-700 dark:text-gray-300">
+                          v{attachment.version}
+                        </span>
+                      </Show>
                     </div>
                     <div class="text-sm text-gray-500 dark:text-gray-400">
                       {formatFileSize(attachment.file_size)} •{' '}
-                      {new Date(attachment.created_at).toLocaleDateString()}
+                      {new Date(attachment.created_at).toLocaleDateString()} •{' '}
+                      <span
+                        title={attachment.text_error}
+                        classList={{
+                          'text-red-600 dark:text-red-400':
+                            attachment.text_status === 'failed',
+                        }}
+                      >
+                        {TEXT_STATUS_LABELS[attachment.text_status]}
+                      </span>
+                      <Show when={attachment.text_status === 'failed'}>
+                        {' '}
+                        <button
+                          onClick={() => handleReindex(attachment.id)}
+                          class="text-blue-600 hover:underline dark:text-blue-400"
+                        >
+                          Reintentar
+                        </button>
+                      </Show>
+                      {' • '}
+                      <button
+                        onClick={() => toggleHistory(attachment)}
+                        class="text-blue-600 hover:underline dark:text-blue-400"
+                      >
+                        {attachment.version_count > 1
+                          ? `${attachment.version_count} versiones`
+                          : 'Historial'}
+                      </button>
+                      {' • '}
+                      <button
+                        onClick={() => uploadVersion(attachment)}
+                        disabled={isUploading()}
+                        class="text-blue-600 hover:underline disabled:opacity-50 dark:text-blue-400"
+                      >
+                        Nueva versión
+                      </button>
+                      {' • '}
+                      <button
+                        onClick={() => handleExport(attachment)}
+                        class="text-blue-600 hover:underline dark:text-blue-400"
+                        title="Guardar en la carpeta del proyecto"
+                      >
+                        Exportar
+                      </button>
                     </div>
+                    <Show when={historyFor() === attachment.id}>
+                      <ul class="mt-2 space-y-1 border-l-2 border-gray-200 pl-3 text-sm dark:border-gray-600">
+                        <For each={versions()}>
+                          {(version) => (
+                            <li class="flex items-center gap-2 text-gray-600 dark:text-gray-400">
+                              <span class="font-medium">v{version.version}</span>
+                              <span class="truncate">{version.filename}</span>
+                              <span>
+                                {formatFileSize(version.file_size)} •{' '}
+                                {new Date(version.created_at).toLocaleString()}
+                              </span>
+                              <button
+                                onClick={() => downloadAttachment(version)}
+                                class="text-blue-600 hover:underline dark:text-blue-400"
+                              >
+                                Descargar
+                              </button>
+                              <button
+                                onClick={() => handleExport(version)}
+                                class="text-blue-600 hover:underline dark:text-blue-400"
+                              >
+                                Exportar
+                              </button>
+                              <Show when={version.version !== attachment.version}>
+                                <button
+                                  onClick={() => handleRollback(version)}
+                                  class="text-blue-600 hover:underline dark:text-blue-400"
+                                >
+                                  Restaurar
+                                </button>
+                              </Show>
+                            </li>
+                          )}
+                        </For>
+                      </ul>
+                    </Show>
                   </div>
                 </div>
                 <div class="ml-4 flex items-center space-x-2">
-                  <Show when={attachment.mime_type.startsWith('image/')}>
+                  <Show when={isPreviewable(attachment.mime_type)}>
                     <button
                       onClick={() => setPreviewImage(attachment)}
                       class="rounded p-2 text-purple-600 transition-colors hover:bg-purple-50 dark:text-purple-400 dark:hover:bg-purple-900/20"
-                      title="Ver imagen"
+                      title="Vista previa"
                     >
                       <svg
                         class="h-5 w-5"
@@ -315,7 +541,7 @@ const AttachmentManager: Component<AttachmentManagerProps> = (props) => {
                   </button>
                   <button
                     onClick={() =>
-                      handleDelete(attachment.id, attachment.filename)
+                      handleDelete(attachment.id, attachment.logical_name)
                     }
                     class="rounded p-2 text-red-600 transition-colors hover:bg-red-50 dark:text-red-400 dark:hover:bg-red-900/20"
                     title="Eliminar"
@@ -370,11 +596,22 @@ const AttachmentManager: Component<AttachmentManagerProps> = (props) => {
                 />
               </svg>
             </button>
-            <img
-              src={getImageDataUrl(previewImage()!)}
-              alt={previewImage()!.filename}
-              class="max-h-[90vh] rounded-lg shadow-2xl"
-            />
+            <Show
+              when={previewImage()!.mime_type === 'application/pdf'}
+              fallback={
+                <img
+                  src={attachmentUrl(previewImage()!.id)}
+                  alt={previewImage()!.filename}
+                  class="max-h-[90vh] rounded-lg shadow-2xl"
+                />
+              }
+            >
+              <iframe
+                src={attachmentUrl(previewImage()!.id)}
+                title={previewImage()!.filename}
+                class="h-[80vh] w-[min(56rem,90vw)] rounded-lg bg-white shadow-2xl"
+              />
+            </Show>
             <div class="mt-4 rounded-lg bg-white p-4 dark:bg-gray-800">
               <div class="font-medium text-gray-900 dark:text-gray-100">
                 {previewImage()!.filename}
@@ -0,0 +1,57 @@
+import { Component, For, Show, createResource } from 'solid-js';
+import DOMPurify from 'dompurify';
+import { attachmentUrl, searchAttachments } from '../services/api';
+
+interface AttachmentSearchResultsProps {
+  query: string;
+}
+
+// Adjuntos cuyo texto coincide con la búsqueda actual, con el fragmento encontrado
+const AttachmentSearchResults: Component<AttachmentSearchResultsProps> = (
+  props
+) => {
+  const [hits] = createResource(
+    () => props.query.trim() || null,
+    // Los errores de sintaxis ya los muestra la búsqueda de proyectos
+    (query) => searchAttachments(query).catch(() => [])
+  );
+
+  // El backend escapa el texto; solo se permiten las marcas de coincidencia
+  const snippetHtml = (snippet: string) =>
+    DOMPurify.sanitize(snippet, { ALLOWED_TAGS: ['mark'] });
+
+  return (
+    <Show when={(hits() ?? []).length > 0}>
+      <div class="mb-6 rounded-lg border border-gray-200 bg-white p-4 dark:border-gray-700 dark:bg-gray-800">
+        <h3 class="mb-2 text-sm font-medium text-gray-700 dark:text-gray-300">
+          📎 Coincidencias en archivos adjuntos
+        </h3>
+        <ul class="space-y-2">
+          <For each={hits()}>
+            {(hit) => (
+              <li class="text-sm">
+                <a
+                  href={attachmentUrl(hit.attachment.id)}
+                  target="_blank"
+                  class="font-medium text-blue-600 hover:underline dark:text-blue-400"
+                >
+                  {hit.attachment.filename}
+                </a>
+                <span class="text-gray-500 dark:text-gray-400">
+                  {' '}
+                  · {hit.project_name}
+                </span>
+                <p
+                  class="mt-0.5 whitespace-pre-line text-gray-600 dark:text-gray-400"
+                  innerHTML={snippetHtml(hit.snippet)}
+                />
+              </li>
+            )}
+          </For>
+        </ul>
+      </div>
+    </Show>
+  );
+};
+
+export default AttachmentSearchResults;
@@ -0,0 +1,177 @@
+import { Component, createSignal, For, Show } from 'solid-js';
+import { open } from '@tauri-apps/plugin-dialog';
+import type { Project, BookmarkFolder } from '../types/project';
+import {
+  getAllProjects,
+  importBookmarks,
+  previewBookmarks,
+} from '../services/api';
+
+interface FolderChoice {
+  projectId: number | null; // null: no importar
+  includeSubfolders: boolean;
+}
+
+const folderLabel = (folder: BookmarkFolder) =>
+  folder.path.length > 0 ? folder.path.join(' › ') : 'Raíz';
+
+// Importa carpetas de marcadores del navegador como enlaces de proyectos
+const BookmarkImport: Component = () => {
+  const [path, setPath] = createSignal<string | null>(null);
+  const [folders, setFolders] = createSignal<BookmarkFolder[]>([]);
+  const [projects, setProjects] = createSignal<Project[]>([]);
+  const [choices, setChoices] = createSignal<Record<number, FolderChoice>>({});
+  const [isImporting, setIsImporting] = createSignal(false);
+  const [message, setMessage] = createSignal<string | null>(null);
+  const [error, setError] = createSignal<string | null>(null);
+
+  const handleChooseFile = async () => {
+    const selected = await open({
+      multiple: false,
+      title: 'Selecciona un archivo de marcadores',
+      filters: [
+        { name: 'Marcadores', extensions: ['html', 'htm', 'json'] },
+        // El archivo `Bookmarks` de Chromium no tiene extensión
+        { name: 'Todos los archivos', extensions: ['*'] },
+      ],
+    });
+    if (!selected || Array.isArray(selected)) return;
+
+    setError(null);
+    setMessage(null);
+    try {
+      const [parsed, allProjects] = await Promise.all([
+        previewBookmarks(selected),
+        getAllProjects(),
+      ]);
+      setPath(selected);
+      setFolders(parsed);
+      setProjects(allProjects);
+      setChoices({});
+    } catch (err) {
+      setError(`Error leyendo marcadores: ${err}`);
+    }
+  };
+
+  const updateChoice = (index: number, change: Partial<FolderChoice>) => {
+    const current = choices()[index] ?? {
+      projectId: null,
+      includeSubfolders: false,
+    };
+    setChoices({ ...choices(), [index]: { ...current, ...change } });
+  };
+
+  const mappings = () =>
+    Object.entries(choices())
+      .filter(([, choice]) => choice.projectId !== null)
+      .map(([index, choice]) => ({
+        folder: folders()[Number(index)].path,
+        project_id: choice.projectId as number,
+        include_subfolders: choice.includeSubfolders,
+      }));
+
+  const handleImport = async () => {
+    const file = path();
+    if (!file || mappings().length === 0) return;
+
+    setIsImporting(true);
+    setError(null);
+    try {
+      const summary = await importBookmarks(file, mappings());
+      setMessage(
+        `✅ ${summary.imported} enlaces importados, ${summary.duplicates} ya existían, ${summary.skipped} omitidos`
+      );
+      setChoices({});
+    } catch (err) {
+      setError(`Error importando marcadores: ${err}`);
+    } finally {
+      setIsImporting(false);
+    }
+  };
+
+  return (
+    <div class="space-y-4 rounded-lg bg-gray-50 p-4 dark:bg-gray-800">
+      <h3 class="text-lg font-semibold text-gray-900 dark:text-white">
+        Importar Marcadores
+      </h3>
+      <p class="text-xs text-gray-500 dark:text-gray-400">
+        Acepta el HTML exportado por cualquier navegador o el JSON de marcadores
+        de Firefox y Chromium. Las URLs que el proyecto ya tiene no se repiten.
+      </p>
+      <button
+        type="button"
+        class="rounded-md border border-gray-300 px-4 py-2 text-sm font-medium text-gray-700 hover:bg-gray-100 dark:border-gray-600 dark:text-gray-300 dark:hover:bg-gray-700"
+        onClick={handleChooseFile}
+      >
+        Elegir archivo...
+      </button>
+
+      <Show when={error()}>
+        <p class="text-sm text-red-600 dark:text-red-400">{error()}</p>
+      </Show>
+      <Show when={message()}>
+        <p class="text-sm text-green-700 dark:text-green-400">{message()}</p>
+      </Show>
+
+      <Show when={folders().length > 0}>
+        <ul class="divide-y divide-gray-200 text-sm dark:divide-gray-700">
+          <For each={folders()}>
+            {(folder, index) => (
+              <li class="flex items-center justify-between py-2">
+                <span class="text-gray-700 dark:text-gray-300">
+                  {folderLabel(folder)}
+                  <span class="ml-1 text-xs text-gray-500 dark:text-gray-400">
+                    ({folder.bookmarks.length})
+                  </span>
+                </span>
+                <div class="flex items-center space-x-2">
+                  <label class="flex items-center text-xs text-gray-600 dark:text-gray-400">
+                    <input
+                      type="checkbox"
+                      class="mr-1"
+                      checked={choices()[index()]?.includeSubfolders ?? false}
+                      onChange={(e) =>
+                        updateChoice(index(), {
+                          includeSubfolders: e.currentTarget.checked,
+                        })
+                      }
+                    />
+                    Subcarpetas
+                  </label>
+                  <select
+                    class="rounded border border-gray-300 bg-white px-2 py-1 text-sm text-gray-900 dark:border-gray-600 dark:bg-gray-700 dark:text-white"
+                    value={choices()[index()]?.projectId ?? ''}
+                    onChange={(e) =>
+                      updateChoice(index(), {
+                        projectId: e.currentTarget.value
+                          ? Number(e.currentTarget.value)
+                          : null,
+                      })
+                    }
+                  >
+                    <option value="">No importar</option>
+                    <For each={projects()}>
+                      {(project) => (
+                        <option value={project.id}>{project.name}</option>
+                      )}
+                    </For>
+                  </select>
+                </div>
+              </li>
+            )}
+          </For>
+        </ul>
+        <button
+          type="button"
+          class="rounded-md bg-blue-600 px-4 py-2 text-sm font-medium text-white hover:bg-blue-700 disabled:opacity-50"
+          disabled={isImporting() || mappings().length === 0}
+          onClick={handleImport}
+        >
+          {isImporting() ? 'Importando...' : 'Importar'}
+        </button>
+      </Show>
+    </div>
+  );
+};
+
+export default BookmarkImport;
@@ -15,6 +15,7 @@ vi.mock('../services/api', () => ({
   getProjectTodos: vi.fn(),
   getProjectLinks: vi.fn(),
   getAttachments: vi.fn(),
+  attachmentUrl: vi.fn(),
 }));
 
 import {
@@ -12,6 +12,7 @@ import {
   getProjectTodos,
   getProjectLinks,
   getAttachments,
+  attachmentUrl,
 } from '../services/api';
 
 interface ProjectContextProps {
@@ -304,7 +305,7 @@ export default function ProjectContext(props: ProjectContextProps) {
                             when={attachment.mime_type.startsWith('image/')}
                           >
                             <img
-                              src={attachment.file_data}
+                              src={attachmentUrl(attachment.id)}
                               alt={attachment.filename}
                               class="h-12 w-12 rounded border border-gray-300 object-cover dark:border-gray-600"
                             />
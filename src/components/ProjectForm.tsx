@@ -1,5 +1,6 @@
-import { Component, createSignal, Show } from 'solid-js';
+import { Component, createSignal, onMount, Show } from 'solid-js';
 import type { Project } from '../types/project';
+import { getProjectImage } from '../services/api';
 import MarkdownEditor from './MarkdownEditor';
 
 interface ProjectFormProps {
@@ -50,6 +51,15 @@ const ProjectForm: Component<ProjectFormProps> = (props) => {
   const [imageData, setImageData] = createSignal(initialValues.imageData);
   const [imageError, setImageError] = createSignal('');
 
+  // Los listados no incluyen la imagen: cargarla al editar
+  onMount(async () => {
+    const p = props.project;
+    if (p?.has_image && !p.image_data) {
+      const image = await getProjectImage(p.id);
+      if (image && !imageData()) setImageData(image);
+    }
+  });
+
   const handleImageChange = async (e: Event) => {
     const input = e.currentTarget as HTMLInputElement;
     const file = input.files?.[0];
@@ -4,7 +4,9 @@ import {
   createProjectLink,
   getProjectLinks,
   deleteProjectLink,
+  checkLinks,
   openUrl,
+  faviconUrl,
 } from '../services/api';
 import type { CreateLinkDTO } from '../services/api';
 
@@ -54,6 +56,18 @@ const LINK_TYPES = [
     color:
       'bg-orange-100 dark:bg-orange-900/30 text-orange-800 dark:text-orange-200',
   },
+  {
+    value: 'issues',
+    label: 'Incidencias',
+    icon: '🐞',
+    color: 'bg-pink-100 dark:bg-pink-900/30 text-pink-800 dark:text-pink-200',
+  },
+  {
+    value: 'drive',
+    label: 'Drive',
+    icon: '☁️',
+    color: 'bg-cyan-100 dark:bg-cyan-900/30 text-cyan-800 dark:text-cyan-200',
+  },
   {
     value: 'other',
     label: 'Otro',
@@ -66,9 +80,11 @@ const ProjectLinks: Component<ProjectLinksProps> = (props) => {
   const [links, setLinks] = createSignal<ProjectLink[]>([]);
   const [showAddForm, setShowAddForm] = createSignal(false);
   const [isLoading, setIsLoading] = createSignal(false);
+  const [isChecking, setIsChecking] = createSignal(false);
+  const [fetchMetadata, setFetchMetadata] = createSignal(false);
   const [newLink, setNewLink] = createSignal<Partial<CreateLinkDTO>>({
     project_id: 0, // Se actualizará en onMount
-    link_type: 'repository',
+    link_type: '',
     title: '',
     url: '',
   });
@@ -92,17 +108,18 @@ const ProjectLinks: Component<ProjectLinksProps> = (props) => {
 
   const handleAddLink = async () => {
     const linkData = newLink();
-    if (!linkData.title || !linkData.url) {
-      alert('Por favor completa todos los campos');
+    // El título y el tipo son opcionales: el backend los completa desde la URL
+    if (!linkData.url?.trim()) {
+      alert('Por favor indica la URL del enlace');
       return;
     }
 
     try {
       setIsLoading(true);
-      await createProjectLink(linkData as CreateLinkDTO);
+      await createProjectLink(linkData as CreateLinkDTO, fetchMetadata());
       setNewLink({
         project_id: props.projectId,
-        link_type: 'repository',
+        link_type: '',
         title: '',
         url: '',
       });
@@ -142,8 +159,46 @@ const ProjectLinks: Component<ProjectLinksProps> = (props) => {
     }
   };
 
+  const handleCheckLinks = async () => {
+    try {
+      setIsChecking(true);
+      await checkLinks(props.projectId);
+      await loadLinks();
+    } catch (error) {
+      console.error('Error comprobando enlaces:', error);
+      alert('Error al comprobar los enlaces');
+    } finally {
+      setIsChecking(false);
+    }
+  };
+
+  // Indicador del resultado de la última comprobación
+  const getCheckInfo = (link: ProjectLink) => {
+    const check = link.check;
+    if (!check) {
+      return { color: 'bg-gray-300 dark:bg-gray-600', label: 'Sin comprobar' };
+    }
+    const checkedAt = new Date(check.checked_at).toLocaleString();
+    if (check.is_broken) {
+      return {
+        color: 'bg-red-500',
+        label: `Roto (${check.status_code ?? check.error}) · ${checkedAt}`,
+      };
+    }
+    if (check.final_url) {
+      return {
+        color: 'bg-yellow-400',
+        label: `Redirige a ${check.final_url} · ${checkedAt}`,
+      };
+    }
+    return { color: 'bg-green-500', label: `Funciona · ${checkedAt}` };
+  };
+
   const getLinkTypeInfo = (type: string) => {
-    return LINK_TYPES.find((t) => t.value === type) || LINK_TYPES[6];
+    return (
+      LINK_TYPES.find((t) => t.value === type) ||
+      LINK_TYPES.find((t) => t.value === 'other')!
+    );
   };
 
   return (
@@ -152,12 +207,23 @@ const ProjectLinks: Component<ProjectLinksProps> = (props) => {
         <h3 class="text-lg font-semibold text-gray-900 dark:text-white">
           Enlaces del Proyecto
         </h3>
-        <button
-          onClick={() => setShowAddForm(!showAddForm())}
-          class="rounded-lg bg-blue-600 px-3 py-1.5 text-sm text-white hover:bg-blue-700 focus:outline-none focus:ring-2 focus:ring-blue-500 focus:ring-offset-2 dark:bg-blue-500 dark:hover:bg-blue-600 dark:focus:ring-offset-gray-800"
-        >
-          + Agregar Enlace
-        </button>
+        <div class="flex items-center space-x-2">
+          <Show when={links().length > 0}>
+            <button
+              onClick={handleCheckLinks}
+              disabled={isChecking()}
+              class="rounded-lg border border-gray-300 px-3 py-1.5 text-sm text-gray-700 hover:bg-gray-50 disabled:opacity-50 dark:border-gray-600 dark:text-gray-300 dark:hover:bg-gray-700"
+            >
+              {isChecking() ? 'Comprobando...' : 'Comprobar'}
+            </button>
+          </Show>
+          <button
+            onClick={() => setShowAddForm(!showAddForm())}
+            class="rounded-lg bg-blue-600 px-3 py-1.5 text-sm text-white hover:bg-blue-700 focus:outline-none focus:ring-2 focus:ring-blue-500 focus:ring-offset-2 dark:bg-blue-500 dark:hover:bg-blue-600 dark:focus:ring-offset-gray-800"
+          >
+            + Agregar Enlace
+          </button>
+        </div>
       </div>
 
       <Show when={showAddForm()}>
@@ -171,7 +237,7 @@ const ProjectLinks: Component<ProjectLinksProps> = (props) => {
                 Tipo
               </label>
               <select
-                value={newLink().link_type || 'repository'}
+                value={newLink().link_type || ''}
                 onChange={(e) =>
                   setNewLink({
                     ...newLink(),
@@ -181,6 +247,7 @@ const ProjectLinks: Component<ProjectLinksProps> = (props) => {
                 }
                 class="w-full rounded border border-gray-300 bg-white px-2 py-1 text-sm text-gray-900 dark:border-gray-600 dark:bg-gray-700 dark:text-white"
               >
+                <option value="">✨ Automático (según la URL)</option>
                 <For each={LINK_TYPES}>
                   {(type) => (
                     <option value={type.value}>
@@ -200,7 +267,7 @@ const ProjectLinks: Component<ProjectLinksProps> = (props) => {
                 onInput={(e) =>
                   setNewLink({ ...newLink(), title: e.currentTarget.value })
                 }
-                placeholder="Ej: Repositorio principal"
+                placeholder="Opcional: título de la página o el dominio"
                 class="w-full rounded border border-gray-300 bg-white px-2 py-1 text-sm text-gray-900 dark:border-gray-600 dark:bg-gray-700 dark:text-white"
               />
             </div>
@@ -218,6 +285,15 @@ const ProjectLinks: Component<ProjectLinksProps> = (props) => {
                 class="w-full rounded border border-gray-300 bg-white px-2 py-1 text-sm text-gray-900 dark:border-gray-600 dark:bg-gray-700 dark:text-white"
               />
             </div>
+            <label class="flex items-center text-xs text-gray-600 dark:text-gray-400">
+              <input
+                type="checkbox"
+                class="mr-1"
+                checked={fetchMetadata()}
+                onChange={(e) => setFetchMetadata(e.currentTarget.checked)}
+              />
+              Obtener título e icono de la página (requiere conexión)
+            </label>
             <div class="flex space-x-2">
               <button
                 onClick={handleAddLink}
@@ -253,12 +329,27 @@ const ProjectLinks: Component<ProjectLinksProps> = (props) => {
         <For each={links()}>
           {(link) => {
             const typeInfo = getLinkTypeInfo(link.link_type);
+            const checkInfo = getCheckInfo(link);
             return (
               <div class="flex items-center justify-between rounded-lg border border-gray-200 bg-white p-3 dark:border-gray-700 dark:bg-gray-800">
                 <div class="flex flex-1 items-center space-x-3">
-                  <span class="text-lg">{typeInfo.icon}</span>
+                  <Show
+                    when={link.has_favicon}
+                    fallback={<span class="text-lg">{typeInfo.icon}</span>}
+                  >
+                    <img
+                      src={faviconUrl(link.id)}
+                      alt=""
+                      class="h-5 w-5 flex-shrink-0 rounded-sm"
+                      title={typeInfo.label}
+                    />
+                  </Show>
                   <div class="min-w-0 flex-1">
-                    <p class="truncate text-sm font-medium text-gray-900 dark:text-white">
+                    <p class="flex items-center truncate text-sm font-medium text-gray-900 dark:text-white">
+                      <span
+                        class={`mr-2 inline-block h-2 w-2 flex-shrink-0 rounded-full ${checkInfo.color}`}
+                        title={checkInfo.label}
+                      />
                       {link.title}
                     </p>
                     <p class="truncate text-xs text-gray-500 dark:text-gray-400">
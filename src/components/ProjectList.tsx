@@ -15,6 +15,7 @@ import {
   trackProjectOpen,
   togglePinProject,
   updateProjectStatus,
+  projectImageUrl,
 } from '../services/api';
 import { open } from '@tauri-apps/plugin-dialog';
 import { invoke } from '@tauri-apps/api/core';
@@ -26,6 +27,26 @@ marked.use({
   gfm: true,
 });
 
+// Miniatura del proyecto; sin ella se usa la imagen completa servida por gestor://
+// (los listados no incluyen image_data)
+const ProjectImage: Component<{ project: Project }> = (props) => {
+  const image = () =>
+    props.project.thumbnail_data ||
+    (props.project.has_image ? projectImageUrl(props.project.id) : undefined);
+
+  return (
+    <Show when={image()}>
+      {(src) => (
+        <img
+          src={src()}
+          alt={props.project.name}
+          class="h-16 w-16 flex-shrink-0 rounded-lg border-2 border-gray-300 object-cover dark:border-gray-600"
+        />
+      )}
+    </Show>
+  );
+};
+
 interface ProjectListProps {
   projects: Project[];
   onEdit: (project: Project) => void;
@@ -334,13 +355,7 @@ const ProjectList: Component<ProjectListProps> = (props) => {
                 </div>
 
                 <div class="flex gap-3">
-                  <Show when={project.image_data}>
-                    <img
-                      src={project.image_data}
-                      alt={project.name}
-                      class="h-16 w-16 flex-shrink-0 rounded-lg border-2 border-gray-300 object-cover dark:border-gray-600"
-                    />
-                  </Show>
+                  <ProjectImage project={project} />
                   <div class="flex-1">
                     <h3 class="text-lg font-semibold text-gray-900 dark:text-white">
                       {project.name}
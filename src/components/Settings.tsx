@@ -1,17 +1,27 @@
 import { createSignal, Show, For, onMount } from 'solid-js';
 import type {
   AppConfig,
+  ArchivePolicyConfig,
+  ArchivePolicyMode,
   DetectedPrograms,
   ProgramMode,
   ProgramConfig,
 } from '../types/config';
+import type { BrokenLink, StaleProject } from '../types/project';
 import {
   getConfig,
   updateConfig,
   resetConfig,
   detectPrograms,
   selectBackupFolder,
+  purgeAnalytics,
+  getStaleProjects,
+  archiveStaleProjects,
+  getBrokenLinks,
+  checkLinks,
+  openUrl,
 } from '../services/api';
+import BookmarkImport from './BookmarkImport';
 
 type Tab = 'programs' | 'backup' | 'ui' | 'shortcuts' | 'advanced';
 
@@ -24,6 +34,11 @@ export default function Settings(props: { onClose: () => void }) {
   const [isSaving, setIsSaving] = createSignal(false);
   const [error, setError] = createSignal<string | null>(null);
   const [successMessage, setSuccessMessage] = createSignal<string | null>(null);
+  const [staleProjects, setStaleProjects] = createSignal<StaleProject[] | null>(
+    null
+  );
+  const [brokenLinks, setBrokenLinks] = createSignal<BrokenLink[] | null>(null);
+  const [isCheckingLinks, setIsCheckingLinks] = createSignal(false);
 
   // Cargar configuración y programas detectados
   onMount(async () => {
@@ -93,6 +108,89 @@ export default function Settings(props: { onClose: () => void }) {
     }
   };
 
+  const handlePurgeAnalytics = async () => {
+    if (
+      !confirm(
+        '¿Borrar todo el historial de actividad y las estadísticas de uso? Esta acción no se puede deshacer.'
+      )
+    )
+      return;
+
+    setError(null);
+    try {
+      const deleted = await purgeAnalytics();
+      setSuccessMessage(`✅ Analítica borrada (${deleted} registros)`);
+      window.setTimeout(() => setSuccessMessage(null), 3000);
+    } catch (err) {
+      setError(`Error al borrar analítica: ${err}`);
+    }
+  };
+
+  const updateArchivePolicy = (updates: Partial<ArchivePolicyConfig>) => {
+    const cfg = config();
+    if (!cfg) return;
+
+    setConfig({
+      ...cfg,
+      archive_policy: { ...cfg.archive_policy, ...updates },
+    });
+    // La vista previa usa la política guardada
+    setStaleProjects(null);
+  };
+
+  const handlePreviewStale = async () => {
+    setError(null);
+    try {
+      setStaleProjects(await getStaleProjects());
+    } catch (err) {
+      setError(`Error buscando proyectos inactivos: ${err}`);
+    }
+  };
+
+  const handleArchiveStale = async () => {
+    const projects = staleProjects();
+    if (!projects || projects.length === 0) return;
+    if (!confirm(`¿Archivar ${projects.length} proyectos inactivos?`)) return;
+
+    setError(null);
+    try {
+      const archived = await archiveStaleProjects(
+        projects.map((p) => p.project_id)
+      );
+      setStaleProjects(null);
+      setSuccessMessage(`✅ ${archived.length} proyectos archivados`);
+      window.setTimeout(() => setSuccessMessage(null), 3000);
+    } catch (err) {
+      setError(`Error al archivar: ${err}`);
+    }
+  };
+
+  const handleShowBrokenLinks = async () => {
+    setError(null);
+    try {
+      setBrokenLinks(await getBrokenLinks());
+    } catch (err) {
+      setError(`Error obteniendo enlaces rotos: ${err}`);
+    }
+  };
+
+  const handleCheckAllLinks = async () => {
+    setError(null);
+    setIsCheckingLinks(true);
+    try {
+      const summary = await checkLinks();
+      setBrokenLinks(await getBrokenLinks());
+      setSuccessMessage(
+        `✅ ${summary.checked} enlaces comprobados, ${summary.broken} rotos`
+      );
+      window.setTimeout(() => setSuccessMessage(null), 3000);
+    } catch (err) {
+      setError(`Error comprobando enlaces: ${err}`);
+    } finally {
+      setIsCheckingLinks(false);
+    }
+  };
+
   const updateProgramConfig = (
     type: 'terminal' | 'browser' | 'file_manager' | 'text_editor',
     updates: Partial<ProgramConfig>
@@ -1141,7 +1239,7 @@ export default function Settings(props: { onClose: () => void }) {
                         Habilitar Analytics
                       </p>
                       <p class="text-sm text-gray-600 dark:text-gray-400">
-                        Enviar estadísticas anónimas de uso
+                        Registrar actividad y tiempo de uso de los proyectos
                       </p>
                     </div>
                     <label class="relative inline-flex cursor-pointer items-center">
@@ -1165,8 +1263,235 @@ export default function Settings(props: { onClose: () => void }) {
                       <div class="peer h-6 w-11 rounded-full bg-gray-200 after:absolute after:start-[2px] after:top-[2px] after:h-5 after:w-5 after:rounded-full after:border after:border-gray-300 after:bg-white after:transition-all after:content-[''] peer-checked:bg-blue-600 peer-checked:after:translate-x-full peer-checked:after:border-white peer-focus:outline-none peer-focus:ring-4 peer-focus:ring-blue-300 dark:border-gray-600 dark:bg-gray-700 dark:peer-focus:ring-blue-800 rtl:peer-checked:after:-translate-x-full" />
                     </label>
                   </div>
+
+                  {/* Retención de actividad */}
+                  <div>
+                    <label class="mb-2 block text-sm font-medium text-gray-700 dark:text-gray-300">
+                      Retención de Actividad (días)
+                    </label>
+                    <input
+                      type="number"
+                      min="0"
+                      max="3650"
+                      class="w-full rounded-md border border-gray-300 bg-white px-3 py-2 text-gray-900 dark:border-gray-600 dark:bg-gray-700 dark:text-white"
+                      value={config()?.advanced.analytics_retention_days ?? 180}
+                      onInput={(e) => {
+                        const cfg = config();
+                        if (cfg) {
+                          setConfig({
+                            ...cfg,
+                            advanced: {
+                              ...cfg.advanced,
+                              analytics_retention_days: Math.max(
+                                0,
+                                parseInt(e.currentTarget.value) || 0
+                              ),
+                            },
+                          });
+                        }
+                      }}
+                    />
+                    <p class="mt-1 text-xs text-gray-500 dark:text-gray-400">
+                      La actividad más antigua se resume por día (0 = conservar todo)
+                    </p>
+                  </div>
+
+                  <button
+                    type="button"
+                    class="rounded-md border border-red-300 px-4 py-2 text-sm font-medium text-red-600 hover:bg-red-50 dark:border-red-700 dark:text-red-400 dark:hover:bg-red-900/20"
+                    onClick={handlePurgeAnalytics}
+                  >
+                    Borrar toda la analítica
+                  </button>
+                </div>
+
+                {/* Proyectos inactivos */}
+                <div class="space-y-4 rounded-lg bg-gray-50 p-4 dark:bg-gray-800">
+                  <h3 class="text-lg font-semibold text-gray-900 dark:text-white">
+                    Proyectos Inactivos
+                  </h3>
+                  <div>
+                    <label class="mb-2 block text-sm font-medium text-gray-700 dark:text-gray-300">
+                      Política de Archivado
+                    </label>
+                    <select
+                      class="w-full rounded-md border border-gray-300 bg-white px-3 py-2 text-gray-900 dark:border-gray-600 dark:bg-gray-700 dark:text-white"
+                      value={config()?.archive_policy.mode || 'suggest'}
+                      onChange={(e) =>
+                        updateArchivePolicy({
+                          mode: e.currentTarget.value as ArchivePolicyMode,
+                        })
+                      }
+                    >
+                      <option value="off">Desactivada</option>
+                      <option value="suggest">Solo sugerir</option>
+                      <option value="auto">Archivar automáticamente</option>
+                    </select>
+                  </div>
+                  <div class="grid grid-cols-2 gap-4">
+                    <div>
+                      <label class="mb-2 block text-sm font-medium text-gray-700 dark:text-gray-300">
+                        Días sin abrir
+                      </label>
+                      <input
+                        type="number"
+                        min="1"
+                        class="w-full rounded-md border border-gray-300 bg-white px-3 py-2 text-gray-900 dark:border-gray-600 dark:bg-gray-700 dark:text-white"
+                        value={config()?.archive_policy.days_not_opened ?? 90}
+                        onInput={(e) =>
+                          updateArchivePolicy({
+                            days_not_opened: Math.max(
+                              1,
+                              parseInt(e.currentTarget.value) || 1
+                            ),
+                          })
+                        }
+                      />
+                    </div>
+                    <div>
+                      <label class="mb-2 block text-sm font-medium text-gray-700 dark:text-gray-300">
+                        Días sin commits
+                      </label>
+                      <input
+                        type="number"
+                        min="0"
+                        class="w-full rounded-md border border-gray-300 bg-white px-3 py-2 text-gray-900 dark:border-gray-600 dark:bg-gray-700 dark:text-white"
+                        value={config()?.archive_policy.days_without_commits ?? 60}
+                        onInput={(e) =>
+                          updateArchivePolicy({
+                            days_without_commits: Math.max(
+                              0,
+                              parseInt(e.currentTarget.value) || 0
+                            ),
+                          })
+                        }
+                      />
+                    </div>
+                  </div>
+                  <p class="text-xs text-gray-500 dark:text-gray-400">
+                    Los proyectos activos que cumplan ambos criterios pasan a
+                    'archivado'. Guarda la configuración antes de ver la vista
+                    previa.
+                  </p>
+
+                  <button
+                    type="button"
+                    class="rounded-md border border-gray-300 px-4 py-2 text-sm font-medium text-gray-700 hover:bg-gray-100 dark:border-gray-600 dark:text-gray-300 dark:hover:bg-gray-700"
+                    onClick={handlePreviewStale}
+                  >
+                    Ver proyectos afectados
+                  </button>
+
+                  <Show when={staleProjects()}>
+                    {(projects) => (
+                      <div class="space-y-2">
+                        <Show
+                          when={projects().length > 0}
+                          fallback={
+                            <p class="text-sm text-gray-600 dark:text-gray-400">
+                              No hay proyectos inactivos
+                            </p>
+                          }
+                        >
+                          <ul class="divide-y divide-gray-200 text-sm dark:divide-gray-700">
+                            <For each={projects()}>
+                              {(project) => (
+                                <li class="flex justify-between py-1 text-gray-700 dark:text-gray-300">
+                                  <span>{project.project_name}</span>
+                                  <span class="text-gray-500 dark:text-gray-400">
+                                    {project.never_opened
+                                      ? 'nunca abierto'
+                                      : `${project.days_since_open} días sin abrir`}
+                                    {project.days_since_commit !== undefined &&
+                                    project.days_since_commit !== null
+                                      ? ` · ${project.days_since_commit} días sin commits`
+                                      : ''}
+                                  </span>
+                                </li>
+                              )}
+                            </For>
+                          </ul>
+                          <button
+                            type="button"
+                            class="rounded-md bg-blue-600 px-4 py-2 text-sm font-medium text-white hover:bg-blue-700"
+                            onClick={handleArchiveStale}
+                          >
+                            Archivar {projects().length} proyectos
+                          </button>
+                        </Show>
+                      </div>
+                    )}
+                  </Show>
                 </div>
 
+                {/* Enlaces rotos */}
+                <div class="space-y-4 rounded-lg bg-gray-50 p-4 dark:bg-gray-800">
+                  <h3 class="text-lg font-semibold text-gray-900 dark:text-white">
+                    Enlaces Rotos
+                  </h3>
+                  <p class="text-xs text-gray-500 dark:text-gray-400">
+                    Los enlaces y URLs de documentación de todos los proyectos
+                    se comprueban en segundo plano una vez al día.
+                  </p>
+                  <div class="flex space-x-2">
+                    <button
+                      type="button"
+                      class="rounded-md border border-gray-300 px-4 py-2 text-sm font-medium text-gray-700 hover:bg-gray-100 dark:border-gray-600 dark:text-gray-300 dark:hover:bg-gray-700"
+                      onClick={handleShowBrokenLinks}
+                    >
+                      Ver enlaces rotos
+                    </button>
+                    <button
+                      type="button"
+                      class="rounded-md border border-gray-300 px-4 py-2 text-sm font-medium text-gray-700 hover:bg-gray-100 disabled:opacity-50 dark:border-gray-600 dark:text-gray-300 dark:hover:bg-gray-700"
+                      disabled={isCheckingLinks()}
+                      onClick={handleCheckAllLinks}
+                    >
+                      {isCheckingLinks() ? 'Comprobando...' : 'Comprobar ahora'}
+                    </button>
+                  </div>
+
+                  <Show when={brokenLinks()}>
+                    {(links) => (
+                      <Show
+                        when={links().length > 0}
+                        fallback={
+                          <p class="text-sm text-gray-600 dark:text-gray-400">
+                            No hay enlaces rotos
+                          </p>
+                        }
+                      >
+                        <ul class="divide-y divide-gray-200 text-sm dark:divide-gray-700">
+                          <For each={links()}>
+                            {(link) => (
+                              <li class="py-1 text-gray-700 dark:text-gray-300">
+                                <div class="flex justify-between">
+                                  <span>
+                                    {link.project_name} · {link.title}
+                                  </span>
+                                  <span class="text-red-600 dark:text-red-400">
+                                    {link.check.status_code ?? link.check.error}
+                                  </span>
+                                </div>
+                                <button
+                                  type="button"
+                                  class="truncate text-xs text-blue-600 hover:underline dark:text-blue-400"
+                                  onClick={() => openUrl(link.url)}
+                                >
+                                  {link.url}
+                                </button>
+                              </li>
+                            )}
+                          </For>
+                        </ul>
+                      </Show>
+                    )}
+                  </Show>
+                </div>
+
+                {/* Importar marcadores */}
+                <BookmarkImport />
+
                 {/* Auto-updates */}
                 <div class="space-y-4 rounded-lg bg-gray-50 p-4 dark:bg-gray-800">
                   <h3 class="text-lg font-semibold text-gray-900 dark:text-white">
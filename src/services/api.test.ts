@@ -117,7 +117,15 @@ describe('API Service', () => {
 
       await api.deleteProject(1);
 
-      expect(invoke).toHaveBeenCalledWith('delete_project', { id: 1 });
+      expect(invoke).toHaveBeenCalledWith('delete_project', { id: 1, force: false });
+    });
+
+    it('should pass force to delete a project with dependents', async () => {
+      vi.mocked(invoke).mockResolvedValue(undefined);
+
+      await api.deleteProject(1, true);
+
+      expect(invoke).toHaveBeenCalledWith('delete_project', { id: 1, force: true });
     });
   });
 
@@ -143,6 +151,17 @@ describe('API Service', () => {
     });
   });
 
+  describe('getProjectImage', () => {
+    it('should call get_project_image command with id', async () => {
+      vi.mocked(invoke).mockResolvedValue('data:image/jpeg;base64,AAAA');
+
+      const result = await api.getProjectImage(1);
+
+      expect(invoke).toHaveBeenCalledWith('get_project_image', { id: 1 });
+      expect(result).toBe('data:image/jpeg;base64,AAAA');
+    });
+  });
+
   describe('openTerminal', () => {
     it('should call open_terminal command with path', async () => {
       vi.mocked(invoke).mockResolvedValue(undefined);
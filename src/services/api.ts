@@ -1,13 +1,23 @@
-import { invoke } from '@tauri-apps/api/core';
+import { convertFileSrc, invoke } from '@tauri-apps/api/core';
 import type {
   Project,
   ProjectLink,
+  BrokenLink,
+  BookmarkFolder,
+  BookmarkMapping,
+  BookmarkImportSummary,
+  LinkCheckSummary,
   ProjectActivity,
+  ActivityFilter,
+  ActivityPruneResult,
+  DailyActivitySummary,
   ProjectStats,
   CreateProjectDTO,
   UpdateProjectDTO,
   ProjectAttachment,
   CreateAttachmentDTO,
+  AttachmentImportResult,
+  AttachmentSearchHit,
   GitCommit,
   JournalEntry,
   CreateJournalEntryDTO,
@@ -15,6 +25,29 @@ import type {
   ProjectTodo,
   CreateTodoDTO,
   UpdateTodoDTO,
+  SmartView,
+  SmartViewFilter,
+  SmartViewSortField,
+  SortDirection,
+  CreateSmartViewDTO,
+  UpdateSmartViewDTO,
+  SearchQuery,
+  ProjectRelation,
+  RelatedProject,
+  CreateRelationDTO,
+  ProjectGraph,
+  ProjectMilestone,
+  CreateMilestoneDTO,
+  UpdateMilestoneDTO,
+  UpcomingMilestone,
+  Reminder,
+  CreateReminderDTO,
+  BoardColumn,
+  CreateBoardColumnDTO,
+  UpdateBoardColumnDTO,
+  ProjectBoard,
+  ProjectHealth,
+  StaleProject,
 } from '../types/project';
 import type { AppConfig, DetectedPrograms } from '../types/config';
 
@@ -39,14 +72,25 @@ export async function updateProject(
   return await invoke('update_project', { id, updates });
 }
 
-export async function deleteProject(id: number): Promise<void> {
-  await invoke('delete_project', { id });
+// Sin `force`, el backend rechaza eliminar un proyecto del que dependen otros
+export async function deleteProject(id: number, force = false): Promise<void> {
+  await invoke('delete_project', { id, force });
 }
 
+// Acepta texto libre o consultas como `status:activo pinned:yes opened:<30d`.
+// Los errores de sintaxis se rechazan con un SearchError que incluye la posición.
 export async function searchProjects(query: string): Promise<Project[]> {
   return await invoke('search_projects', { query });
 }
 
+export async function parseSearchQuery(query: string): Promise<SearchQuery> {
+  return await invoke('parse_search_query', { query });
+}
+
+export async function getProjectImage(id: number): Promise<string | null> {
+  return await invoke('get_project_image', { id });
+}
+
 export async function openTerminal(path: string): Promise<void> {
   await invoke('open_terminal', { path });
 }
@@ -84,14 +128,19 @@ export async function syncProjectToBackup(
 // Funciones para manejar enlaces de proyectos
 export interface CreateLinkDTO {
   project_id: number;
+  // '' para deducirlo de la URL
   link_type:
+    | ''
     | 'repository'
     | 'documentation'
     | 'staging'
     | 'production'
     | 'design'
     | 'api'
+    | 'issues'
+    | 'drive'
     | 'other';
+  // '' para usar el título de la página
   title: string;
   url: string;
 }
@@ -104,15 +153,20 @@ export interface UpdateLinkDTO {
     | 'production'
     | 'design'
     | 'api'
+    | 'issues'
+    | 'drive'
     | 'other';
   title?: string;
   url?: string;
 }
 
+// Con fetchMetadata el backend descarga la página (si no está en caché) para
+// completar el título y el favicon; sin él no se conecta a la red
 export async function createProjectLink(
-  link: CreateLinkDTO
+  link: CreateLinkDTO,
+  fetchMetadata = false
 ): Promise<ProjectLink> {
-  return await invoke('create_project_link', { link });
+  return await invoke('create_project_link', { link, fetchMetadata });
 }
 
 export async function getProjectLinks(
@@ -132,6 +186,31 @@ export async function deleteProjectLink(id: number): Promise<void> {
   await invoke('delete_project_link', { id });
 }
 
+// Comprueba ahora los enlaces de un proyecto (o de todos sin projectId)
+export async function checkLinks(
+  projectId?: number
+): Promise<LinkCheckSummary> {
+  return await invoke('check_links', { projectId });
+}
+
+export async function getBrokenLinks(): Promise<BrokenLink[]> {
+  return await invoke('get_broken_links');
+}
+
+// Marcadores del navegador: HTML exportado o JSON de Firefox/Chromium
+export async function previewBookmarks(
+  path: string
+): Promise<BookmarkFolder[]> {
+  return await invoke('preview_bookmarks', { path });
+}
+
+export async function importBookmarks(
+  path: string,
+  mappings: BookmarkMapping[]
+): Promise<BookmarkImportSummary> {
+  return await invoke('import_bookmarks', { path, mappings });
+}
+
 // Funciones para Analytics y Tracking
 export async function trackProjectOpen(projectId: number): Promise<void> {
   await invoke('track_project_open', { projectId });
@@ -155,6 +234,25 @@ export async function getProjectActivities(
   return await invoke('get_project_activities', { projectId, limit });
 }
 
+export async function getActivities(filter: ActivityFilter = {}): Promise<ProjectActivity[]> {
+  return await invoke('get_activities', { filter });
+}
+
+export async function getDailyActivity(
+  projectId?: number,
+  since?: string
+): Promise<DailyActivitySummary[]> {
+  return await invoke('get_daily_activity', { projectId, since });
+}
+
+export async function pruneActivities(): Promise<ActivityPruneResult> {
+  return await invoke('prune_activities');
+}
+
+export async function purgeAnalytics(): Promise<number> {
+  return await invoke('purge_analytics');
+}
+
 // Funciones para manejar archivos adjuntos
 export async function addAttachment(
   attachment: CreateAttachmentDTO
@@ -162,16 +260,90 @@ export async function addAttachment(
   return await invoke('add_attachment', { attachment });
 }
 
+export async function addAttachmentFromPath(
+  projectId: number,
+  path: string
+): Promise<ProjectAttachment> {
+  return await invoke('add_attachment_from_path', { projectId, path });
+}
+
+export async function addAttachmentsFromPaths(
+  projectId: number,
+  paths: string[]
+): Promise<AttachmentImportResult> {
+  return await invoke('add_attachments_from_paths', { projectId, paths });
+}
+
+// Busca en el texto de los adjuntos; acepta la misma sintaxis que searchProjects
+export async function searchAttachments(
+  query: string,
+  projectId?: number
+): Promise<AttachmentSearchHit[]> {
+  return await invoke('search_attachments', { query, projectId });
+}
+
+export async function reindexAttachment(
+  id: number
+): Promise<ProjectAttachment> {
+  return await invoke('reindex_attachment', { id });
+}
+
 export async function getAttachments(
   projectId: number
 ): Promise<ProjectAttachment[]> {
   return await invoke('get_attachments', { projectId });
 }
 
+// Elimina el documento con todas sus versiones
 export async function deleteAttachment(id: number): Promise<void> {
   await invoke('delete_attachment', { id });
 }
 
+// Sube un archivo del disco como nueva versión del documento
+export async function addAttachmentVersion(
+  attachmentId: number,
+  path: string
+): Promise<ProjectAttachment> {
+  return await invoke('add_attachment_version', { attachmentId, path });
+}
+
+// Versiones del documento, de la más reciente a la más antigua
+export async function getAttachmentVersions(
+  attachmentId: number
+): Promise<ProjectAttachment[]> {
+  return await invoke('get_attachment_versions', { attachmentId });
+}
+
+// Restaura una versión anterior como nueva última versión
+export async function rollbackAttachment(
+  versionId: number
+): Promise<ProjectAttachment> {
+  return await invoke('rollback_attachment', { versionId });
+}
+
+// Escribe la versión en la carpeta local del proyecto; devuelve la ruta final
+export async function exportAttachment(
+  id: number,
+  relativePath?: string,
+  overwrite = false
+): Promise<string> {
+  return await invoke('export_attachment', { id, relativePath, overwrite });
+}
+
+// URLs del protocolo gestor:// (el backend sirve los bytes sin pasar por IPC).
+// convertFileSrc genera la forma correcta para cada plataforma.
+export function attachmentUrl(id: number): string {
+  return convertFileSrc(`attachment/${id}`, 'gestor');
+}
+
+export function projectImageUrl(projectId: number): string {
+  return convertFileSrc(`project-image/${projectId}`, 'gestor');
+}
+
+export function faviconUrl(linkId: number): string {
+  return convertFileSrc(`favicon/${linkId}`, 'gestor');
+}
+
 // Funciones para obtener información de Git
 export async function getGitBranch(path: string): Promise<string> {
   return await invoke('get_git_branch', { path });
@@ -181,6 +353,100 @@ export async function getGitStatus(path: string): Promise<string> {
   return await invoke('get_git_status', { path });
 }
 
+export async function getGitBranches(
+  path: string
+): Promise<import('../types/git').GitBranch[]> {
+  return await invoke('get_git_branches', { path });
+}
+
+export async function gitCreateBranch(
+  path: string,
+  name: string,
+  startPoint: string | null = null
+): Promise<void> {
+  return await invoke('git_create_branch', { path, name, startPoint });
+}
+
+// Falla con cambios sin commitear salvo con autoStash
+export async function gitSwitchBranch(
+  path: string,
+  name: string,
+  autoStash: boolean = false
+): Promise<import('../types/git').BranchSwitch> {
+  return await invoke('git_switch_branch', { path, name, autoStash });
+}
+
+export async function gitRenameBranch(
+  path: string,
+  oldName: string,
+  newName: string
+): Promise<void> {
+  return await invoke('git_rename_branch', { path, oldName, newName });
+}
+
+export async function gitDeleteBranch(
+  path: string,
+  name: string,
+  force: boolean = false
+): Promise<void> {
+  return await invoke('git_delete_branch', { path, name, force });
+}
+
+export async function getGitStashes(
+  path: string
+): Promise<import('../types/git').GitStash[]> {
+  return await invoke('get_git_stashes', { path });
+}
+
+export async function gitStashSave(
+  path: string,
+  message: string | null = null,
+  includeUntracked: boolean = false
+): Promise<void> {
+  return await invoke('git_stash_save', { path, message, includeUntracked });
+}
+
+export async function gitStashApply(path: string, index: number): Promise<void> {
+  return await invoke('git_stash_apply', { path, index });
+}
+
+// Aplica y borra el stash (se conserva si hay conflictos)
+export async function gitStashPop(path: string, index: number): Promise<void> {
+  return await invoke('git_stash_pop', { path, index });
+}
+
+export async function gitStashDrop(path: string, index: number): Promise<void> {
+  return await invoke('git_stash_drop', { path, index });
+}
+
+export async function getGitStashDiff(
+  path: string,
+  index: number
+): Promise<import('../types/git').FileDiff[]> {
+  return await invoke('get_git_stash_diff', { path, index });
+}
+
+export async function getGitStatusDetailed(
+  path: string
+): Promise<import('../types/git').GitStatusDetailed> {
+  return await invoke('get_git_status_detailed', { path });
+}
+
+export async function getGitDiff(
+  path: string,
+  file: string | null = null,
+  staged: boolean = false
+): Promise<import('../types/git').FileDiff[]> {
+  return await invoke('get_git_diff', { path, file, staged });
+}
+
+export async function getCommitDiff(
+  path: string,
+  hash: string
+): Promise<import('../types/git').FileDiff[]> {
+  return await invoke('get_commit_diff', { path, hash });
+}
+
 export async function getRecentCommits(
   path: string,
   limit: number = 5
@@ -203,6 +469,50 @@ export async function gitAdd(path: string, files: string[]): Promise<string> {
   return await invoke('git_add', { path, files });
 }
 
+export async function gitStageHunks(
+  path: string,
+  hunks: import('../types/git').HunkSelection[]
+): Promise<void> {
+  return await invoke('git_stage_hunks', { path, hunks });
+}
+
+export async function gitUnstageHunks(
+  path: string,
+  hunks: import('../types/git').HunkSelection[]
+): Promise<void> {
+  return await invoke('git_unstage_hunks', { path, hunks });
+}
+
+export async function gitUnstageFiles(
+  path: string,
+  files: string[]
+): Promise<void> {
+  return await invoke('git_unstage_files', { path, files });
+}
+
+export async function gitDiscardPreview(
+  path: string,
+  files: string[],
+  hunks: import('../types/git').HunkSelection[] = []
+): Promise<import('../types/git').DiscardPreview> {
+  return await invoke('git_discard_preview', { path, files, hunks });
+}
+
+// Descarta los cambios confirmados con el token de gitDiscardPreview
+export async function gitDiscardChanges(
+  path: string,
+  files: string[],
+  hunks: import('../types/git').HunkSelection[],
+  confirmToken: string
+): Promise<void> {
+  return await invoke('git_discard_changes', {
+    path,
+    files,
+    hunks,
+    confirmToken,
+  });
+}
+
 export async function gitCommit(
   path: string,
   message: string
@@ -228,6 +538,26 @@ export async function getGitAheadBehind(
   return await invoke('get_git_ahead_behind', { path });
 }
 
+export async function getProjectHealth(projectId: number): Promise<ProjectHealth> {
+  return await invoke('get_project_health', { projectId });
+}
+
+// Ordenados de peor a mejor puntuación
+export async function getProjectsHealth(): Promise<ProjectHealth[]> {
+  return await invoke('get_projects_health');
+}
+
+export async function getStaleProjects(): Promise<StaleProject[]> {
+  return await invoke('get_stale_projects');
+}
+
+// Sin projectIds archiva todos los proyectos inactivos
+export async function archiveStaleProjects(
+  projectIds?: number[]
+): Promise<StaleProject[]> {
+  return await invoke('archive_stale_projects', { projectIds });
+}
+
 // ==================== FUNCIONES PARA PROJECT JOURNAL ====================
 
 export async function createJournalEntry(
@@ -276,6 +606,121 @@ export async function deleteTodo(id: number): Promise<void> {
   await invoke('delete_todo', { id });
 }
 
+// ==================== FUNCIONES PARA TABLERO DE TODOS ====================
+
+export async function getProjectBoard(projectId: number): Promise<ProjectBoard> {
+  return await invoke('get_project_board', { projectId });
+}
+
+export async function getBoardColumns(
+  projectId: number
+): Promise<BoardColumn[]> {
+  return await invoke('get_board_columns', { projectId });
+}
+
+export async function createBoardColumn(
+  column: CreateBoardColumnDTO
+): Promise<BoardColumn> {
+  return await invoke('create_board_column', { column });
+}
+
+export async function updateBoardColumn(
+  id: number,
+  updates: UpdateBoardColumnDTO
+): Promise<BoardColumn> {
+  return await invoke('update_board_column', { id, updates });
+}
+
+export async function deleteBoardColumn(id: number): Promise<void> {
+  await invoke('delete_board_column', { id });
+}
+
+export async function reorderBoardColumns(
+  projectId: number,
+  columnIds: number[]
+): Promise<void> {
+  await invoke('reorder_board_columns', { projectId, columnIds });
+}
+
+// position 0 = arriba de la columna
+export async function moveTodo(
+  todoId: number,
+  columnId: number,
+  position: number
+): Promise<ProjectTodo> {
+  return await invoke('move_todo', { todoId, columnId, position });
+}
+
+// ==================== FUNCIONES PARA HITOS ====================
+
+export async function createMilestone(
+  milestone: CreateMilestoneDTO
+): Promise<ProjectMilestone> {
+  return await invoke('create_milestone', { milestone });
+}
+
+export async function getProjectMilestones(
+  projectId: number
+): Promise<ProjectMilestone[]> {
+  return await invoke('get_project_milestones', { projectId });
+}
+
+export async function updateMilestone(
+  id: number,
+  updates: UpdateMilestoneDTO
+): Promise<ProjectMilestone> {
+  return await invoke('update_milestone', { id, updates });
+}
+
+export async function deleteMilestone(id: number): Promise<void> {
+  await invoke('delete_milestone', { id });
+}
+
+// milestoneId null desvincula el TODO
+export async function setTodoMilestone(
+  todoId: number,
+  milestoneId: number | null
+): Promise<void> {
+  await invoke('set_todo_milestone', { todoId, milestoneId });
+}
+
+export async function getUpcomingMilestones(
+  daysAhead?: number
+): Promise<UpcomingMilestone[]> {
+  return await invoke('get_upcoming_milestones', { daysAhead });
+}
+
+// ==================== FUNCIONES PARA RECORDATORIOS ====================
+
+export async function createReminder(
+  reminder: CreateReminderDTO
+): Promise<Reminder> {
+  return await invoke('create_reminder', { reminder });
+}
+
+// Sin projectId devuelve los recordatorios de todos los proyectos
+export async function getReminders(
+  projectId?: number,
+  includeDismissed?: boolean
+): Promise<Reminder[]> {
+  return await invoke('get_reminders', { projectId, includeDismissed });
+}
+
+export async function snoozeReminder(
+  id: number,
+  minutes: number
+): Promise<Reminder> {
+  return await invoke('snooze_reminder', { id, minutes });
+}
+
+export async function dismissReminder(id: number): Promise<Reminder> {
+  return await invoke('dismiss_reminder', { id });
+}
+
+export async function deleteReminder(id: number): Promise<void> {
+  await invoke('delete_reminder', { id });
+}
+
 // ==================== FUNCIONES PARA ESTADOS Y FAVORITOS ====================
 
 export async function updateProjectStatus(
@@ -295,6 +740,73 @@ export async function reorderPinnedProjects(
   await invoke('reorder_pinned_projects', { projectIds });
 }
 
+// ==================== FUNCIONES PARA VISTAS GUARDADAS ====================
+
+export async function createSmartView(
+  view: CreateSmartViewDTO
+): Promise<SmartView> {
+  return await invoke('create_smart_view', { view });
+}
+
+export async function getSmartViews(): Promise<SmartView[]> {
+  return await invoke('get_smart_views');
+}
+
+export async function updateSmartView(
+  id: number,
+  updates: UpdateSmartViewDTO
+): Promise<SmartView> {
+  return await invoke('update_smart_view', { id, updates });
+}
+
+export async function deleteSmartView(id: number): Promise<void> {
+  await invoke('delete_smart_view', { id });
+}
+
+export async function runSmartView(id: number): Promise<Project[]> {
+  return await invoke('run_smart_view', { id });
+}
+
+export async function previewSmartView(
+  filter: SmartViewFilter,
+  sortBy: SmartViewSortField,
+  sortDirection: SortDirection
+): Promise<Project[]> {
+  return await invoke('preview_smart_view', { filter, sortBy, sortDirection });
+}
+
+// ==================== FUNCIONES PARA RELACIONES ====================
+
+export async function createProjectRelation(
+  relation: CreateRelationDTO
+): Promise<ProjectRelation> {
+  return await invoke('create_project_relation', { relation });
+}
+
+export async function deleteProjectRelation(id: number): Promise<void> {
+  await invoke('delete_project_relation', { id });
+}
+
+export async function getProjectRelations(
+  projectId: number
+): Promise<RelatedProject[]> {
+  return await invoke('get_project_relations', { projectId });
+}
+
+// Proyectos que dependen de este (para avisar antes de eliminarlo)
+export async function getProjectDependents(
+  projectId: number
+): Promise<RelatedProject[]> {
+  return await invoke('get_project_dependents', { projectId });
+}
+
+export async function getProjectGraph(
+  projectId: number,
+  depth?: number
+): Promise<ProjectGraph> {
+  return await invoke('get_project_graph', { projectId, depth });
+}
+
 // ==================== FUNCIONES PARA CONFIGURACIÓN ====================
 
 export async function getConfig(): Promise<AppConfig> {
@@ -126,7 +126,7 @@ describe('ProjectStore', () => {
 
       await store.deleteProject(1);
 
-      expect(api.deleteProject).toHaveBeenCalledWith(1);
+      expect(api.deleteProject).toHaveBeenCalledWith(1, false);
       expect(api.getAllProjects).toHaveBeenCalledTimes(2);
 
       dispose();
@@ -3,6 +3,7 @@ import type {
   Project,
   CreateProjectDTO,
   UpdateProjectDTO,
+  SearchError,
 } from '../types/project';
 import * as api from '../services/api';
 
@@ -63,11 +64,11 @@ export function createProjectStore() {
     }
   }
 
-  async function deleteProject(id: number) {
+  async function deleteProject(id: number, force = false) {
     setIsLoading(true);
     setError(null);
     try {
-      await api.deleteProject(id);
+      await api.deleteProject(id, force);
       await loadProjects();
     } catch (err) {
       setError(err instanceof Error ? err.message : 'Error desconocido');
@@ -84,7 +85,13 @@ export function createProjectStore() {
       const data = await api.searchProjects(query);
       setProjects(data);
     } catch (err) {
-      setError(err instanceof Error ? err.message : 'Error desconocido');
+      // Los errores de la consulta llegan como { message, span }
+      const searchError = err as SearchError | undefined;
+      setError(
+        err instanceof Error
+          ? err.message
+          : searchError?.message || 'Error desconocido'
+      );
     } finally {
       setIsLoading(false);
     }
@@ -42,10 +42,22 @@ export interface UiConfig {
 export interface AdvancedConfig {
   log_level: LogLevel;
   enable_analytics: boolean;
+  // Días que se conserva la actividad detallada (0 = siempre)
+  analytics_retention_days: number;
   database_path?: string;
   enable_auto_update: boolean;
 }
 
+// Política de archivado de proyectos inactivos
+export type ArchivePolicyMode = 'off' | 'suggest' | 'auto';
+
+export interface ArchivePolicyConfig {
+  mode: ArchivePolicyMode;
+  days_not_opened: number;
+  // Los proyectos sin repositorio git solo se evalúan por apertura
+  days_without_commits: number;
+}
+
 // Configuración completa de la aplicación
 export interface AppConfig {
   version: string;
@@ -54,6 +66,7 @@ export interface AppConfig {
   ui: UiConfig;
   advanced: AdvancedConfig;
   shortcuts: ShortcutsConfig;
+  archive_policy: ArchivePolicyConfig;
 }
 
 // ==================== DETECCIÓN DE PROGRAMAS ====================
@@ -2,10 +2,124 @@
 
 // Información de un commit
 export interface GitCommit {
-  hash: string;
+  hash: string; // Abreviado (7 caracteres)
+  full_hash: string;
   author: string;
-  date: string;
+  date: string; // Relativa, ej: "hace 3 días"
+  timestamp: number; // Unix, en segundos
+  message: string;
+}
+
+// Estado de un archivo en el índice o en el directorio de trabajo
+export type FileState =
+  | 'unmodified'
+  | 'modified'
+  | 'added'
+  | 'deleted'
+  | 'renamed'
+  | 'copied'
+  | 'type_changed'
+  | 'untracked'
+  | 'conflicted';
+
+// Archivo con cambios (rutas relativas a la raíz del repositorio)
+export interface GitStatusEntry {
+  path: string;
+  orig_path: string | null; // Ruta anterior de un renombrado o copia
+  index: FileState;
+  worktree: FileState;
+  conflicted: boolean;
+  submodule: boolean;
+}
+
+// Estado detallado devuelto por el backend
+export interface GitStatusDetailed {
+  branch: string | null; // null con HEAD desacoplado
+  head: string | null; // null si aún no hay commits
+  upstream: string | null;
+  ahead: number;
+  behind: number;
+  stash_count: number;
+  entries: GitStatusEntry[];
+}
+
+// Trozo de una línea en el diff por palabras
+export interface WordSegment {
+  text: string;
+  changed: boolean;
+}
+
+export interface DiffLine {
+  kind: 'context' | 'addition' | 'deletion';
+  content: string;
+  old_lineno: number | null;
+  new_lineno: number | null;
+  missing_newline: boolean;
+  words: WordSegment[]; // Vacío si la línea no tiene pareja en el otro lado
+}
+
+export interface DiffHunk {
+  old_start: number;
+  old_lines: number;
+  new_start: number;
+  new_lines: number;
+  header: string;
+  lines: DiffLine[];
+}
+
+// Cambios de un archivo
+export interface FileDiff {
+  path: string;
+  old_path: string | null; // Renombrados y copias
+  status: FileState;
+  binary: boolean;
+  additions: number;
+  deletions: number;
+  hunks: DiffHunk[];
+}
+
+// Hunk elegido para stage/unstage/descartar, identificado por sus posiciones
+export interface HunkSelection {
+  file: string; // Ruta desde la raíz del repositorio
+  old_start: number;
+  new_start: number;
+  lines: number[] | null; // Índices en DiffHunk.lines; null para el hunk entero
+}
+
+// Lo que se perdería al descartar; el token se pasa a gitDiscardChanges
+export interface DiscardPreview {
+  token: string;
+  diffs: FileDiff[];
+  untracked: string[]; // Se borran del disco
+}
+
+// Rama local (main) o remota (origin/main)
+export interface GitBranch {
+  name: string;
+  remote: boolean;
+  current: boolean;
+  upstream: string | null;
+  upstream_gone: boolean; // El upstream ya no existe en el remoto
+  ahead: number;
+  behind: number;
+  last_commit: GitCommit | null;
+}
+
+// Resultado de cambiar de rama
+export interface BranchSwitch {
+  branch: string;
+  stashed: boolean; // Se usó el auto-stash
+  restored: boolean; // Si es false, los cambios siguen en stash@{0}
+}
+
+// Entrada de la pila de stashes
+export interface GitStash {
+  index: number; // stash@{index}
+  hash: string;
+  branch: string | null; // Rama en la que se creó
   message: string;
+  date: string; // Relativa, ej: "hace 3 días"
+  timestamp: number;
 }
 
 // Conteo de archivos modificados
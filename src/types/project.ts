@@ -8,10 +8,23 @@ export interface ProjectLink {
     | 'production'
     | 'design'
     | 'api'
+    | 'issues'
+    | 'drive'
     | 'other';
   title: string;
   url: string;
   created_at: string;
+  has_favicon?: boolean; // Favicon servido por faviconUrl(id)
+  check?: LinkCheck; // Sin valor hasta la primera comprobación
+}
+
+// Última comprobación de una URL (compartida por los enlaces con la misma URL)
+export interface LinkCheck {
+  status_code?: number; // Sin valor en file:// o si no hubo respuesta
+  final_url?: string; // Destino si la URL redirige
+  error?: string;
+  is_broken: boolean;
+  checked_at: string;
 }
 
 export interface Project {
@@ -23,7 +36,9 @@ export interface Project {
   ai_documentation_url?: string;
   drive_link?: string;
   notes?: string;
-  image_data?: string;
+  image_data?: string; // Solo en getProject; los listados usan has_image
+  has_image?: boolean;
+  thumbnail_data?: string; // Miniatura (máx. 128px) generada en el backend
   links?: ProjectLink[];
   created_at: string;
   updated_at: string;
@@ -38,15 +53,96 @@ export interface Project {
   pinned_order?: number;
 }
 
+export type ActivityType =
+  | 'opened'
+  | 'time_tracked'
+  | 'project_created'
+  | 'project_updated'
+  | 'project_deleted'
+  | 'status_changed'
+  | 'pinned'
+  | 'unpinned'
+  | 'link_added'
+  | 'link_updated'
+  | 'link_removed'
+  | 'links_imported'
+  | 'attachment_added'
+  | 'attachment_removed'
+  | 'attachment_restored'
+  | 'attachment_exported'
+  | 'journal_entry_added'
+  | 'journal_entry_updated'
+  | 'journal_entry_removed'
+  | 'todo_created'
+  | 'todo_updated'
+  | 'todo_completed'
+  | 'todo_reopened'
+  | 'todo_moved'
+  | 'todo_deleted'
+  | 'board_updated'
+  | 'milestone_created'
+  | 'milestone_updated'
+  | 'milestone_completed'
+  | 'milestone_deleted'
+  | 'reminder_created'
+  | 'reminder_snoozed'
+  | 'reminder_dismissed'
+  | 'reminder_deleted'
+  | 'relation_added'
+  | 'relation_removed'
+  | 'git_commit'
+  | 'git_push'
+  | 'git_pull'
+  | 'git_staged'
+  | 'git_unstaged'
+  | 'git_discarded'
+  | 'git_branch_created'
+  | 'git_branch_switched'
+  | 'git_branch_renamed'
+  | 'git_branch_deleted'
+  | 'git_stash_saved'
+  | 'git_stash_applied'
+  | 'git_stash_popped'
+  | 'git_stash_dropped'
+  | 'backup_created'
+  | 'backup_synced';
+
 export interface ProjectActivity {
   id: number;
   project_id: number;
-  activity_type: string;
+  // Nombre del proyecto si todavía existe
+  project_name?: string;
+  activity_type: ActivityType | string;
   description?: string;
   duration_seconds?: number;
+  // Detalles estructurados según el tipo de actividad
+  details?: Record<string, unknown>;
   created_at: string;
 }
 
+export interface ActivityPruneResult {
+  deleted_rows: number;
+  summarized_days: number;
+}
+
+export interface DailyActivitySummary {
+  project_id: number;
+  day: string;
+  activity_type: ActivityType | string;
+  count: number;
+  total_duration_seconds: number;
+}
+
+export interface ActivityFilter {
+  project_id?: number;
+  activity_types?: ActivityType[];
+  // Fechas en formato "YYYY-MM-DD" o "YYYY-MM-DD HH:MM:SS" (UTC)
+  since?: string;
+  until?: string;
+  limit?: number;
+  offset?: number;
+}
+
 export interface ProjectStats {
   total_projects: number;
   active_today: number;
@@ -77,14 +173,44 @@ export interface UpdateProjectDTO {
   image_data?: string;
 }
 
+// El contenido se obtiene con attachmentUrl(id) (protocolo gestor://)
 export interface ProjectAttachment {
   id: number;
   project_id: number;
   filename: string;
-  file_data: string;
   file_size: number;
   mime_type: string;
   created_at: string;
+  content_hash?: string; // SHA-256; ausente en adjuntos antiguos
+  text_status: AttachmentTextStatus;
+  text_error?: string;
+  logical_name: string; // Compartido por todas las versiones del documento
+  version: number;
+  version_count: number;
+}
+
+// Extracción de texto para la búsqueda (se procesa en segundo plano)
+export type AttachmentTextStatus =
+  | 'pending'
+  | 'processing'
+  | 'indexed'
+  | 'unsupported'
+  | 'failed';
+
+export interface AttachmentSearchHit {
+  attachment: ProjectAttachment;
+  project_name: string;
+  snippet: string; // HTML escapado con las coincidencias en <mark>
+}
+
+export interface SkippedAttachment {
+  path: string;
+  reason: string;
+}
+
+export interface AttachmentImportResult {
+  added: ProjectAttachment[];
+  skipped: SkippedAttachment[];
 }
 
 export interface CreateAttachmentDTO {
@@ -133,14 +259,306 @@ export interface ProjectTodo {
   is_completed: boolean;
   created_at: string;
   completed_at?: string;
+  milestone_id?: number;
+  // Tablero: columna y posición dentro de ella
+  column_id?: number;
+  position?: number;
 }
 
 export interface CreateTodoDTO {
   project_id: number;
   content: string;
+  milestone_id?: number;
+  column_id?: number; // Por defecto, la primera columna del tablero
 }
 
 export interface UpdateTodoDTO {
   content?: string;
+  // Completar mueve el TODO a la columna de terminados (y descompletar lo saca de ella)
   is_completed?: boolean;
+  column_id?: number; // Mover al final de otra columna
+}
+
+// ==================== TABLERO DE TODOS ====================
+
+export interface BoardColumn {
+  id: number;
+  project_id: number;
+  name: string;
+  position: number;
+  is_done_column: boolean;
+  created_at: string;
+}
+
+export interface CreateBoardColumnDTO {
+  project_id: number;
+  name: string;
+  is_done_column?: boolean;
+}
+
+export interface UpdateBoardColumnDTO {
+  name?: string;
+  is_done_column?: boolean;
+}
+
+export interface ProjectBoard {
+  project_id: number;
+  columns: BoardColumn[];
+  todos: ProjectTodo[]; // Ordenados por columna y posición
+}
+
+// ==================== SMART VIEWS ====================
+
+export type RelativePeriod = 'today' | 'this_week' | 'this_month' | 'this_year';
+
+export interface SmartViewFilter {
+  text?: string;
+  statuses?: string[];
+  pinned?: boolean;
+  opened_within_days?: number;
+  not_opened_in_days?: number;
+  status_changed_in?: RelativePeriod;
+  has_uncommitted_changes?: boolean;
+}
+
+export type SmartViewSortField =
+  | 'name'
+  | 'created_at'
+  | 'updated_at'
+  | 'last_opened_at'
+  | 'opened_count'
+  | 'status_changed_at'
+  | 'pinned_order';
+
+export type SortDirection = 'asc' | 'desc';
+
+export interface SmartView {
+  id: number;
+  name: string;
+  filter: SmartViewFilter;
+  sort_by: SmartViewSortField;
+  sort_direction: SortDirection;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface CreateSmartViewDTO {
+  name: string;
+  filter: SmartViewFilter;
+  sort_by: SmartViewSortField;
+  sort_direction: SortDirection;
+}
+
+export interface UpdateSmartViewDTO {
+  name?: string;
+  filter?: SmartViewFilter;
+  sort_by?: SmartViewSortField;
+  sort_direction?: SortDirection;
+}
+
+// ==================== BÚSQUEDA ESTRUCTURADA ====================
+
+// Rango en caracteres dentro de la consulta (fin exclusivo)
+export interface QuerySpan {
+  start: number;
+  end: number;
+}
+
+// Error devuelto por search_projects / parse_search_query
+export interface SearchError {
+  message: string;
+  span?: QuerySpan;
+}
+
+export interface SearchClause {
+  term: { type: string; value: unknown };
+  negated: boolean;
+  span: QuerySpan;
+}
+
+export interface SearchQuery {
+  raw: string;
+  clauses: SearchClause[];
+}
+
+// ==================== RELACIONES ENTRE PROYECTOS ====================
+
+// depends-on y fork-of son dirigidas (origen → destino); related-to es simétrica
+export type RelationType = 'depends-on' | 'related-to' | 'fork-of';
+
+export interface ProjectRelation {
+  id: number;
+  source_project_id: number;
+  target_project_id: number;
+  relation_type: RelationType;
+  created_at: string;
+}
+
+export interface RelatedProject {
+  relation: ProjectRelation;
+  // outgoing: este proyecto es el origen; incoming: el otro proyecto apunta a este
+  direction: 'outgoing' | 'incoming';
+  project_id: number;
+  project_name: string;
+  project_status?: string;
+}
+
+export interface CreateRelationDTO {
+  source_project_id: number;
+  target_project_id: number;
+  relation_type: RelationType;
+}
+
+export interface ProjectGraphNode {
+  id: number;
+  name: string;
+  status?: string;
+  depth: number;
+}
+
+export interface ProjectGraph {
+  root_id: number;
+  nodes: ProjectGraphNode[];
+  edges: ProjectRelation[];
+}
+
+// ==================== HITOS ====================
+
+export interface ProjectMilestone {
+  id: number;
+  project_id: number;
+  title: string;
+  description?: string;
+  due_date?: string; // YYYY-MM-DD
+  is_completed: boolean;
+  completed_at?: string;
+  created_at: string;
+  updated_at: string;
+  // Calculados a partir de los TODOs vinculados
+  total_todos: number;
+  completed_todos: number;
+  progress: number; // 0 - 1
+  is_overdue: boolean;
+}
+
+export interface CreateMilestoneDTO {
+  project_id: number;
+  title: string;
+  description?: string;
+  due_date?: string;
+}
+
+export interface UpdateMilestoneDTO {
+  title?: string;
+  description?: string;
+  due_date?: string; // Cadena vacía para quitar la fecha
+  is_completed?: boolean;
+}
+
+export interface UpcomingMilestone extends ProjectMilestone {
+  project_name: string;
+  days_until_due: number; // Negativo si ya venció
+}
+
+// ==================== RECORDATORIOS ====================
+
+export type ReminderStatus = 'pending' | 'fired' | 'dismissed';
+
+export interface Reminder {
+  id: number;
+  project_id: number;
+  project_name: string;
+  todo_id?: number;
+  milestone_id?: number;
+  message: string;
+  remind_at: string; // UTC, "YYYY-MM-DD HH:MM:SS"
+  status: ReminderStatus;
+  fired_at?: string;
+  snooze_count: number;
+  created_at: string;
+}
+
+export interface CreateReminderDTO {
+  project_id: number;
+  todo_id?: number;
+  milestone_id?: number;
+  message: string;
+  // RFC 3339 o fecha local "YYYY-MM-DD HH:MM"
+  remind_at: string;
+}
+
+// Payload del evento 'reminder-fired'
+export interface ReminderFiredEvent {
+  reminder: Reminder;
+  missed: boolean; // Venció con la aplicación cerrada
+}
+
+// ==================== SALUD DE PROYECTOS ====================
+
+export type HealthLevel = 'good' | 'warning' | 'critical';
+
+export interface HealthFactor {
+  // Identificador estable (ej: 'uncommitted_changes', 'overdue_todos')
+  key: string;
+  description: string;
+  penalty: number;
+}
+
+export interface ProjectHealth {
+  project_id: number;
+  project_name: string;
+  score: number; // 0-100, mayor es mejor
+  level: HealthLevel;
+  factors: HealthFactor[]; // De mayor a menor penalización
+}
+
+// ==================== PROYECTOS INACTIVOS ====================
+
+// Origen de un enlace: project_links o un campo de URL del proyecto
+export type LinkSource = 'link' | 'documentation' | 'ai_documentation' | 'drive';
+
+export interface BrokenLink {
+  project_id: number;
+  project_name: string;
+  source: LinkSource;
+  link_id?: number; // Solo para source 'link'
+  title: string;
+  url: string;
+  check: LinkCheck;
+}
+
+export interface LinkCheckSummary {
+  checked: number;
+  broken: number;
+}
+
+export interface Bookmark {
+  title: string;
+  url: string;
+}
+
+export interface BookmarkFolder {
+  path: string[]; // Nombres desde la raíz; vacío para la raíz
+  bookmarks: Bookmark[]; // Solo los directos, sin los de subcarpetas
+}
+
+export interface BookmarkMapping {
+  folder: string[];
+  project_id: number;
+  include_subfolders?: boolean;
+}
+
+export interface BookmarkImportSummary {
+  imported: number;
+  duplicates: number; // Ya existían en el proyecto
+  skipped: number; // javascript:, place:...
+}
+
+export interface StaleProject {
+  project_id: number;
+  project_name: string;
+  local_path: string;
+  days_since_open: number;
+  never_opened: boolean;
+  days_since_commit?: number; // Sin valor si no es un repositorio git
 }